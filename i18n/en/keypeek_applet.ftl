@@ -5,3 +5,125 @@ welcome = Welcome to COSMIC! ✨
 page-id = Page { $num }
 git-description = Git commit {$hash} on {$date}
 example-row = Example Row
+repository = Repository
+loaded-shortcuts = Loaded from {$source}: {$count} shortcuts
+
+tour-progress = Step {$step} of {$total}
+tour-next = Next
+tour-done = Done
+tour-skip = Skip
+tour-dont-show-again = Don't show again
+tour-search-title = Search as you type
+tour-search-body = Start typing in the search field to narrow the list down to matching shortcut descriptions.
+tour-categories-title = Filter by category
+tour-categories-body = Use the category checkboxes to show only the groups of shortcuts you care about, such as workspaces or media keys.
+tour-live-title = Always up to date
+tour-live-body = Shortcuts are read from COSMIC Settings and refresh automatically whenever you change them there.
+defined-in = Defined in: {$sources}
+clear-search-history = Clear search history
+pull-to-reload = Pull down to reload
+release-to-reload = Release to reload
+saved-searches = Saved searches
+saved-search-name = Name of this search
+save-search = Save search
+
+export-description = Save the shortcuts currently shown, grouped by category, as an image for printing or sharing.
+export-png = Save as PNG
+export-svg = Save as SVG
+export-saved = Saved to {$path}
+export-failed = Export failed: {$error}
+print = Print…
+print-failed = Printing failed: {$error}
+copy-list = Copy list
+copy-list-markdown = Copy as Markdown
+share-custom = Share custom shortcuts
+share-custom-description = Scan this code on another machine running KeyPeek to copy your custom shortcuts.
+http-serving = Cheat sheet available at {$url}
+sheet-error = {$file}: {$message}
+check-sheets = Check custom sheets
+check-sheets-ok = All sheets are valid
+new-sheet = New sheet…
+new-sheet-failed = Could not create a sheet: {$error}
+import-sheet = Import
+import-sheet-confirm = Import the sheet “{$name}” with {$count} shortcuts?
+import-sheet-done = Imported to {$path}
+import-sheet-failed = Could not import the sheet: {$error}
+sheet-version = Version {$version}
+sheet-author = By {$author}
+sheet-homepage = Homepage
+cancel = Cancel
+dismiss = Dismiss
+community-enable = Browse community sheets
+community-browse = Community sheets…
+community-description = Cheat sheets for other applications, maintained by the community. Choose one to preview it.
+community-loading = Loading the sheet index…
+community-empty = No sheets are listed yet.
+community-failed = Could not load community sheets: {$error}
+community-install = Install sheet
+community-bundled = Included sheets
+community-shortcut-count = {$count} shortcuts
+check-updates = Check for updates
+notify-actions = Show a notification when a command is copied
+update-available = KeyPeek {$version} is available
+update-get = Get update
+update-notes = Release notes
+update-hide-notes = Hide release notes
+usage = Your usage
+usage-enable = Keep usage statistics
+usage-description = Counts searches and the shortcuts, categories and features you use most. They are stored on this computer only and are never sent anywhere.
+usage-searches = Searches: {$count}
+usage-shortcuts = Most viewed shortcuts
+usage-categories = Most filtered categories
+usage-features = Most used features
+usage-clear = Clear usage statistics
+spawn-group-other = Other commands
+group-by-category = By category
+group-by-modifiers = By modifiers
+no-modifiers = No modifiers
+executable-not-found = Executable not found: {$program}
+free-keys-description = Pick modifiers to see which keys are still free to use with them for a new custom shortcut.
+free-keys-count = {$count} free keys
+stats-summary = {$total} shortcuts: {$custom} custom, {$default} default
+stats-categories = Per category
+stats-modifiers = Most used modifiers
+stats-keys = Most used keys
+keyboard-description = Each key shows how many shortcuts use it, with any modifiers. Darker keys are used more often.
+profile-description = Save all shortcuts as a profile to compare them with another machine.
+profile-save = Save profile
+profile-compare = Compare with profile…
+compare-choose = Choose a KeyPeek profile
+compare-failed = Comparison failed: {$error}
+compare-current = This computer
+compare-profile = Profile
+compare-identical = The profile has the same shortcuts as this computer.
+timings = Startup timings
+panel-appearance = Panel
+panel-icon = Icon name or path, press Enter to apply
+panel-label = Label shown next to the icon
+default-changed = Was {$was} until {$until}
+detail-bindings = Bindings
+detail-source = Defined in
+detail-config-entry = Configuration entry
+detail-copy-keys = Copy keys
+detail-copy-command = Copy command
+command-copied = Command copied
+detail-copy-entry = Copy entry
+raw-config = Raw configuration
+raw-config-description = The COSMIC shortcut configuration as stored on disk. Your changes are applied on top of the system defaults.
+raw-config-empty = No shortcut configuration files were found.
+raw-config-system = System defaults
+raw-config-user = Your changes
+layer-default = Default
+layer-custom = Custom
+modifier-tap = Tap on its own
+hidden = Hidden
+hide-shortcut = Hide shortcut
+unhide-shortcut = Show shortcut
+hide-category = Hide {$category}
+unhide-category = Show {$category}
+show-hidden = Show hidden shortcuts ({$count})
+
+error-config-unavailable-remedy = KeyPeek reads the shortcuts of the COSMIC desktop. Make sure it runs in a COSMIC session.
+error-parse-failure-remedy = Its shortcuts are left out of the list. If COSMIC was updated recently, update KeyPeek as well; otherwise fix the file or reset your shortcuts in Settings › Keyboard › Keyboard shortcuts. To start over, move {$path} away.
+error-permission-denied-remedy = Make {$path} readable for your user, for example with chmod u+r.
+error-sandbox-blocked-remedy = Grant the Flatpak read access to the COSMIC configuration: flatpak override --user --filesystem=xdg-config/cosmic:ro io.github.l-const.keypeek
@@ -1,20 +1,257 @@
 // SPDX-License-Identifier: MIT
 
-use crate::config::Config;
-use crate::shortcuts::{KeyBinding, load_cosmic_shortcuts, ShortcutCategory};
+use crate::cache;
+use crate::command::{self, TokenKind};
+#[cfg(feature = "sheets")]
+use crate::community;
+use crate::compare::{self, Change};
+use crate::config::{Config, GroupBy, Layout, QuickFilter};
+use crate::conflicts;
+use crate::details;
+use crate::error::ShortcutsError;
+use crate::events::{self, Event};
+#[cfg(feature = "export")]
+use crate::export::ImageFormat;
+use crate::export::{self, ExportRow, ExportSection, TextFormat};
+use crate::fl;
+use crate::format;
+use crate::history;
+use crate::http;
+use crate::i18n;
+use crate::icons;
+use crate::keyboard;
+use crate::launcher;
+use crate::layers;
+use crate::link::Link;
+#[cfg(feature = "sheets")]
+use crate::net;
+use crate::notifications;
+#[cfg(feature = "export")]
+use crate::print;
+use crate::redact;
+use crate::search;
+#[cfg(feature = "sheets")]
+use crate::sheets::{self, SheetError};
+use crate::shortcuts::{
+    KeyBinding, ModifierFilter, Modifiers, ShortcutCategory, assign_spawn_groups, dedup_bindings,
+    format_key, format_keybind, free_keys, layer_parse_failure, load_cosmic_shortcuts,
+};
+use crate::state::State;
+use crate::stats::Stats;
+use crate::style;
+use crate::timings;
+use crate::usage::Usage;
+use cosmic::applet::cosmic_panel_config::PanelAnchor;
+use cosmic::cctk::wayland_protocols::xdg::shell::client::xdg_positioner::{
+    Anchor as PopupAnchor, Gravity,
+};
 use cosmic::cosmic_config::{self, CosmicConfigEntry};
-use std::collections::HashSet;
-use cosmic::iced::widget::svg;
-use cosmic::iced::{Limits, Subscription, window::Id};
+use cosmic::iced::event::PlatformSpecific;
+use cosmic::iced::event::wayland::{Event as WaylandEvent, OutputEvent};
+use cosmic::iced::keyboard::{Key, key::Named};
+use cosmic::iced::widget::scrollable::{self, RelativeOffset};
+use cosmic::iced::widget::{qr_code, svg};
+use cosmic::iced::{Limits, Point, Subscription, touch, window::Id};
 use cosmic::iced_winit::commands::popup::{destroy_popup, get_popup};
 use cosmic::prelude::*;
-use cosmic::widget;
-use futures_util::SinkExt;
-use notify::{RecursiveMode, Watcher};
+use cosmic::widget::{self, about::About, segmented_button};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::time::Duration;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
 
-const COSMIC_SHORTCUTS_DIR: &str = ".config/cosmic/com.system76.CosmicSettings.Shortcuts/";
+#[cfg(feature = "sheets")]
+mod custom_sheets;
+#[cfg(feature = "sheets")]
+use custom_sheets::{SheetImport, SheetMessage};
+#[cfg(feature = "overlay")]
+mod overlay;
+#[cfg(feature = "overlay")]
+use overlay::OverlayMessage;
+
+/// Maximum number of queries kept in the search history.
+const SEARCH_HISTORY_LIMIT: usize = 8;
+
+/// How long sections take to collapse or expand and the detail page to open.
+const ANIMATION_DURATION: Duration = Duration::from_millis(180);
+/// How long a row shows a checkmark after its command was copied.
+const CONFIRMATION_DURATION: Duration = Duration::from_millis(1200);
+/// Rough height of a row at standard density, for animating section heights.
+const ANIMATION_ROW_HEIGHT: f32 = 64.0;
+
+/// Descriptions longer than this are truncated in single-line popup rows.
+const ROW_MAX_DESCRIPTION_CHARS: usize = 56;
+
+/// Screens narrower than this, in logical pixels, get the touch layout.
+const TOUCH_SCREEN_WIDTH: i32 = 720;
+/// How far a finger has to move sideways across the list to switch category.
+const SWIPE_DISTANCE: f32 = 80.0;
+/// How far the list has to be pulled down from the top to reload shortcuts.
+const PULL_DISTANCE: f32 = 96.0;
+/// Padding of icon buttons in the touch layout, for a finger-sized target.
+const TOUCH_BUTTON_PADDING: u16 = 12;
+
+/// Rows assumed to fit in the list before its viewport has been reported.
+const LIST_VISIBLE_ITEMS_ESTIMATE: f32 = 8.0;
+
+/// Scrollable holding the shortcut list, so it can be scrolled programmatically.
+static LIST_ID: LazyLock<widget::Id> = LazyLock::new(|| widget::Id::new("shortcut-list"));
+
+const APP_ICON: &[u8] = include_bytes!("../resources/io.github.l-const.keypeek.svg");
+/// Size of the bitmap the panel icon is rendered to for light rendering, large
+/// enough for the biggest panel.
+const PANEL_ICON_BITMAP_SIZE: u32 = 128;
+
+/// The image drawn in the panel.
+#[derive(Debug, Clone)]
+enum PanelIcon {
+    /// Drawn as an SVG to preserve its colors.
+    Svg(svg::Handle),
+    /// A theme icon, a PNG, or an SVG rendered ahead of time for light rendering.
+    Image(widget::icon::Handle),
+}
+
+impl Default for PanelIcon {
+    fn default() -> Self {
+        Self::Svg(svg::Handle::from_memory(APP_ICON))
+    }
+}
+
+impl PanelIcon {
+    /// Loads the configured icon, falling back to the bundled one when it is
+    /// unset, missing from the icon theme or unreadable.
+    fn load(config: &Config) -> Self {
+        if let Some(name) = config.panel_icon_name() {
+            let named = widget::icon::from_name(name).prefer_svg(!config.light_rendering);
+            if named.clone().path().is_some() {
+                return Self::Image(named.handle());
+            }
+            log::warn!("Panel icon {} is not in the icon theme", name);
+        }
+
+        let custom = config
+            .panel_icon_path()
+            .and_then(|path| match std::fs::read(&path) {
+                Ok(data) => Some((path, data)),
+                Err(e) => {
+                    log::warn!("Failed to read panel icon {}: {}", path.display(), e);
+                    None
+                }
+            });
+        let (is_svg, data) = match &custom {
+            Some((path, data)) => {
+                let is_svg = path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"));
+                (is_svg, data.as_slice())
+            }
+            None => (true, APP_ICON),
+        };
+
+        if !is_svg {
+            return Self::Image(widget::icon::from_raster_bytes(data.to_vec()));
+        }
+        let bitmap = config
+            .light_rendering
+            .then(|| icons::rasterize_svg(data, PANEL_ICON_BITMAP_SIZE))
+            .flatten();
+        match bitmap {
+            Some(bitmap) => Self::Image(bitmap),
+            None => Self::Svg(svg::Handle::from_memory(data.to_vec())),
+        }
+    }
+}
+
+/// Pages that can be shown inside the popup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum PopupPage {
+    #[default]
+    Shortcuts,
+    About,
+    Onboarding,
+    Export,
+    FreeKeys,
+    Stats,
+    Keyboard,
+    Compare,
+    /// Everything about the shortcut in [`AppModel::detail`].
+    Detail,
+    /// The COSMIC shortcut configuration files, for debugging.
+    RawConfig,
+    /// Sheets from the community index, to preview and install.
+    #[cfg(feature = "sheets")]
+    Community,
+}
+
+/// A section that is collapsing or expanding.
+#[derive(Debug, Clone)]
+struct SectionAnimation {
+    section: Section,
+    started: Instant,
+    collapsing: bool,
+}
+
+/// A section of the shortcut list, depending on how the list is grouped.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Section {
+    Category(ShortcutCategory),
+    Modifiers(Modifiers),
+}
+
+impl Section {
+    fn label(&self) -> String {
+        match self {
+            Section::Category(category) => category.label().to_string(),
+            Section::Modifiers(modifiers) if *modifiers == Modifiers::new() => {
+                fl!("no-modifiers")
+            }
+            Section::Modifiers(modifiers) => format!("{} + …", modifiers),
+        }
+    }
+}
+
+/// Steps of the first-run tour, in display order.
+const TOUR_STEPS: &[(&str, &str)] = &[
+    ("tour-search-title", "tour-search-body"),
+    ("tour-categories-title", "tour-categories-body"),
+    ("tour-live-title", "tour-live-body"),
+];
+
+/// How the applet was started.
+#[derive(Debug, Clone, Default)]
+pub struct Flags {
+    /// Run as a regular window instead of in the panel, showing the popup
+    /// content directly.
+    pub standalone: bool,
+    /// Where the shortcuts come from.
+    pub provider: Provider,
+}
+
+/// Where the applet's shortcuts come from.
+#[derive(Debug, Clone, Default)]
+pub enum Provider {
+    /// The COSMIC settings and custom sheets, with the user's config and state.
+    #[default]
+    System,
+    /// Fixed bindings with the default config, reading and writing nothing on
+    /// disk; lets headless tests drive `update` and `view`.
+    Fixed(Vec<KeyBinding>),
+}
+
+impl Provider {
+    fn load(&self, config: &Config) -> Loaded {
+        match self {
+            Self::System => load_shortcuts(config),
+            Self::Fixed(shortcuts) => Loaded {
+                shortcuts: dedup_bindings(shortcuts.clone(), config.merge_policy),
+                #[cfg(feature = "sheets")]
+                sheet_errors: Vec::new(),
+                error: None,
+            },
+        }
+    }
+}
 
 /// The application model stores app-specific state used to describe its interface and
 /// drive its logic.
@@ -24,14 +261,128 @@ pub struct AppModel {
     core: cosmic::Core,
     /// The popup id.
     popup: Option<Id>,
+    /// Running as a regular window; see [`Flags::standalone`].
+    standalone: bool,
+    /// Where the shortcuts come from; see [`Flags::provider`].
+    provider: Provider,
     /// Configuration data that persists between application runs.
     config: Config,
+    /// Handle used to write configuration changes.
+    config_handler: Option<cosmic_config::Config>,
+    /// The panel icon, decoded once instead of on every redraw.
+    panel_icon: PanelIcon,
+    /// Runtime state as last saved, restored after a panel restart.
+    state: State,
+    state_handler: Option<cosmic_config::Config>,
+    /// Local usage statistics, recorded while `usage_stats` is on.
+    usage: Usage,
+    usage_handler: Option<cosmic_config::Config>,
 
     shortcuts: Vec<KeyBinding>,
     /// Search query for filtering shortcuts
     search_query: String,
     /// Selected categories for filtering shortcuts
     selected_categories: HashSet<ShortcutCategory>,
+    /// Page currently displayed in the popup.
+    page: PopupPage,
+    /// Contents of the about page.
+    about: About,
+    /// Current step of the first-run tour.
+    tour_step: usize,
+    /// Whether finishing the tour should keep it from showing again.
+    tour_dont_show_again: bool,
+    /// Relative vertical scroll offset of the shortcut list, from 0.0 to 1.0.
+    list_scroll: f32,
+    /// Fraction of the shortcut list that fits in the viewport, from 0.0 to 1.0.
+    list_visible: f32,
+    /// Category sections whose rows are currently hidden.
+    collapsed_sections: HashSet<Section>,
+    /// Modifiers every listed binding must use.
+    modifier_filters: HashSet<ModifierFilter>,
+    /// Name typed for a new saved search, while the saved search editor is open.
+    saved_search_name: Option<String>,
+    /// Modifiers to find unused keys for.
+    free_key_modifiers: Modifiers,
+    /// Segmented control choosing how the list is grouped.
+    group_by_model: segmented_button::SingleSelectModel,
+    /// Compiled `redaction_patterns` from the config.
+    redactions: Vec<Regex>,
+    /// Commands the user chose to reveal unredacted.
+    revealed_commands: HashSet<String>,
+    /// Bumped on every key event from the daemon to cancel pending peeks.
+    #[cfg(feature = "overlay")]
+    peek_generation: u64,
+    /// The overlay surface opened by holding Super.
+    overlay: Option<Id>,
+    /// Row under the mouse pointer, as an index into the visible rows.
+    hovered_row: Option<usize>,
+    /// Row selected by clicking or keyboard navigation.
+    selected_row: Option<usize>,
+    /// Logical width of the screen, once the compositor has reported it.
+    screen_width: Option<i32>,
+    /// Where the finger touching the list went down, to recognize swipes.
+    touch_start: Option<Point>,
+    /// How far the list is pulled down past its top, for pull to refresh.
+    pull_distance: f32,
+    /// Current size of the overlay surface, once the compositor has configured it.
+    #[cfg(feature = "overlay")]
+    overlay_size: Option<cosmic::iced::Size>,
+    /// Outcome of the last export, shown on the export page.
+    export_status: Option<Result<PathBuf, String>>,
+    /// Error from the last print attempt, shown on the export page.
+    print_error: Option<String>,
+    /// QR code with the custom shortcuts, built when the export page opens.
+    share_qr: Option<qr_code::Data>,
+    /// Differences with the last profile chosen for comparison.
+    profile_changes: Vec<Change>,
+    compare_error: Option<String>,
+    /// HTML cheat sheet served by the HTTP server.
+    http_page: http::SharedPage,
+    /// Custom sheets that failed to load.
+    #[cfg(feature = "sheets")]
+    sheet_errors: Vec<SheetError>,
+    /// Why the COSMIC shortcuts couldn't be loaded, if they couldn't.
+    load_error: Option<ShortcutsError>,
+    /// Problems found by the last sheet check, if one was run.
+    #[cfg(feature = "sheets")]
+    sheet_check: Option<Vec<SheetError>>,
+    #[cfg(feature = "sheets")]
+    new_sheet_error: Option<String>,
+    /// Dropped sheet, shown above the current page until dismissed.
+    #[cfg(feature = "sheets")]
+    sheet_import: Option<SheetImport>,
+    /// Community sheet index, fetched when its page opens; `None` while loading.
+    #[cfg(feature = "sheets")]
+    community_index: Option<Result<Vec<community::Entry>, String>>,
+    /// Curated sheets built into the binary, read when the page opens.
+    #[cfg(feature = "sheets")]
+    bundled_sheets: Vec<community::Sheet>,
+    /// Sheet being previewed, or the error downloading it.
+    #[cfg(feature = "sheets")]
+    community_preview: Option<Result<community::Sheet, String>>,
+    /// Where the previewed sheet was installed, or why it failed.
+    #[cfg(feature = "sheets")]
+    community_status: Option<Result<PathBuf, String>>,
+    /// Newer release found by the update check.
+    #[cfg(feature = "sheets")]
+    update: Option<net::Release>,
+    #[cfg(feature = "sheets")]
+    show_release_notes: bool,
+    /// Panel icon being edited on the about page, saved on submit.
+    panel_icon_input: String,
+    /// Shortcut shown on the detail page.
+    detail: Option<KeyBinding>,
+    /// Files shown on the raw configuration page, read when it opens.
+    raw_files: Vec<layers::RawFile>,
+    /// Section being animated; collapsing sections are only marked collapsed
+    /// once the animation ends.
+    section_animation: Option<SectionAnimation>,
+    /// When the detail page started opening, while it is animated.
+    detail_opened: Option<Instant>,
+    /// Command just copied and when, for the checkmark confirming it.
+    copied: Option<(String, Instant)>,
+    /// Time of the last animation frame.
+    frame_time: Option<Instant>,
 }
 
 /// Messages emitted by the application and its widgets.
@@ -40,10 +391,74 @@ pub enum Message {
     UpdateShortcuts,
     TogglePopup,
     PopupClosed(Id),
-    SubscriptionChannel,
+    OpenLink(Link),
     UpdateConfig(Config),
     SearchInput(String),
     ToggleCategory(ShortcutCategory),
+    ShowPage(PopupPage),
+    OpenUrl(String),
+    TourNext,
+    TourFinish,
+    TourDontShowAgain(bool),
+    ListScrolled(f32, f32),
+    PanelIconInput(String),
+    SavePanelIcon,
+    PanelLabelInput(String),
+    ToggleSection(Section),
+    ToggleModifierFilter(ModifierFilter),
+    ApplyQuickFilter(usize),
+    ApplySavedSearch(usize),
+    EditSavedSearches(bool),
+    SavedSearchNameInput(String),
+    SaveSearch,
+    MoveSavedSearch(usize, usize),
+    RemoveSavedSearch(usize),
+    ToggleFreeKeyModifier(ModifierFilter),
+    GroupBySelected(segmented_button::Entity),
+    CopyCommand(String),
+    NotificationSent(Result<(), String>),
+    NotifyActions(bool),
+    CopyText(String),
+    CopyList(TextFormat),
+    OpenDetail(usize),
+    ToggleHiddenShortcut(String),
+    ToggleHiddenCategory(ShortcutCategory),
+    ShowHidden(bool),
+    RevealCommand(String),
+    ClearSearchHistory,
+    #[cfg(feature = "overlay")]
+    Overlay(OverlayMessage),
+    HoverRow(Option<usize>),
+    ScreenWidth(i32),
+    TouchPressed(Point),
+    TouchMoved(Point),
+    TouchLifted(Point),
+    TouchLost,
+    SelectNext,
+    SelectPrevious,
+    AnimationFrame(Instant),
+    #[cfg(feature = "export")]
+    ExportImage(ImageFormat),
+    ExportFinished(Result<PathBuf, String>),
+    #[cfg(feature = "export")]
+    Print,
+    ExportProfile,
+    CompareProfile,
+    ProfileLoaded(Result<format::Document, String>),
+    PrintFinished(Result<(), String>),
+    HttpServerError(String),
+    #[cfg(feature = "sheets")]
+    Sheets(SheetMessage),
+    #[cfg(feature = "sheets")]
+    CheckUpdates(bool),
+    #[cfg(feature = "sheets")]
+    UpdateChecked(Result<Option<net::Release>, String>),
+    UsageStats(bool),
+    ClearUsage,
+    #[cfg(feature = "sheets")]
+    ToggleReleaseNotes,
+    #[cfg(feature = "sheets")]
+    DismissUpdate,
 }
 
 /// Create a COSMIC application from the app model
@@ -52,13 +467,13 @@ impl cosmic::Application for AppModel {
     type Executor = cosmic::executor::Default;
 
     /// Data that your application receives to its init method.
-    type Flags = ();
+    type Flags = Flags;
 
     /// Messages which the application and its widgets will emit.
     type Message = Message;
 
     /// Unique identifier in RDNN (reverse domain name notation) format.
-    const APP_ID: &'static str = "io.github.l-const.keypeek";
+    const APP_ID: &'static str = launcher::APP_ID;
 
     fn core(&self) -> &cosmic::Core {
         &self.core
@@ -69,39 +484,80 @@ impl cosmic::Application for AppModel {
     }
 
     /// Initializes the application with any given flags and startup commands.
-    fn init(
-        core: cosmic::Core,
-        _flags: Self::Flags,
-    ) -> (Self, Task<cosmic::Action<Self::Message>>) {
-        // Load cosmic shortcuts
-        let shortcuts = load_cosmic_shortcuts().unwrap_or_else(|e| {
-            log::error!("Failed to load cosmic shortcuts: {}", e);
-            Vec::new()
-        });
+    fn init(core: cosmic::Core, flags: Self::Flags) -> (Self, Task<cosmic::Action<Self::Message>>) {
+        let about = About::default()
+            .name(fl!("app-title"))
+            .icon(widget::icon::from_svg_bytes(APP_ICON))
+            .version(env!("CARGO_PKG_VERSION"))
+            .license(env!("CARGO_PKG_LICENSE"))
+            .links([(fl!("repository"), env!("CARGO_PKG_REPOSITORY"))]);
+
+        let isolated = matches!(flags.provider, Provider::Fixed(_));
+        let (config_handler, config) = if isolated {
+            (None, Config::default())
+        } else {
+            Config::load(Self::APP_ID)
+        };
+
+        // Greet first-time users with the tour until they dismiss it for good.
+        let page = if config.onboarding_complete {
+            PopupPage::Shortcuts
+        } else {
+            PopupPage::Onboarding
+        };
+
+        let redactions = redact::compile_patterns(&config.redaction_patterns);
+        let ((state_handler, state), (usage_handler, usage)) = if isolated {
+            ((None, State::default()), (None, Usage::default()))
+        } else {
+            (State::load(Self::APP_ID), Usage::load(Self::APP_ID))
+        };
 
         // Construct the app model with the runtime's core.
-        let app = AppModel {
+        let mut app = AppModel {
             core,
-            config: cosmic_config::Config::new(Self::APP_ID, Config::VERSION)
-                .map(|context| match Config::get_entry(&context) {
-                    Ok(config) => config,
-                    Err((_errors, config)) => {
-                        // for why in errors {
-                        //     tracing::error!(%why, "error loading app config");
-                        // }
-
-                        config
-                    }
-                })
-                .unwrap_or_default(),
-            shortcuts,
+            standalone: flags.standalone,
+            provider: flags.provider,
+            config,
+            redactions,
+            config_handler,
+            state_handler,
+            usage,
+            usage_handler,
+            page,
+            tour_dont_show_again: true,
             search_query: String::new(),
             // Initialize with all categories selected by default
             selected_categories: ShortcutCategory::all().iter().copied().collect(),
+            about,
+            free_key_modifiers: Modifiers {
+                logo: true,
+                ..Modifiers::new()
+            },
+            group_by_model: segmented_button::ModelBuilder::default()
+                .insert(|b| b.text(fl!("group-by-category")).data(GroupBy::Category))
+                .insert(|b| b.text(fl!("group-by-modifiers")).data(GroupBy::Modifiers))
+                .build(),
             ..Default::default()
         };
+        app.reload_shortcuts();
+        app.panel_icon = PanelIcon::load(&app.config);
+        app.panel_icon_input = app.config.panel_icon.clone();
+        app.sync_group_by_model();
+        app.sync_collapsed_categories();
+        app.restore_state(state);
+        app.ensure_http_token();
+        app.refresh_http_page();
 
-        (app, Task::none())
+        #[cfg(feature = "sheets")]
+        let task = if app.config.check_updates {
+            app.check_for_update()
+        } else {
+            Task::none()
+        };
+        #[cfg(not(feature = "sheets"))]
+        let task = Task::none();
+        (app, task)
     }
 
     fn on_close_requested(&self, id: Id) -> Option<Message> {
@@ -114,13 +570,50 @@ impl cosmic::Application for AppModel {
     /// This view should emit messages to toggle the applet's popup window, which will
     /// be drawn using the `view_window` method.
     fn view(&self) -> Element<'_, Self::Message> {
-        // Embed SVG directly to preserve colors
-        let svg_data = include_bytes!("../resources/io.github.l-const.keypeek.svg");
-        let svg_handle = svg::Handle::from_memory(svg_data);
+        timings::record_once("first view");
+
+        if self.standalone {
+            return self.view_page();
+        }
+
+        // Size the icon and padding for the panel, so it neither overflows small
+        // panels nor looks tiny on large docks. Both follow panel size changes.
+        let applet = &self.core.applet;
+        let (width, height) = applet.suggested_size(false);
+        let (width, height) = (
+            cosmic::iced::Length::Fixed(width.into()),
+            cosmic::iced::Length::Fixed(height.into()),
+        );
+        let (major_padding, minor_padding) = applet.suggested_padding(false);
+        let (horizontal_padding, vertical_padding) = if applet.is_horizontal() {
+            (major_padding, minor_padding)
+        } else {
+            (minor_padding, major_padding)
+        };
+
+        let icon: Element<'_, Message> = match &self.panel_icon {
+            PanelIcon::Svg(handle) => svg(handle.clone()).width(width).height(height).into(),
+            PanelIcon::Image(handle) => widget::icon(handle.clone())
+                .width(width)
+                .height(height)
+                .into(),
+        };
 
-        let icon_svg = svg(svg_handle);
+        // Vertical panels are too narrow for a label
+        let label = self.config.panel_label.trim();
+        let show_label = !label.is_empty() && applet.is_horizontal();
+        let content: Element<'_, Message> = if !show_label {
+            icon
+        } else {
+            widget::row::with_children(vec![icon, applet.text(label).into()])
+                .spacing(4)
+                .align_y(cosmic::iced::Alignment::Center)
+                .into()
+        };
 
-        widget::button::custom(icon_svg)
+        widget::button::custom(content)
+            .padding([vertical_padding, horizontal_padding])
+            .class(cosmic::theme::Button::AppletIcon)
             .on_press(Message::TogglePopup)
             .into()
     }
@@ -128,90 +621,17 @@ impl cosmic::Application for AppModel {
     /// The applet's popup window will be drawn using this view method. If there are
     /// multiple poups, you may match the id parameter to determine which popup to
     /// create a view for.
-    fn view_window(&self, _id: Id) -> Element<'_, Self::Message> {
-        // Search input at the top with container to avoid edge artifacts
-        let search_input = widget::container(
-            widget::text_input("Search shortcuts...", &self.search_query)
-                .on_input(Message::SearchInput)
-                .padding(8),
-        )
-        .padding([8, 12]);
-
-        // Category filter checkboxes with wrapping
-        let mut category_checkboxes = Vec::new();
-
-        for category in ShortcutCategory::all() {
-            let is_checked = self.selected_categories.contains(category);
-            let checkbox = widget::checkbox(category.label(), is_checked)
-                .on_toggle(move |_| Message::ToggleCategory(*category));
-            category_checkboxes.push(checkbox.into());
-        }
-
-        let category_row = widget::row::with_children(category_checkboxes)
-            .spacing(8)
-            .padding([8, 12, 4, 12])
-            .wrap();
-
-        let category_filter = widget::container(category_row)
-            .width(cosmic::iced::Length::Fill);
-
-        let mut content_list = widget::list_column().padding(5).spacing(0);
-
-        // Filter shortcuts based on search query and selected categories
-        let filtered_shortcuts: Vec<&KeyBinding> = self
-            .shortcuts
-            .iter()
-            .filter(|shortcut| {
-                // Filter by search query
-                let matches_search = if self.search_query.is_empty() {
-                    true
-                } else {
-                    shortcut
-                        .description
-                        .to_lowercase()
-                        .contains(&self.search_query.to_lowercase())
-                };
-
-                // Filter by selected categories
-                let matches_category = self.selected_categories.is_empty()
-                    || self.selected_categories.contains(&shortcut.category);
-
-                matches_search && matches_category
-            })
-            .collect();
-
-        // Add each shortcut as a column with binding in bold and description in normal text
-        for shortcut in filtered_shortcuts {
-            // Create a column with binding (bold) on top and description (normal wrapped) below
-            let shortcut_item = widget::column::with_children(vec![
-                widget::text::body(shortcut.to_string())
-                    .font(cosmic::iced_core::Font {
-                        weight: cosmic::iced_core::font::Weight::Bold,
-                        ..Default::default()
-                    })
-                    .into(),
-                widget::text::body(&shortcut.description)
-                    .wrapping(cosmic::iced::widget::text::Wrapping::Word)
-                    .into(),
-            ])
-            .spacing(4)
-            .padding([8, 12]);
-
-            content_list = content_list.add(shortcut_item);
+    fn view_window(&self, id: Id) -> Element<'_, Self::Message> {
+        // The overlay always shows the list and isn't attached to the panel
+        #[cfg(feature = "overlay")]
+        if self.overlay == Some(id) {
+            return widget::container(self.view_overlay())
+                .class(cosmic::theme::Container::Background)
+                .padding(8)
+                .into();
         }
 
-        // Wrap in scrollable to show all shortcuts
-        let scrollable_content = widget::scrollable(content_list);
-
-        // Combine search input, category filter, and scrollable content in a column
-        let popup_content = widget::column::with_children(vec![
-            search_input.into(),
-            category_filter.into(),
-            scrollable_content.into(),
-        ])
-        .spacing(0);
-
-        self.core.applet.popup_container(popup_content).into()
+        self.core.applet.popup_container(self.view_page()).into()
     }
 
     /// Register subscriptions for this application.
@@ -221,61 +641,125 @@ impl cosmic::Application for AppModel {
     /// activated by selectively appending to the subscription batch, and will
     /// continue to execute for the duration that they remain in the batch.
     fn subscription(&self) -> Subscription<Self::Message> {
-        struct MySubscription;
+        let mut subscriptions = vec![
+            // Config, file and session changes, links and the daemon, through
+            // one supervised event bus. Links, hold to peek and the HTTP
+            // server are left to the panel instance.
+            events::subscription(events::Sources {
+                #[cfg(feature = "sheets")]
+                sheets_dir: self.config.sheets_dir(),
+                links: !self.standalone,
+                #[cfg(feature = "overlay")]
+                daemon: self.config.hold_to_peek && !self.standalone,
+            })
+            .map(|event| match event {
+                Event::Config(config) => Message::UpdateConfig(config),
+                Event::ShortcutsChanged => Message::UpdateShortcuts,
+                // Catch changes made while suspended or from another session.
+                #[cfg(feature = "dbus")]
+                Event::Resumed(_) => Message::UpdateShortcuts,
+                Event::Link(link) => Message::OpenLink(link),
+                #[cfg(feature = "overlay")]
+                Event::Daemon(event) => Message::Overlay(OverlayMessage::Daemon(event)),
+            }),
+        ];
 
-        Subscription::batch(vec![
-            // Create a subscription which emits updates through a channel.
-            Subscription::run_with_id(
-                std::any::TypeId::of::<MySubscription>(),
-                cosmic::iced::stream::channel(4, move |mut channel| async move {
-                    _ = channel.send(Message::SubscriptionChannel).await;
+        // Sheets dropped onto the popup or window can be imported.
+        #[cfg(feature = "sheets")]
+        if self.list_open() && self.config.allow_editing {
+            subscriptions.push(cosmic::iced::event::listen_with(
+                |event, _status, _id| match event {
+                    cosmic::iced::Event::Window(cosmic::iced::window::Event::FileDropped(path)) => {
+                        Some(Message::Sheets(SheetMessage::FileDropped(path)))
+                    }
+                    _ => None,
+                },
+            ));
+        }
 
-                    futures_util::future::pending().await
-                }),
-            ),
-            // Watch for application configuration changes.
-            self.core()
-                .watch_config::<Config>(Self::APP_ID)
-                .map(|update| {
-                    // for why in update.errors {
-                    //     tracing::error!(?why, "app config error");
-                    // }
-
-                    Message::UpdateConfig(update.config)
-                }),
-            // Watch for changes in the cosmic shortcuts directory.
-            Subscription::run_with_id(
-                std::any::TypeId::of::<PathBuf>(),
-                cosmic::iced::stream::channel(4, move |mut channel| async move {
-                    let home = std::env::var("HOME").unwrap_or_else(|_| String::from("/home"));
-                    let shortcuts_path = PathBuf::from(&home).join(COSMIC_SHORTCUTS_DIR);
-
-                    let (tx, mut rx) = tokio::sync::mpsc::channel(100);
-
-                    let mut watcher = notify::RecommendedWatcher::new(
-                        move |res: Result<notify::Event, notify::Error>| {
-                            if let Ok(event) = res {
-                                if event.kind.is_modify() || event.kind.is_create() {
-                                    let _ = tx.blocking_send(());
-                                }
-                            }
-                        },
-                        notify::Config::default().with_poll_interval(Duration::from_millis(100)),
-                    )
-                    .ok();
+        // Arrow keys move the row selection while the popup is open.
+        if self.list_open() {
+            subscriptions.push(cosmic::iced::keyboard::on_key_press(
+                |key, _modifiers| match key {
+                    Key::Named(Named::ArrowDown) => Some(Message::SelectNext),
+                    Key::Named(Named::ArrowUp) => Some(Message::SelectPrevious),
+                    _ => None,
+                },
+            ));
+        }
 
-                    if let Some(ref mut w) = watcher {
-                        let _ = w.watch(&shortcuts_path, RecursiveMode::Recursive);
-                    }
+        // The touch layout is chosen by the size of the screen.
+        if self.config.layout == Layout::Auto {
+            subscriptions.push(cosmic::iced::event::listen_with(
+                |event, _status, _id| match event {
+                    cosmic::iced::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                        WaylandEvent::Output(
+                            OutputEvent::Created(Some(info)) | OutputEvent::InfoUpdate(info),
+                            _,
+                        ),
+                    )) => info
+                        .logical_size
+                        .map(|(width, _)| Message::ScreenWidth(width)),
+                    _ => None,
+                },
+            ));
+        }
 
-                    while let Some(_) = rx.recv().await {
-                        let _ = channel.send(Message::UpdateShortcuts).await;
+        // Touchscreen gestures: swiping sideways across the list switches
+        // between categories and pulling it down from the top reloads it.
+        if self.list_open() {
+            subscriptions.push(cosmic::iced::event::listen_with(|event, _status, _id| {
+                let cosmic::iced::Event::Touch(event) = event else {
+                    return None;
+                };
+                match event {
+                    touch::Event::FingerPressed { position, .. } => {
+                        Some(Message::TouchPressed(position))
                     }
+                    touch::Event::FingerMoved { position, .. } => {
+                        Some(Message::TouchMoved(position))
+                    }
+                    touch::Event::FingerLifted { position, .. } => {
+                        Some(Message::TouchLifted(position))
+                    }
+                    touch::Event::FingerLost { .. } => Some(Message::TouchLost),
+                }
+            }));
+        }
 
-                    futures_util::future::pending().await
-                }),
-            ),
-        ])
+        // Optional read-only cheat sheet for other devices.
+        if self.config.http_server && !self.config.http_token.is_empty() && !self.standalone {
+            let ip = if self.config.http_lan {
+                std::net::Ipv4Addr::UNSPECIFIED
+            } else {
+                std::net::Ipv4Addr::LOCALHOST
+            };
+            subscriptions.push(
+                http::subscription(
+                    (ip, self.config.http_port).into(),
+                    self.config.http_token.clone(),
+                    self.http_page.clone(),
+                )
+                .map(Message::HttpServerError),
+            );
+        }
+
+        // Redraw every frame while a section or the detail page is animated.
+        if self.section_animation.is_some() || self.detail_opened.is_some() || self.copied.is_some()
+        {
+            subscriptions.push(cosmic::iced::window::frames().map(Message::AnimationFrame));
+        }
+
+        // The overlay grid adapts its column count to the surface size.
+        #[cfg(feature = "overlay")]
+        if self.overlay.is_some() {
+            subscriptions.push(
+                cosmic::iced::window::resize_events()
+                    .map(|(id, size)| Message::Overlay(OverlayMessage::Resized(id, size))),
+            );
+        }
+
+        Subscription::batch(subscriptions)
     }
 
     /// Handles messages emitted by the application and its widgets.
@@ -285,56 +769,3184 @@ impl cosmic::Application for AppModel {
     /// tasks are finished.
     fn update(&mut self, message: Self::Message) -> Task<cosmic::Action<Self::Message>> {
         match message {
+            // Read-only deployments can't change files or settings.
+            Message::ExportProfile
+            | Message::PanelIconInput(_)
+            | Message::SavePanelIcon
+            | Message::PanelLabelInput(_)
+            | Message::ToggleHiddenShortcut(_)
+            | Message::ToggleHiddenCategory(_)
+            | Message::EditSavedSearches(_)
+            | Message::SavedSearchNameInput(_)
+            | Message::SaveSearch
+            | Message::MoveSavedSearch(..)
+            | Message::RemoveSavedSearch(_)
+            | Message::ClearSearchHistory
+            | Message::UsageStats(_)
+            | Message::ClearUsage
+            | Message::NotifyActions(_)
+                if !self.config.allow_editing => {}
+            #[cfg(feature = "export")]
+            Message::ExportImage(_) if !self.config.allow_editing => {}
+            #[cfg(feature = "sheets")]
+            Message::CheckUpdates(_) if !self.config.allow_editing => {}
             Message::UpdateShortcuts => {
-                self.shortcuts = load_cosmic_shortcuts().unwrap();
-            }
-            Message::SubscriptionChannel => {
-                // For example purposes only.
+                self.reload_shortcuts();
             }
             Message::UpdateConfig(config) => {
+                if config.redaction_patterns != self.config.redaction_patterns {
+                    self.redactions = redact::compile_patterns(&config.redaction_patterns);
+                }
+                let reload_icon = config.panel_icon != self.config.panel_icon
+                    || config.light_rendering != self.config.light_rendering;
+                let reload = config.merge_policy != self.config.merge_policy
+                    || config.primary_binding != self.config.primary_binding
+                    || config.spawn_groups != self.config.spawn_groups
+                    || config.sync_dir != self.config.sync_dir
+                    || config.low_memory != self.config.low_memory
+                    || config.http_server != self.config.http_server;
                 self.config = config;
+                if reload {
+                    self.reload_shortcuts();
+                    self.release_memory();
+                }
+                if reload_icon {
+                    self.panel_icon = PanelIcon::load(&self.config);
+                    self.panel_icon_input = self.config.panel_icon.clone();
+                }
+                self.sync_group_by_model();
+                self.sync_collapsed_categories();
+                self.ensure_http_token();
+                self.refresh_http_page();
             }
             Message::SearchInput(query) => {
+                if self.search_query.is_empty() && !query.is_empty() {
+                    self.record_usage(|usage| usage.searches += 1);
+                }
                 self.search_query = query;
+                self.selected_row = None;
+                self.hovered_row = None;
+                // The first results are the most relevant, so show them.
+                return scrollable::snap_to(LIST_ID.clone(), RelativeOffset::START);
             }
-            Message::ToggleCategory(category) => {
-                if self.selected_categories.contains(&category) {
-                    self.selected_categories.remove(&category);
+            #[cfg(feature = "export")]
+            Message::ExportImage(format) => {
+                self.record_feature("export-image");
+                let title = fl!("app-title");
+                let sections = self.export_sections(&self.filtered_shortcuts());
+                self.export_status = None;
+                return Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            export::save_image(&title, &sections, format)
+                                .map_err(|e| format!("{:#}", e))
+                        })
+                        .await
+                        .unwrap_or_else(|e| Err(e.to_string()))
+                    },
+                    |result| cosmic::Action::App(Message::ExportFinished(result)),
+                );
+            }
+            Message::ExportProfile => {
+                let document = self.export_document(self.shortcuts.iter());
+                self.export_status = Some(
+                    export::save_profile(&document, self.config.profiles_dir())
+                        .map_err(|e| format!("{:#}", e)),
+                );
+            }
+            Message::CompareProfile => {
+                self.compare_error = None;
+                return Task::perform(compare::open_profile(fl!("compare-choose")), |result| {
+                    cosmic::Action::App(Message::ProfileLoaded(
+                        result.map_err(|e| format!("{:#}", e)),
+                    ))
+                });
+            }
+            Message::ProfileLoaded(result) => match result {
+                Ok(profile) => {
+                    let current = self.export_document(self.shortcuts.iter());
+                    self.profile_changes = compare::diff(&current, &profile);
+                    self.page = PopupPage::Compare;
+                }
+                Err(e) => {
+                    log::error!("Failed to load profile: {}", e);
+                    self.compare_error = Some(e);
+                }
+            },
+            Message::ExportFinished(result) => {
+                if let Err(e) = &result {
+                    log::error!("Export failed: {}", e);
+                }
+                self.export_status = Some(result);
+            }
+            #[cfg(feature = "export")]
+            Message::Print => {
+                self.record_feature("print");
+                let title = fl!("app-title");
+                let sections = self.export_sections(&self.filtered_shortcuts());
+                self.print_error = None;
+                return Task::perform(print::print(title, sections), |result| {
+                    cosmic::Action::App(Message::PrintFinished(
+                        result.map_err(|e| format!("{:#}", e)),
+                    ))
+                });
+            }
+            Message::PrintFinished(result) => {
+                if let Err(e) = result {
+                    log::error!("Printing failed: {}", e);
+                    self.print_error = Some(e);
+                }
+            }
+            Message::HttpServerError(e) => {
+                log::error!("HTTP server stopped: {}", e);
+            }
+            #[cfg(feature = "sheets")]
+            Message::Sheets(message) => return self.update_sheets(message),
+            Message::NotifyActions(enabled) => match &self.config_handler {
+                Some(handler) => {
+                    if let Err(e) = self.config.set_notify_actions(handler, enabled) {
+                        log::error!("Failed to save notification setting: {}", e);
+                    }
+                }
+                None => self.config.notify_actions = enabled,
+            },
+            #[cfg(feature = "sheets")]
+            Message::CheckUpdates(enabled) => {
+                match &self.config_handler {
+                    Some(handler) => {
+                        if let Err(e) = self.config.set_check_updates(handler, enabled) {
+                            log::error!("Failed to save update check setting: {}", e);
+                        }
+                    }
+                    None => self.config.check_updates = enabled,
+                }
+                if enabled {
+                    return self.check_for_update();
+                }
+                self.update = None;
+            }
+            #[cfg(feature = "sheets")]
+            Message::UpdateChecked(result) => match result {
+                Ok(release) => {
+                    self.update =
+                        release.filter(|release| release.version != self.config.dismissed_release);
+                }
+                Err(e) => log::warn!("Update check failed: {}", e),
+            },
+            #[cfg(feature = "sheets")]
+            Message::ToggleReleaseNotes => {
+                self.show_release_notes = !self.show_release_notes;
+            }
+            #[cfg(feature = "sheets")]
+            Message::DismissUpdate => {
+                if let Some(release) = self.update.take() {
+                    match &self.config_handler {
+                        Some(handler) => {
+                            if let Err(e) =
+                                self.config.set_dismissed_release(handler, release.version)
+                            {
+                                log::error!("Failed to save dismissed release: {}", e);
+                            }
+                        }
+                        None => self.config.dismissed_release = release.version,
+                    }
+                }
+            }
+            Message::UsageStats(enabled) => match &self.config_handler {
+                Some(handler) => {
+                    if let Err(e) = self.config.set_usage_stats(handler, enabled) {
+                        log::error!("Failed to save usage statistics setting: {}", e);
+                    }
+                }
+                None => self.config.usage_stats = enabled,
+            },
+            Message::ClearUsage => {
+                self.usage = Usage::default();
+                if let Some(handler) = &self.usage_handler {
+                    if let Err(e) = self.usage.write_entry(handler) {
+                        log::error!("Failed to clear usage statistics: {}", e);
+                    }
+                }
+            }
+            Message::HoverRow(index) => {
+                self.hovered_row = index;
+            }
+            Message::ScreenWidth(width) => {
+                self.screen_width = Some(width);
+            }
+            Message::TouchPressed(position) => {
+                if self.page == PopupPage::Shortcuts {
+                    self.touch_start = Some(position);
+                }
+            }
+            Message::TouchMoved(position) => {
+                // Only a list already at its top can be pulled
+                if let Some(start) = self.touch_start {
+                    self.pull_distance = if self.list_scroll <= 0.0 {
+                        (position.y - start.y).max(0.0)
+                    } else {
+                        0.0
+                    };
+                }
+            }
+            Message::TouchLifted(position) => {
+                let pulled = std::mem::take(&mut self.pull_distance) > PULL_DISTANCE;
+                let Some(start) = self.touch_start.take() else {
+                    return Task::none();
+                };
+                let (dx, dy) = (position.x - start.x, position.y - start.y);
+                // Mostly sideways, so scrolling the list isn't taken for a swipe
+                if dx.abs() > SWIPE_DISTANCE && dx.abs() > 2.0 * dy.abs() {
+                    self.swipe_category(dx < 0.0);
+                    return scrollable::snap_to(LIST_ID.clone(), RelativeOffset::START);
+                }
+                if pulled && dy > 2.0 * dx.abs() {
+                    self.record_feature("pull-to-refresh");
+                    self.reload_shortcuts();
+                }
+            }
+            Message::TouchLost => {
+                self.touch_start = None;
+                self.pull_distance = 0.0;
+            }
+            Message::OpenDetail(index) => {
+                self.selected_row = Some(index);
+                let detail = self
+                    .visible_sections()
+                    .into_iter()
+                    .flat_map(|(_, rows)| rows)
+                    .nth(index)
+                    .cloned();
+                if let Some(shortcut) = &detail {
+                    let description = shortcut.description.clone();
+                    self.record_usage(|usage| {
+                        *usage.shortcut_views.entry(description).or_default() += 1;
+                    });
+                }
+                self.detail = detail;
+                if self.detail.is_some() {
+                    self.page = PopupPage::Detail;
+                    if !self.config.reduce_motion {
+                        let started = Instant::now();
+                        self.frame_time = Some(started);
+                        self.detail_opened = Some(started);
+                    }
+                }
+            }
+            Message::SelectNext => {
+                let count = self.visible_row_count();
+                if count > 0 {
+                    self.selected_row = Some(match self.selected_row {
+                        Some(index) => (index + 1).min(count - 1),
+                        None => 0,
+                    });
+                    return self.scroll_to_selected();
+                }
+            }
+            Message::SelectPrevious => {
+                self.selected_row = self.selected_row.map(|index| index.saturating_sub(1));
+                return self.scroll_to_selected();
+            }
+            Message::ListScrolled(offset, visible) => {
+                self.list_scroll = offset;
+                self.list_visible = visible;
+            }
+            Message::PanelIconInput(icon) => {
+                self.panel_icon_input = icon;
+            }
+            Message::SavePanelIcon => {
+                let icon = self.panel_icon_input.trim().to_string();
+                match &self.config_handler {
+                    Some(handler) => {
+                        if let Err(e) = self.config.set_panel_icon(handler, icon) {
+                            log::error!("Failed to save panel icon: {}", e);
+                        }
+                    }
+                    None => self.config.panel_icon = icon,
+                }
+                self.panel_icon = PanelIcon::load(&self.config);
+            }
+            Message::PanelLabelInput(label) => match &self.config_handler {
+                Some(handler) => {
+                    if let Err(e) = self.config.set_panel_label(handler, label) {
+                        log::error!("Failed to save panel label: {}", e);
+                    }
+                }
+                None => self.config.panel_label = label,
+            },
+            Message::GroupBySelected(entity) => {
+                self.group_by_model.activate(entity);
+                if let Some(group_by) = self.group_by_model.data::<GroupBy>(entity).copied() {
+                    self.selected_row = None;
+                    match &self.config_handler {
+                        Some(handler) => {
+                            if let Err(e) = self.config.set_group_by(handler, group_by) {
+                                log::error!("Failed to save grouping: {}", e);
+                            }
+                        }
+                        None => self.config.group_by = group_by,
+                    }
+                }
+            }
+            Message::ToggleModifierFilter(filter) => {
+                if !self.modifier_filters.remove(&filter) {
+                    self.modifier_filters.insert(filter);
+                }
+                self.selected_row = None;
+                self.hovered_row = None;
+            }
+            Message::ToggleFreeKeyModifier(filter) => {
+                let m = &mut self.free_key_modifiers;
+                let held = match filter {
+                    ModifierFilter::Super => &mut m.logo,
+                    ModifierFilter::Ctrl => &mut m.ctrl,
+                    ModifierFilter::Alt => &mut m.alt,
+                    ModifierFilter::Shift => &mut m.shift,
+                    ModifierFilter::Fn => return Task::none(),
+                };
+                *held = !*held;
+            }
+            Message::ToggleSection(section) => {
+                self.finish_section_animation();
+                let collapsing = !self.collapsed_sections.contains(&section);
+                if !collapsing {
+                    self.set_collapsed(section.clone(), false);
+                }
+                if self.config.reduce_motion {
+                    if collapsing {
+                        self.set_collapsed(section, true);
+                    }
                 } else {
-                    self.selected_categories.insert(category);
+                    let started = Instant::now();
+                    self.frame_time = Some(started);
+                    self.section_animation = Some(SectionAnimation {
+                        section,
+                        started,
+                        collapsing,
+                    });
                 }
             }
-            Message::TogglePopup => {
-                return if let Some(p) = self.popup.take() {
-                    destroy_popup(p)
+            Message::AnimationFrame(now) => {
+                self.frame_time = Some(now);
+                let done = |started: Instant| now.duration_since(started) >= ANIMATION_DURATION;
+                if self
+                    .section_animation
+                    .as_ref()
+                    .is_some_and(|a| done(a.started))
+                {
+                    self.finish_section_animation();
+                }
+                if self.detail_opened.is_some_and(done) {
+                    self.detail_opened = None;
+                }
+                let confirmed = |(_, started): &(String, Instant)| {
+                    now.duration_since(*started) >= CONFIRMATION_DURATION
+                };
+                if self.copied.as_ref().is_some_and(confirmed) {
+                    self.copied = None;
+                }
+            }
+            Message::CopyCommand(command) => {
+                let started = Instant::now();
+                self.frame_time = Some(started);
+                self.copied = Some((command.clone(), started));
+                let command = if self.revealed_commands.contains(&command) {
+                    command
                 } else {
-                    let new_id = Id::unique();
-                    self.popup.replace(new_id);
-                    let mut popup_settings = self.core.applet.get_popup_settings(
-                        self.core.main_window_id().unwrap(),
-                        new_id,
-                        None,
-                        None,
-                        None,
-                    );
-                    popup_settings.positioner.size_limits = Limits::NONE
-                        .max_width(500.0)
-                        .min_width(450.0)
-                        .min_height(200.0)
-                        .max_height(800.0);
-                    get_popup(popup_settings)
+                    redact::redact(&command, &self.redactions).into_owned()
                 };
+                if !self.config.notify_actions {
+                    return cosmic::iced::clipboard::write(command);
+                }
+                let notify = notifications::notify(fl!("command-copied"), command.clone());
+                return Task::batch([
+                    cosmic::iced::clipboard::write(command),
+                    Task::perform(notify, |result| {
+                        cosmic::Action::App(Message::NotificationSent(
+                            result.map_err(|e| format!("{:#}", e)),
+                        ))
+                    }),
+                ]);
             }
-            Message::PopupClosed(id) => {
-                if self.popup.as_ref() == Some(&id) {
-                    self.popup = None;
+            Message::NotificationSent(result) => {
+                if let Err(e) = result {
+                    log::warn!("Failed to show notification: {}", e);
                 }
             }
-        }
-        Task::none()
-    }
-
-    fn style(&self) -> Option<cosmic::iced_runtime::Appearance> {
-        Some(cosmic::applet::style())
+            Message::CopyText(text) => {
+                return cosmic::iced::clipboard::write(text);
+            }
+            Message::CopyList(format) => {
+                self.record_feature("copy-list");
+                return cosmic::iced::clipboard::write(format.render(&self.list_export_sections()));
+            }
+            Message::ToggleHiddenShortcut(description) => {
+                let mut hidden = self.config.hidden_shortcuts.clone();
+                match hidden.iter().position(|d| *d == description) {
+                    Some(index) => {
+                        hidden.remove(index);
+                    }
+                    None => hidden.push(description),
+                }
+                match &self.config_handler {
+                    Some(handler) => {
+                        if let Err(e) = self.config.set_hidden_shortcuts(handler, hidden) {
+                            log::error!("Failed to save hidden shortcuts: {}", e);
+                        }
+                    }
+                    None => self.config.hidden_shortcuts = hidden,
+                }
+            }
+            Message::ToggleHiddenCategory(category) => {
+                let mut hidden = self.config.hidden_categories.clone();
+                match hidden.iter().position(|id| id == category.id()) {
+                    Some(index) => {
+                        hidden.remove(index);
+                    }
+                    None => hidden.push(category.id().to_string()),
+                }
+                match &self.config_handler {
+                    Some(handler) => {
+                        if let Err(e) = self.config.set_hidden_categories(handler, hidden) {
+                            log::error!("Failed to save hidden categories: {}", e);
+                        }
+                    }
+                    None => self.config.hidden_categories = hidden,
+                }
+            }
+            Message::ShowHidden(show) => match &self.config_handler {
+                Some(handler) => {
+                    if let Err(e) = self.config.set_show_hidden(handler, show) {
+                        log::error!("Failed to save hidden shortcut visibility: {}", e);
+                    }
+                }
+                None => self.config.show_hidden = show,
+            },
+            Message::RevealCommand(command) => {
+                if !self.revealed_commands.remove(&command) {
+                    self.revealed_commands.insert(command);
+                }
+            }
+            Message::ApplyQuickFilter(index) => {
+                if let Some(filter) = self.config.quick_filters.get(index).cloned() {
+                    return self.apply_filter(&filter);
+                }
+            }
+            Message::ApplySavedSearch(index) => {
+                if let Some(filter) = self.config.saved_searches.get(index).cloned() {
+                    return self.apply_filter(&filter);
+                }
+            }
+            Message::EditSavedSearches(open) => {
+                self.saved_search_name = open.then(String::new);
+            }
+            Message::SavedSearchNameInput(name) => {
+                self.saved_search_name = Some(name);
+            }
+            Message::SaveSearch => {
+                let Some(name) = self.saved_search_name.take() else {
+                    return Task::none();
+                };
+                let name = name.trim();
+                if !name.is_empty() {
+                    let mut searches = self.config.saved_searches.clone();
+                    let filter = self.current_filter(name);
+                    // Saving under an existing name replaces that search in place
+                    match searches.iter_mut().find(|s| s.name == filter.name) {
+                        Some(existing) => *existing = filter,
+                        None => searches.push(filter),
+                    }
+                    self.save_saved_searches(searches);
+                }
+            }
+            Message::MoveSavedSearch(from, to) => {
+                let mut searches = self.config.saved_searches.clone();
+                if from < searches.len() && to < searches.len() {
+                    let search = searches.remove(from);
+                    searches.insert(to, search);
+                    self.save_saved_searches(searches);
+                }
+            }
+            Message::RemoveSavedSearch(index) => {
+                let mut searches = self.config.saved_searches.clone();
+                if index < searches.len() {
+                    searches.remove(index);
+                    self.save_saved_searches(searches);
+                }
+            }
+            Message::ToggleCategory(category) => {
+                if self.selected_categories.contains(&category) {
+                    self.selected_categories.remove(&category);
+                } else {
+                    self.selected_categories.insert(category);
+                    self.record_usage(|usage| {
+                        *usage
+                            .category_views
+                            .entry(category.id().to_string())
+                            .or_default() += 1;
+                    });
+                }
+            }
+            Message::ShowPage(page) => {
+                self.page = page;
+                if page != PopupPage::Shortcuts {
+                    self.record_feature(&format!("{page:?}").to_lowercase());
+                }
+                if page == PopupPage::Export {
+                    self.share_qr = self.share_qr_data();
+                }
+                if page == PopupPage::RawConfig {
+                    self.raw_files = layers::read();
+                }
+                self.save_state();
+                #[cfg(feature = "sheets")]
+                if page == PopupPage::Community {
+                    return self.open_community();
+                }
+            }
+            Message::OpenUrl(url) => {
+                if let Err(e) = std::process::Command::new("xdg-open").arg(&url).spawn() {
+                    log::error!("Failed to open {}: {}", url, e);
+                }
+            }
+            Message::TourNext => {
+                self.tour_step = (self.tour_step + 1).min(TOUR_STEPS.len() - 1);
+            }
+            Message::TourDontShowAgain(value) => {
+                self.tour_dont_show_again = value;
+            }
+            Message::TourFinish => {
+                self.tour_step = 0;
+                self.page = PopupPage::Shortcuts;
+                if self.tour_dont_show_again {
+                    if let Some(handler) = &self.config_handler {
+                        if let Err(e) = self.config.set_onboarding_complete(handler, true) {
+                            log::error!("Failed to save onboarding state: {}", e);
+                        }
+                    }
+                }
+            }
+            Message::ClearSearchHistory => {
+                self.save_search_history(Vec::new());
+            }
+            Message::TogglePopup => {
+                return if self.popup.is_some() {
+                    self.close_popup()
+                } else {
+                    self.open_popup()
+                };
+            }
+            #[cfg(feature = "overlay")]
+            Message::Overlay(message) => return self.update_overlay(message),
+            Message::OpenLink(link) => {
+                self.page = PopupPage::Shortcuts;
+                if let Link::Search { query, categories } = link {
+                    self.search_query = query;
+                    self.selected_categories = if categories.is_empty() {
+                        ShortcutCategory::all().iter().copied().collect()
+                    } else {
+                        categories.into_iter().collect()
+                    };
+                    self.modifier_filters.clear();
+                    self.selected_row = None;
+                    self.hovered_row = None;
+                }
+                if !self.list_open() {
+                    return self.open_popup();
+                }
+            }
+            Message::PopupClosed(id) => {
+                if self.popup.as_ref() == Some(&id) {
+                    self.popup = None;
+                    self.remember_search();
+                    self.save_state();
+                    self.release_memory();
+                }
+            }
+        }
+        Task::none()
+    }
+
+    fn style(&self) -> Option<cosmic::iced_runtime::Appearance> {
+        (!self.standalone).then(cosmic::applet::style)
+    }
+}
+
+impl AppModel {
+    /// Content of the current page, shown in the popup or the standalone window.
+    fn view_page(&self) -> Element<'_, Message> {
+        let page = match self.page {
+            PopupPage::Shortcuts => self.view_shortcuts(),
+            PopupPage::About => self.view_about(),
+            PopupPage::Export => self.view_export(),
+            PopupPage::Onboarding => self.view_onboarding(),
+            PopupPage::FreeKeys => self.view_free_keys(),
+            PopupPage::Stats => self.view_stats(),
+            PopupPage::Keyboard => self.view_keyboard(),
+            PopupPage::Compare => self.view_compare(),
+            PopupPage::Detail => match self.detail_opened {
+                // Unfold the page from the top
+                Some(started) => {
+                    let height =
+                        800.0 * style::interface_scale() * self.animation_progress(started);
+                    widget::container(self.view_detail())
+                        .max_height(height)
+                        .clip(true)
+                        .into()
+                }
+                None => self.view_detail(),
+            },
+            PopupPage::RawConfig => self.view_raw_config(),
+            #[cfg(feature = "sheets")]
+            PopupPage::Community => self.view_community(),
+        };
+
+        let mut banners = Vec::new();
+        #[cfg(feature = "sheets")]
+        if let Some(import) = &self.sheet_import {
+            banners.push(self.view_import(import));
+        }
+        // Only the list shows the update, to keep other pages quiet
+        #[cfg(feature = "sheets")]
+        if let Some(release) = self
+            .update
+            .as_ref()
+            .filter(|_| self.page == PopupPage::Shortcuts)
+        {
+            banners.push(self.view_update(release));
+        }
+
+        if banners.is_empty() {
+            page
+        } else {
+            banners.push(page);
+            widget::column::with_children(banners).into()
+        }
+    }
+
+    /// A newer release, with its notes on request.
+    #[cfg(feature = "sheets")]
+    fn view_update(&self, release: &net::Release) -> Element<'_, Message> {
+        let mut children = vec![
+            widget::text::body(fl!("update-available", version = release.version.as_str())).into(),
+        ];
+        if self.show_release_notes {
+            children.push(
+                widget::scrollable(
+                    widget::text::caption(release.body.as_str())
+                        .wrapping(cosmic::iced::widget::text::Wrapping::Word),
+                )
+                .height(cosmic::iced::Length::Shrink)
+                .into(),
+            );
+        }
+        children.push(
+            widget::row::with_children(vec![
+                widget::button::suggested(fl!("update-get"))
+                    .on_press(Message::OpenUrl(release.download_url()))
+                    .into(),
+                widget::button::text(if self.show_release_notes {
+                    fl!("update-hide-notes")
+                } else {
+                    fl!("update-notes")
+                })
+                .on_press(Message::ToggleReleaseNotes)
+                .into(),
+                widget::button::text(fl!("dismiss"))
+                    .on_press(Message::DismissUpdate)
+                    .into(),
+            ])
+            .spacing(8)
+            .wrap()
+            .into(),
+        );
+
+        widget::container(widget::column::with_children(children).spacing(8))
+            .padding([8, 12])
+            .class(cosmic::theme::Container::Card)
+            .into()
+    }
+
+    /// Color of `category`, from the config if set there, otherwise derived
+    /// from the theme.
+    fn category_color(&self, category: ShortcutCategory) -> style::CategoryColor {
+        let custom = self
+            .config
+            .category_colors
+            .get(category.id())
+            .and_then(|color| style::parse_hex(color));
+        match custom {
+            Some(color) => style::CategoryColor::Custom(color),
+            None => {
+                let index = ShortcutCategory::all().iter().position(|c| *c == category);
+                style::CategoryColor::Palette(index.unwrap_or_default())
+            }
+        }
+    }
+
+    fn section_color(&self, section: &Section) -> Option<style::CategoryColor> {
+        match section {
+            Section::Category(category) => Some(self.category_color(*category)),
+            Section::Modifiers(_) => None,
+        }
+    }
+
+    /// Whether the shortcut list can be seen, in the popup or the window.
+    fn list_open(&self) -> bool {
+        self.standalone || self.popup.is_some()
+    }
+
+    /// Whether the popup uses the touch layout, by choice or for a small screen.
+    fn touch_layout(&self) -> bool {
+        match self.config.layout {
+            Layout::Auto => self
+                .screen_width
+                .is_some_and(|width| width < TOUCH_SCREEN_WIDTH),
+            Layout::Standard => false,
+            Layout::Touch => true,
+        }
+    }
+
+    /// Shows only the next category with shortcuts, or the previous one, going
+    /// around at the ends. Starts from the first or last while several
+    /// categories are shown.
+    fn swipe_category(&mut self, forward: bool) {
+        let categories: Vec<ShortcutCategory> = ShortcutCategory::all()
+            .iter()
+            .copied()
+            .filter(|category| self.shortcuts.iter().any(|s| s.category == *category))
+            .collect();
+        if categories.is_empty() {
+            return;
+        }
+
+        let current = if self.selected_categories.len() == 1 {
+            categories
+                .iter()
+                .position(|category| self.selected_categories.contains(category))
+        } else {
+            None
+        };
+        let next = match (current, forward) {
+            (Some(index), true) => (index + 1) % categories.len(),
+            (Some(index), false) => (index + categories.len() - 1) % categories.len(),
+            (None, true) => 0,
+            (None, false) => categories.len() - 1,
+        };
+        self.selected_categories = HashSet::from([categories[next]]);
+        self.selected_row = None;
+    }
+
+    fn open_popup(&mut self) -> Task<cosmic::Action<Message>> {
+        let new_id = Id::unique();
+        self.popup.replace(new_id);
+        if self.shortcuts.is_empty() {
+            self.reload_shortcuts();
+        }
+        let mut popup_settings = self.core.applet.get_popup_settings(
+            self.core.main_window_id().unwrap(),
+            new_id,
+            None,
+            None,
+            None,
+        );
+        let scale = style::interface_scale();
+        popup_settings.positioner.size_limits = Limits::NONE
+            .max_width(500.0 * scale)
+            .min_width(450.0 * scale)
+            .min_height(200.0 * scale)
+            .max_height(800.0 * scale);
+        // Open beside the icon on vertical panels rather than over the panel.
+        let (anchor, gravity) = match self.core.applet.anchor {
+            PanelAnchor::Left => (PopupAnchor::Right, Gravity::Right),
+            PanelAnchor::Right => (PopupAnchor::Left, Gravity::Left),
+            PanelAnchor::Top => (PopupAnchor::Bottom, Gravity::Bottom),
+            PanelAnchor::Bottom => (PopupAnchor::Top, Gravity::Top),
+        };
+        popup_settings.positioner.anchor = anchor;
+        popup_settings.positioner.gravity = gravity;
+        get_popup(popup_settings)
+    }
+
+    /// Whether shortcuts must be kept loaded: always, unless low-memory mode is
+    /// on and neither the popup nor the overlay is open.
+    fn shortcuts_needed(&self) -> bool {
+        !self.config.low_memory
+            || self.config.http_server
+            || self.list_open()
+            || self.overlay.is_some()
+    }
+
+    /// Reloads shortcuts from every source, unless they aren't needed right now.
+    fn reload_shortcuts(&mut self) {
+        if self.shortcuts_needed() {
+            let loaded = self.provider.load(&self.config);
+            self.shortcuts = loaded.shortcuts;
+            #[cfg(feature = "sheets")]
+            self.sheet_errors = loaded.sheet_errors;
+            self.load_error = loaded.error;
+            self.refresh_http_page();
+        }
+    }
+
+    /// In low-memory mode, frees the shortcuts and everything built from them
+    /// once nothing shows them.
+    fn release_memory(&mut self) {
+        if self.shortcuts_needed() {
+            return;
+        }
+        self.shortcuts = Vec::new();
+        self.share_qr = None;
+        self.profile_changes = Vec::new();
+        crate::desktop::release();
+    }
+
+    fn close_popup(&mut self) -> Task<cosmic::Action<Message>> {
+        match self.popup.take() {
+            Some(id) => {
+                self.remember_search();
+                self.save_state();
+                self.release_memory();
+                destroy_popup(id)
+            }
+            None => Task::none(),
+        }
+    }
+
+    /// Brings back the page and filters of the previous run. The first-run tour
+    /// keeps priority over the saved page.
+    fn restore_state(&mut self, state: State) {
+        if self.page != PopupPage::Onboarding {
+            self.page = state.page;
+        }
+        if self.page == PopupPage::RawConfig {
+            self.raw_files = layers::read();
+        }
+        let filter = QuickFilter {
+            name: String::new(),
+            query: state.search_query.clone(),
+            categories: state.categories.clone(),
+            modifiers: state.modifier_filters.clone(),
+        };
+        self.selected_categories = filter.category_set();
+        self.modifier_filters = filter.modifiers.iter().copied().collect();
+        self.search_query = filter.query;
+        self.state = state;
+    }
+
+    /// Saves what to restore if the panel restarts the applet, when it changed.
+    fn save_state(&mut self) {
+        let filter = self.current_filter("");
+        // The detail page needs a shortcut that isn't saved
+        let page = match self.page {
+            PopupPage::Detail => PopupPage::Shortcuts,
+            // The index is fetched again when the page is opened
+            #[cfg(feature = "sheets")]
+            PopupPage::Community => PopupPage::About,
+            page => page,
+        };
+        let state = State {
+            page,
+            search_query: filter.query,
+            categories: filter.categories,
+            modifier_filters: filter.modifiers,
+        };
+        if state == self.state {
+            return;
+        }
+        if let Some(handler) = &self.state_handler {
+            if let Err(e) = state.write_entry(handler) {
+                log::error!("Failed to save state: {}", e);
+            }
+        }
+        self.state = state;
+    }
+
+    /// Adds the current query to the front of the search history.
+    fn remember_search(&mut self) {
+        let query = self.search_query.trim();
+        if query.is_empty() {
+            return;
+        }
+
+        let mut history = vec![query.to_string()];
+        history.extend(
+            self.config
+                .search_history
+                .iter()
+                .filter(|previous| !previous.eq_ignore_ascii_case(query))
+                .cloned(),
+        );
+        history.truncate(SEARCH_HISTORY_LIMIT);
+        self.save_search_history(history);
+    }
+
+    fn save_search_history(&mut self, history: Vec<String>) {
+        match &self.config_handler {
+            Some(handler) => {
+                if let Err(e) = self.config.set_search_history(handler, history) {
+                    log::error!("Failed to save search history: {}", e);
+                }
+            }
+            None => self.config.search_history = history,
+        }
+    }
+
+    /// Header shown above every popup page, with navigation between pages.
+    fn view_header(&self) -> Element<'_, Message> {
+        // The list links to the other pages, which all lead back to the list
+        let pages: &[(&str, PopupPage)] = match self.page {
+            PopupPage::Shortcuts => &[
+                ("edit-find-symbolic", PopupPage::FreeKeys),
+                ("view-list-symbolic", PopupPage::Stats),
+                ("input-keyboard-symbolic", PopupPage::Keyboard),
+                ("document-save-symbolic", PopupPage::Export),
+                ("help-about-symbolic", PopupPage::About),
+            ],
+            _ => &[("go-previous-symbolic", PopupPage::Shortcuts)],
+        };
+
+        let mut children = vec![
+            widget::text::heading(fl!("app-title"))
+                .width(cosmic::iced::Length::Fill)
+                .into(),
+        ];
+        for (icon, page) in pages {
+            children.push(
+                widget::button::icon(widget::icon::from_name(*icon))
+                    .on_press(Message::ShowPage(*page))
+                    .into(),
+            );
+        }
+
+        widget::row::with_children(children)
+            .align_y(cosmic::iced::Alignment::Center)
+            .padding([8, 12, 0, 12])
+            .into()
+    }
+
+    /// Shortcuts matching the search query and the selected categories.
+    ///
+    /// With a query, the best matches come first; ties keep the loader's order.
+    fn filtered_shortcuts(&self) -> Vec<&KeyBinding> {
+        let mut scored: Vec<(u32, &KeyBinding)> = self
+            .shortcuts
+            .iter()
+            .filter(|shortcut| {
+                // Filter by selected categories
+                self.selected_categories.is_empty()
+                    || self.selected_categories.contains(&shortcut.category)
+            })
+            .filter(|shortcut| self.config.show_hidden || !self.config.is_hidden(shortcut))
+            .filter(|shortcut| {
+                // Filter by selected modifier chips
+                self.modifier_filters
+                    .iter()
+                    .all(|filter| filter.matches(shortcut))
+            })
+            .filter_map(|shortcut| {
+                // Filter by search query, falling back to the keys, e.g. "keypad"
+                search::score(&self.search_query, &shortcut.description)
+                    .or_else(|| search::score(&self.search_query, &shortcut.display_label()))
+                    .map(|score| (score, shortcut))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.0.cmp(&a.0));
+        scored.into_iter().map(|(_, shortcut)| shortcut).collect()
+    }
+
+    /// Shortcuts grouped by category, with commands redacted unless the user
+    /// revealed them, ready to be exported.
+    fn export_sections(&self, shortcuts: &[&KeyBinding]) -> Vec<ExportSection> {
+        export_sections(shortcuts, |shortcut| {
+            self.redacted_description(shortcut).into_owned()
+        })
+    }
+
+    /// Converts shortcuts to the exchange format, with secrets redacted.
+    fn export_document<'a>(
+        &self,
+        shortcuts: impl Iterator<Item = &'a KeyBinding>,
+    ) -> format::Document {
+        format::Document::new(
+            shortcuts
+                .map(|shortcut| {
+                    let mut binding = format::Binding::from_shortcut(shortcut);
+                    binding.description = self.redacted_description(shortcut).into_owned();
+                    binding.command = binding
+                        .command
+                        .map(|command| redact::redact(&command, &self.redactions).into_owned());
+                    binding
+                })
+                .collect(),
+        )
+    }
+
+    /// Re-renders the pages served over HTTP from all loaded shortcuts.
+    fn refresh_http_page(&self) {
+        if !self.config.http_server {
+            return;
+        }
+
+        let shortcuts: Vec<&KeyBinding> = self.shortcuts.iter().collect();
+        let html = export::render_html(&fl!("app-title"), &self.export_sections(&shortcuts));
+        let json = self.export_document(self.shortcuts.iter()).to_json();
+        if let Ok(mut page) = self.http_page.write() {
+            *page = http::Page { html, json };
+        }
+    }
+
+    /// Generates and saves an access token the first time the HTTP server is enabled.
+    fn ensure_http_token(&mut self) {
+        if !self.config.http_server || !self.config.http_token.is_empty() {
+            return;
+        }
+
+        let token = match http::generate_token() {
+            Ok(token) => token,
+            Err(e) => {
+                log::error!("Failed to generate HTTP server token: {}", e);
+                return;
+            }
+        };
+        log::info!("generated HTTP server token");
+        match &self.config_handler {
+            Some(handler) => {
+                if let Err(e) = self.config.set_http_token(handler, token) {
+                    log::error!("Failed to save HTTP server token: {}", e);
+                }
+            }
+            None => self.config.http_token = token,
+        }
+    }
+
+    /// The description of a shortcut, with secrets in spawn commands masked
+    /// unless the user revealed them.
+    fn redacted_description<'a>(&self, shortcut: &'a KeyBinding) -> std::borrow::Cow<'a, str> {
+        if shortcut.category == ShortcutCategory::Custom
+            && !self.revealed_commands.contains(&shortcut.command)
+        {
+            redact::redact(&shortcut.description, &self.redactions)
+        } else {
+            std::borrow::Cow::Borrowed(shortcut.description.as_str())
+        }
+    }
+
+    /// Encodes the custom shortcuts as a QR code so they can be scanned on another
+    /// machine. Commands are redacted as on screen.
+    fn share_qr_data(&self) -> Option<qr_code::Data> {
+        let custom = self.export_document(
+            self.shortcuts
+                .iter()
+                .filter(|shortcut| shortcut.category == ShortcutCategory::Custom),
+        );
+        if custom.bindings.is_empty() {
+            return None;
+        }
+
+        match qr_code::Data::new(custom.to_compact_json()) {
+            Ok(data) => Some(data),
+            Err(e) => {
+                log::warn!("Custom shortcuts don't fit in a QR code: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Buttons saving, printing or copying the current (filtered) list; images
+    /// and printing need the `export` feature, and images aren't saved in
+    /// read-only deployments.
+    fn export_buttons(&self) -> Vec<Element<'_, Message>> {
+        let mut buttons = Vec::new();
+        #[cfg(feature = "export")]
+        {
+            if self.config.allow_editing {
+                buttons.extend([
+                    widget::button::standard(fl!("export-png"))
+                        .on_press(Message::ExportImage(ImageFormat::Png))
+                        .into(),
+                    widget::button::standard(fl!("export-svg"))
+                        .on_press(Message::ExportImage(ImageFormat::Svg))
+                        .into(),
+                ]);
+            }
+            buttons.push(
+                widget::button::standard(fl!("print"))
+                    .on_press(Message::Print)
+                    .into(),
+            );
+        }
+        buttons.extend([
+            widget::button::standard(fl!("copy-list"))
+                .on_press(Message::CopyList(TextFormat::Plain))
+                .into(),
+            widget::button::standard(fl!("copy-list-markdown"))
+                .on_press(Message::CopyList(TextFormat::Markdown))
+                .into(),
+        ]);
+        buttons
+    }
+
+    /// Saving the current (filtered) list as an image.
+    fn view_export(&self) -> Element<'_, Message> {
+        let mut profile_buttons = Vec::new();
+        if self.config.allow_editing {
+            profile_buttons.push(
+                widget::button::standard(fl!("profile-save"))
+                    .on_press(Message::ExportProfile)
+                    .into(),
+            );
+        }
+        profile_buttons.push(
+            widget::button::standard(fl!("profile-compare"))
+                .on_press(Message::CompareProfile)
+                .into(),
+        );
+
+        let mut children = vec![
+            widget::text::body(fl!("export-description"))
+                .wrapping(cosmic::iced::widget::text::Wrapping::Word)
+                .into(),
+            widget::row::with_children(self.export_buttons())
+                .spacing(8)
+                .wrap()
+                .into(),
+            widget::text::body(fl!("profile-description"))
+                .wrapping(cosmic::iced::widget::text::Wrapping::Word)
+                .into(),
+            widget::row::with_children(profile_buttons)
+                .spacing(8)
+                .wrap()
+                .into(),
+        ];
+
+        match &self.export_status {
+            Some(Ok(path)) => children.push(
+                widget::text::caption(fl!("export-saved", path = path.display().to_string()))
+                    .into(),
+            ),
+            Some(Err(e)) => children
+                .push(widget::text::caption(fl!("export-failed", error = e.as_str())).into()),
+            None => {}
+        }
+        if let Some(e) = &self.print_error {
+            children.push(widget::text::caption(fl!("print-failed", error = e.as_str())).into());
+        }
+        if let Some(e) = &self.compare_error {
+            children.push(widget::text::caption(fl!("compare-failed", error = e.as_str())).into());
+        }
+
+        if let Some(data) = &self.share_qr {
+            children.push(widget::text::heading(fl!("share-custom")).into());
+            children.push(
+                widget::text::caption(fl!("share-custom-description"))
+                    .wrapping(cosmic::iced::widget::text::Wrapping::Word)
+                    .into(),
+            );
+            children.push(
+                widget::container(qr_code(data).cell_size(4))
+                    .center_x(cosmic::iced::Length::Fill)
+                    .into(),
+            );
+        }
+
+        widget::column::with_children(vec![
+            self.view_header(),
+            widget::column::with_children(children)
+                .spacing(12)
+                .padding([8, 12])
+                .into(),
+        ])
+        .into()
+    }
+
+    /// Rows of the filtered list, in display order, split into sections.
+    ///
+    /// Without category grouping there is a single section without a header.
+    fn list_sections(&self) -> Vec<(Option<Section>, Vec<&KeyBinding>)> {
+        let filtered_shortcuts = self.filtered_shortcuts();
+        if !self.config.group_by_category {
+            return vec![(None, filtered_shortcuts)];
+        }
+
+        match self.config.group_by {
+            GroupBy::Category => group_by_category(&filtered_shortcuts)
+                .into_iter()
+                .map(|(category, rows)| (Some(Section::Category(category)), rows))
+                .collect(),
+            GroupBy::Modifiers => group_by_modifiers(&filtered_shortcuts)
+                .into_iter()
+                .map(|(modifiers, rows)| (Some(Section::Modifiers(modifiers)), rows))
+                .collect(),
+        }
+    }
+
+    /// Rows shown in the list. Collapsed sections keep their header but have
+    /// no rows.
+    fn visible_sections(&self) -> Vec<(Option<Section>, Vec<&KeyBinding>)> {
+        self.list_sections()
+            .into_iter()
+            .map(|(section, rows)| {
+                if section
+                    .as_ref()
+                    .is_some_and(|section| self.collapsed_sections.contains(section))
+                {
+                    (section, Vec::new())
+                } else {
+                    (section, rows)
+                }
+            })
+            .collect()
+    }
+
+    /// The filtered list as sorted and grouped on screen, collapsed sections
+    /// included, with secrets redacted as on screen.
+    fn list_export_sections(&self) -> Vec<ExportSection> {
+        self.list_sections()
+            .into_iter()
+            .map(|(section, rows)| ExportSection {
+                title: section.map(|section| section.label()).unwrap_or_default(),
+                rows: rows
+                    .into_iter()
+                    .map(|shortcut| ExportRow {
+                        keys: shortcut.display_label(),
+                        description: self.redacted_description(shortcut).into_owned(),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Whether the search, category and modifier filters are those of `filter`.
+    fn quick_filter_active(&self, filter: &QuickFilter) -> bool {
+        self.search_query == filter.query
+            && self.selected_categories == filter.category_set()
+            && self.modifier_filters == filter.modifiers.iter().copied().collect::<HashSet<_>>()
+    }
+
+    /// Shows what `filter` selects; applying the active filter again clears it.
+    fn apply_filter(&mut self, filter: &QuickFilter) -> Task<cosmic::Action<Message>> {
+        if self.quick_filter_active(filter) {
+            self.search_query.clear();
+            self.selected_categories = ShortcutCategory::all().iter().copied().collect();
+            self.modifier_filters.clear();
+        } else {
+            self.search_query = filter.query.clone();
+            self.selected_categories = filter.category_set();
+            self.modifier_filters = filter.modifiers.iter().copied().collect();
+        }
+        self.selected_row = None;
+        self.hovered_row = None;
+        scrollable::snap_to(LIST_ID.clone(), RelativeOffset::START)
+    }
+
+    /// The current search and filters, to be saved under `name`.
+    fn current_filter(&self, name: &str) -> QuickFilter {
+        let mut filter = QuickFilter::new(name, &self.search_query, &[]);
+        if self.selected_categories.len() < ShortcutCategory::all().len() {
+            filter.categories = ShortcutCategory::all()
+                .iter()
+                .filter(|category| self.selected_categories.contains(category))
+                .map(|category| category.id().to_string())
+                .collect();
+        }
+        filter.modifiers = ModifierFilter::all()
+            .iter()
+            .copied()
+            .filter(|modifier| self.modifier_filters.contains(modifier))
+            .collect();
+        filter
+    }
+
+    fn save_saved_searches(&mut self, searches: Vec<QuickFilter>) {
+        match &self.config_handler {
+            Some(handler) => {
+                if let Err(e) = self.config.set_saved_searches(handler, searches) {
+                    log::error!("Failed to save searches: {}", e);
+                }
+            }
+            None => self.config.saved_searches = searches,
+        }
+    }
+
+    /// Collapses the category sections listed in the config, keeping the state
+    /// of modifier sections.
+    fn sync_collapsed_categories(&mut self) {
+        self.collapsed_sections
+            .retain(|section| !matches!(section, Section::Category(_)));
+        self.collapsed_sections.extend(
+            self.config
+                .collapsed_categories
+                .iter()
+                .filter_map(|id| ShortcutCategory::from_id(id))
+                .map(Section::Category),
+        );
+    }
+
+    /// Updates the usage statistics, if they are kept, and saves them.
+    fn record_usage(&mut self, update: impl FnOnce(&mut Usage)) {
+        if !self.config.usage_stats {
+            return;
+        }
+        update(&mut self.usage);
+        if let Some(handler) = &self.usage_handler {
+            if let Err(e) = self.usage.write_entry(handler) {
+                log::error!("Failed to save usage statistics: {}", e);
+            }
+        }
+    }
+
+    fn record_feature(&mut self, feature: &str) {
+        self.record_usage(|usage| {
+            *usage.feature_uses.entry(feature.to_string()).or_default() += 1;
+        });
+    }
+
+    #[cfg(feature = "sheets")]
+    fn check_for_update(&self) -> Task<cosmic::Action<Message>> {
+        Task::perform(net::check_for_update(), |result| {
+            cosmic::Action::App(Message::UpdateChecked(
+                result.map_err(|e| format!("{:#}", e)),
+            ))
+        })
+    }
+
+    fn set_collapsed(&mut self, section: Section, collapsed: bool) {
+        let is_category = matches!(section, Section::Category(_));
+        let changed = if collapsed {
+            self.collapsed_sections.insert(section)
+        } else {
+            self.collapsed_sections.remove(&section)
+        };
+        if changed && is_category {
+            self.save_collapsed_categories();
+        }
+    }
+
+    /// Ends the section animation, collapsing the section if it was collapsing.
+    fn finish_section_animation(&mut self) {
+        if let Some(animation) = self.section_animation.take() {
+            if animation.collapsing {
+                self.set_collapsed(animation.section, true);
+            }
+        }
+    }
+
+    /// Whether `command` was copied a moment ago.
+    fn just_copied(&self, command: &str) -> bool {
+        self.copied
+            .as_ref()
+            .is_some_and(|(copied, _)| copied == command)
+    }
+
+    /// Icon of the copy button of `command`: a checkmark popping in for a
+    /// moment after it was copied.
+    fn copy_icon(&self, command: &str) -> widget::icon::Named {
+        match &self.copied {
+            Some((copied, started)) if copied == command => {
+                let size = if self.config.reduce_motion {
+                    16.0
+                } else {
+                    8.0 + 8.0 * self.animation_progress(*started)
+                };
+                widget::icon::from_name("object-select-symbolic").size(size as u16)
+            }
+            _ => widget::icon::from_name("edit-copy-symbolic"),
+        }
+    }
+
+    /// How far an animation started at `started` has come, from 0.0 to 1.0,
+    /// easing out.
+    fn animation_progress(&self, started: Instant) -> f32 {
+        let now = self.frame_time.unwrap_or(started);
+        let t =
+            now.saturating_duration_since(started).as_secs_f32() / ANIMATION_DURATION.as_secs_f32();
+        1.0 - (1.0 - t.clamp(0.0, 1.0)).powi(3)
+    }
+
+    /// Persists which category sections are collapsed.
+    fn save_collapsed_categories(&mut self) {
+        let ids: Vec<String> = ShortcutCategory::all()
+            .iter()
+            .filter(|category| {
+                self.collapsed_sections
+                    .contains(&Section::Category(**category))
+            })
+            .map(|category| category.id().to_string())
+            .collect();
+        match &self.config_handler {
+            Some(handler) => {
+                if let Err(e) = self.config.set_collapsed_categories(handler, ids) {
+                    log::error!("Failed to save collapsed sections: {}", e);
+                }
+            }
+            None => self.config.collapsed_categories = ids,
+        }
+    }
+
+    /// Selects the segment matching the configured grouping.
+    fn sync_group_by_model(&mut self) {
+        let entity = self.group_by_model.iter().find(|entity| {
+            self.group_by_model.data::<GroupBy>(*entity) == Some(&self.config.group_by)
+        });
+        if let Some(entity) = entity {
+            self.group_by_model.activate(entity);
+        }
+    }
+
+    /// Scrolls the list so the selected row is in view, if it is not already.
+    ///
+    /// Like the sticky header, this estimates row positions from item counts
+    /// rather than measuring rows.
+    fn scroll_to_selected(&self) -> Task<cosmic::Action<Message>> {
+        let Some(selected) = self.selected_row else {
+            return Task::none();
+        };
+
+        let mut item = None;
+        let mut items = 0;
+        let mut row_index = 0;
+        for (section, rows) in self.visible_sections() {
+            items += usize::from(section.is_some());
+            for _ in rows {
+                if row_index == selected {
+                    item = Some(items);
+                }
+                items += 1;
+                row_index += 1;
+            }
+        }
+        let Some(item) = item else {
+            return Task::none();
+        };
+
+        let visible = if self.list_visible > 0.0 {
+            self.list_visible
+        } else {
+            (LIST_VISIBLE_ITEMS_ESTIMATE / items as f32).min(1.0)
+        };
+        if visible >= 1.0 {
+            return Task::none();
+        }
+        let position = (item as f32 + 0.5) / items as f32;
+        let top = self.list_scroll * (1.0 - visible);
+        if position >= top && position <= top + visible {
+            return Task::none();
+        }
+
+        // Center the selected row in the viewport
+        let y = ((position - visible / 2.0) / (1.0 - visible)).clamp(0.0, 1.0);
+        scrollable::snap_to(LIST_ID.clone(), RelativeOffset { x: 0.0, y })
+    }
+
+    /// Number of rows shown in the list, used to bound keyboard selection.
+    fn visible_row_count(&self) -> usize {
+        self.visible_sections()
+            .iter()
+            .map(|(_, rows)| rows.len())
+            .sum()
+    }
+
+    /// A quick filter or saved search button, highlighted while it is applied.
+    fn filter_chip<'a>(
+        &'a self,
+        filter: &'a QuickFilter,
+        message: Message,
+    ) -> Element<'a, Message> {
+        let chip = if self.quick_filter_active(filter) {
+            widget::button::suggested(&filter.name)
+        } else {
+            widget::button::standard(&filter.name)
+        };
+        chip.on_press(message).into()
+    }
+
+    /// Saves the current search under a name and reorders or removes saved
+    /// searches. Empty while the editor is closed.
+    fn view_saved_search_editor(&self) -> Element<'_, Message> {
+        let Some(name) = &self.saved_search_name else {
+            return widget::column::with_children(vec![]).into();
+        };
+
+        let save = widget::button::suggested(fl!("save-search"))
+            .on_press_maybe((!name.trim().is_empty()).then_some(Message::SaveSearch));
+        let mut children: Vec<Element<'_, Message>> = vec![
+            widget::row::with_children(vec![
+                widget::text_input(fl!("saved-search-name"), name)
+                    .on_input(Message::SavedSearchNameInput)
+                    .into(),
+                save.into(),
+            ])
+            .spacing(8)
+            .align_y(cosmic::iced::Alignment::Center)
+            .into(),
+        ];
+
+        let count = self.config.saved_searches.len();
+        for (index, search) in self.config.saved_searches.iter().enumerate() {
+            let icon_button = |icon: &'static str, message: Option<Message>| {
+                widget::button::icon(widget::icon::from_name(icon)).on_press_maybe(message)
+            };
+            children.push(
+                widget::row::with_children(vec![
+                    widget::text::body(&search.name)
+                        .width(cosmic::iced::Length::Fill)
+                        .into(),
+                    icon_button(
+                        "go-up-symbolic",
+                        (index > 0).then(|| Message::MoveSavedSearch(index, index - 1)),
+                    )
+                    .into(),
+                    icon_button(
+                        "go-down-symbolic",
+                        (index + 1 < count).then(|| Message::MoveSavedSearch(index, index + 1)),
+                    )
+                    .into(),
+                    icon_button(
+                        "edit-delete-symbolic",
+                        Some(Message::RemoveSavedSearch(index)),
+                    )
+                    .into(),
+                ])
+                .spacing(4)
+                .align_y(cosmic::iced::Alignment::Center)
+                .into(),
+            );
+        }
+
+        widget::column::with_children(children)
+            .spacing(4)
+            .padding([4, 12])
+            .into()
+    }
+
+    /// The searchable list of shortcuts.
+    fn view_shortcuts(&self) -> Element<'_, Message> {
+        let touch = self.touch_layout();
+
+        // Search input with container to avoid edge artifacts
+        let search_input = widget::container(
+            widget::text_input("Search shortcuts...", &self.search_query)
+                .on_input(Message::SearchInput)
+                .padding(if touch { 14 } else { 8 }),
+        )
+        .padding([8, 12]);
+
+        // Offer recent searches while the search field is empty
+        let suggestions = if self.search_query.is_empty() && !self.config.search_history.is_empty()
+        {
+            let mut chips: Vec<Element<'_, Message>> = self
+                .config
+                .search_history
+                .iter()
+                .map(|query| {
+                    widget::button::text(query)
+                        .on_press(Message::SearchInput(query.clone()))
+                        .into()
+                })
+                .collect();
+            if self.config.allow_editing {
+                chips.push(
+                    widget::button::link(fl!("clear-search-history"))
+                        .on_press(Message::ClearSearchHistory)
+                        .into(),
+                );
+            }
+
+            widget::row::with_children(chips)
+                .spacing(4)
+                .padding([0, 12])
+                .wrap()
+                .into()
+        } else {
+            widget::column::with_children(vec![]).into()
+        };
+
+        // Point out COSMIC shortcuts and custom sheets that couldn't be
+        // loaded, with what to do about it
+        let load_warning = self.load_error.iter().map(|error| -> Element<'_, Message> {
+            widget::column::with_children(vec![
+                widget::text::caption(error.to_string())
+                    .class(cosmic::theme::Text::Accent)
+                    .into(),
+                widget::text::caption(error.remedy())
+                    .wrapping(cosmic::iced::widget::text::Wrapping::Word)
+                    .into(),
+            ])
+            .into()
+        });
+        #[allow(unused_mut)]
+        let mut warnings: Vec<Element<'_, Message>> = load_warning.collect();
+        #[cfg(feature = "sheets")]
+        warnings.extend(self.sheet_warnings());
+        let sheet_warnings = widget::column::with_children(warnings)
+            .spacing(2)
+            .padding([0, 12]);
+
+        // Modifier chips, combined with the search text
+        let mut chips: Vec<Element<'_, Message>> = Vec::new();
+        for (index, filter) in self.config.quick_filters.iter().enumerate() {
+            chips.push(self.filter_chip(filter, Message::ApplyQuickFilter(index)));
+        }
+        for (index, filter) in self.config.saved_searches.iter().enumerate() {
+            chips.push(self.filter_chip(filter, Message::ApplySavedSearch(index)));
+        }
+        if self.config.allow_editing {
+            chips.push(
+                widget::button::icon(widget::icon::from_name("starred-symbolic"))
+                    .tooltip(fl!("saved-searches"))
+                    .on_press(Message::EditSavedSearches(self.saved_search_name.is_none()))
+                    .into(),
+            );
+        }
+        let quick_filters = widget::column::with_children(vec![
+            widget::row::with_children(chips)
+                .spacing(4)
+                .padding([4, 12])
+                .wrap()
+                .into(),
+            self.view_saved_search_editor(),
+        ]);
+
+        let modifier_chips = widget::row::with_children(
+            ModifierFilter::all()
+                .iter()
+                .map(|filter| {
+                    let chip = if self.modifier_filters.contains(filter) {
+                        widget::button::suggested(filter.label())
+                    } else {
+                        widget::button::standard(filter.label())
+                    };
+                    chip.on_press(Message::ToggleModifierFilter(*filter)).into()
+                })
+                .collect(),
+        )
+        .spacing(4)
+        .padding([4, 12])
+        .wrap();
+
+        // Category filter checkboxes with wrapping
+        let mut category_checkboxes = Vec::new();
+
+        for category in ShortcutCategory::all() {
+            let is_checked = self.selected_categories.contains(category);
+            let checkbox = widget::checkbox(category.label(), is_checked)
+                .on_toggle(move |_| Message::ToggleCategory(*category));
+            category_checkboxes.push(checkbox.into());
+        }
+
+        let category_row = widget::row::with_children(category_checkboxes)
+            .spacing(8)
+            .padding([8, 12, 4, 12])
+            .wrap();
+
+        let category_filter = widget::container(category_row)
+            .width(cosmic::iced::Length::Fill);
+
+        // Choose between sections per category and per set of modifiers
+        let group_by: Element<'_, Message> = if self.config.group_by_category {
+            widget::container(
+                widget::segmented_control::horizontal(&self.group_by_model)
+                    .on_activate(Message::GroupBySelected),
+            )
+            .padding([4, 12])
+            .into()
+        } else {
+            widget::column::with_children(vec![]).into()
+        };
+
+        let mut content_list = widget::list_column().padding(5).spacing(0);
+        let mut sticky_header = None;
+
+        let started = Instant::now();
+        let sections = self.visible_sections();
+        timings::record("grouping", started);
+
+        // Rows aren't measured, so approximate which item sits at the top of the
+        // viewport from the relative scroll offset and the number of items.
+        let total_items: usize = sections
+            .iter()
+            .map(|(section, rows)| rows.len() + usize::from(section.is_some()))
+            .sum();
+        let top_item = (self.list_scroll * total_items as f32) as usize;
+        let mut seen_items = 0;
+        let mut row_index = 0;
+
+        for (section, rows) in sections {
+            // Label groups of custom commands once rows are grouped by category
+            let grouped = matches!(section, Some(Section::Category(_)))
+                && rows.iter().any(|row| row.group.is_some());
+
+            if let Some(section) = section {
+                if self.list_scroll > 0.0 && seen_items <= top_item {
+                    sticky_header = Some(section.clone());
+                }
+                seen_items += 1;
+
+                let collapsed = self.collapsed_sections.contains(&section)
+                    || self
+                        .section_animation
+                        .as_ref()
+                        .is_some_and(|a| a.section == section && a.collapsing);
+                let color = self.section_color(&section);
+                content_list = content_list.add(section_header(section, collapsed, color));
+            }
+
+            // Rows of a section that is collapsing or expanding are clipped
+            // to a growing or shrinking height.
+            let animation = self
+                .section_animation
+                .as_ref()
+                .filter(|a| section.as_ref() == Some(&a.section));
+            let row_count = rows.len();
+
+            seen_items += rows.len();
+            let mut section_rows = widget::column();
+            let mut current_group = None;
+            for shortcut in rows {
+                if grouped && current_group != Some(shortcut.group.as_deref()) {
+                    current_group = Some(shortcut.group.as_deref());
+                    let header = group_header(shortcut.group.as_deref());
+                    match animation {
+                        Some(_) => section_rows = section_rows.push(header),
+                        None => content_list = content_list.add(header),
+                    }
+                }
+                let row = self.shortcut_row(row_index, shortcut);
+                match animation {
+                    Some(_) => section_rows = section_rows.push(row),
+                    None => content_list = content_list.add(row),
+                }
+                row_index += 1;
+            }
+
+            if let Some(animation) = animation {
+                let progress = self.animation_progress(animation.started);
+                let fraction = if animation.collapsing {
+                    1.0 - progress
+                } else {
+                    progress
+                };
+                let full_height =
+                    row_count as f32 * ANIMATION_ROW_HEIGHT * style::interface_scale();
+                content_list = content_list.add(
+                    widget::container(section_rows)
+                        .max_height(full_height * fraction)
+                        .clip(true),
+                );
+            }
+        }
+
+        // Wrap in scrollable to show all shortcuts
+        let scrollable_content = widget::scrollable(content_list)
+            .id(LIST_ID.clone())
+            .on_scroll(|viewport| {
+                let visible = viewport.bounds().height / viewport.content_bounds().height;
+                Message::ListScrolled(viewport.relative_offset().y, visible.min(1.0))
+            });
+
+        // Keep the section at the top of the viewport visible while scrolling. The
+        // scrollable always stays the second child so its scroll state is preserved.
+        let pinned = match sticky_header {
+            // Pulling the list down from the top offers to reload it
+            _ if self.pull_distance > 0.0 => {
+                let hint = if self.pull_distance > PULL_DISTANCE {
+                    fl!("release-to-reload")
+                } else {
+                    fl!("pull-to-reload")
+                };
+                widget::container(widget::text::caption(hint))
+                    .width(cosmic::iced::Length::Fill)
+                    .align_x(cosmic::iced::Alignment::Center)
+                    .padding(8)
+                    .into()
+            }
+            Some(section) => {
+                let color = self.section_color(&section);
+                section_header(section, false, color)
+            }
+            None => widget::column::with_children(vec![]).into(),
+        };
+        let list = widget::column::with_children(vec![pinned, scrollable_content.into()]);
+
+        // In the touch layout the search field sits at the bottom, within reach
+        // of the thumb, below a list that takes the remaining height.
+        if touch {
+            return widget::column::with_children(vec![
+                self.view_header(),
+                quick_filters.into(),
+                modifier_chips.into(),
+                sheet_warnings.into(),
+                category_filter.into(),
+                group_by,
+                list.height(cosmic::iced::Length::Fill).into(),
+                suggestions,
+                search_input.into(),
+            ])
+            .spacing(0)
+            .into();
+        }
+
+        // Combine header, search input, category filter, and scrollable content in a column
+        widget::column::with_children(vec![
+            self.view_header(),
+            search_input.into(),
+            quick_filters.into(),
+            modifier_chips.into(),
+            suggestions,
+            sheet_warnings.into(),
+            category_filter.into(),
+            group_by,
+            list.into(),
+        ])
+        .spacing(0)
+        .into()
+    }
+
+    /// Keys not bound by any source with the chosen modifiers, as candidates for
+    /// new custom shortcuts.
+    fn view_free_keys(&self) -> Element<'_, Message> {
+        let modifiers = widget::row::with_children(
+            ModifierFilter::all()
+                .iter()
+                .filter(|filter| **filter != ModifierFilter::Fn)
+                .map(|filter| {
+                    let m = &self.free_key_modifiers;
+                    let held = match filter {
+                        ModifierFilter::Super => m.logo,
+                        ModifierFilter::Ctrl => m.ctrl,
+                        ModifierFilter::Alt => m.alt,
+                        _ => m.shift,
+                    };
+                    widget::checkbox(filter.label(), held)
+                        .on_toggle(move |_| Message::ToggleFreeKeyModifier(*filter))
+                        .into()
+                })
+                .collect(),
+        )
+        .spacing(12)
+        .wrap();
+
+        let free = free_keys(&self.shortcuts, &self.free_key_modifiers);
+        let summary = widget::text::caption(fl!("free-keys-count", count = free.len()));
+        let keys = widget::row::with_children(
+            free.into_iter()
+                .map(|key| keycaps(format_keybind(&self.free_key_modifiers, Some(key)), None))
+                .collect(),
+        )
+        .spacing(8)
+        .wrap();
+
+        widget::column::with_children(vec![
+            self.view_header(),
+            widget::scrollable(
+                widget::column::with_children(vec![
+                    widget::text::body(fl!("free-keys-description")).into(),
+                    modifiers.into(),
+                    summary.into(),
+                    keys.into(),
+                ])
+                .spacing(12)
+                .padding([8, 12]),
+            )
+            .into(),
+        ])
+        .into()
+    }
+
+    /// Totals per category and modifier set, custom vs default bindings, and how
+    /// often each key is used.
+    fn view_stats(&self) -> Element<'_, Message> {
+        let stats = Stats::compute(&self.shortcuts);
+
+        let count_row = |label: String, count: usize| -> Element<'_, Message> {
+            widget::row::with_children(vec![
+                widget::text::body(label)
+                    .width(cosmic::iced::Length::Fill)
+                    .into(),
+                widget::text::body(count.to_string()).into(),
+            ])
+            .into()
+        };
+
+        let mut children = vec![
+            widget::text::body(fl!(
+                "stats-summary",
+                total = stats.total,
+                custom = stats.custom,
+                default = stats.default
+            ))
+            .into(),
+            widget::text::heading(fl!("stats-categories")).into(),
+        ];
+        for (category, count) in &stats.per_category {
+            children.push(count_row(category.label().to_string(), *count));
+        }
+
+        children.push(widget::text::heading(fl!("stats-modifiers")).into());
+        for (modifiers, count) in stats.per_modifiers.iter().take(5) {
+            children.push(count_row(
+                Section::Modifiers(modifiers.clone()).label(),
+                *count,
+            ));
+        }
+
+        // Mini heatmap of the most used keys
+        children.push(widget::text::heading(fl!("stats-keys")).into());
+        let max = stats.per_key.first().map_or(1, |(_, count)| *count);
+        let keys = stats
+            .per_key
+            .iter()
+            .take(24)
+            .map(|(key, count)| {
+                widget::container(widget::text::body(format!(
+                    "{} {}",
+                    format_key(*key),
+                    count
+                )))
+                .class(style::heat(*count as f32 / max as f32))
+                .padding([2, 6])
+                .into()
+            })
+            .collect();
+        children.push(widget::row::with_children(keys).spacing(4).wrap().into());
+
+        // What the user looks at most, from the local usage statistics
+        children.push(widget::text::heading(fl!("usage")).into());
+        if self.config.allow_editing {
+            children.push(
+                widget::checkbox(fl!("usage-enable"), self.config.usage_stats)
+                    .on_toggle(Message::UsageStats)
+                    .into(),
+            );
+        }
+        children.push(
+            widget::text::caption(fl!("usage-description"))
+                .wrapping(cosmic::iced::widget::text::Wrapping::Word)
+                .into(),
+        );
+        if self.usage != Usage::default() {
+            children.push(
+                widget::text::body(fl!("usage-searches", count = self.usage.searches)).into(),
+            );
+            let category_label = |id: &str| {
+                ShortcutCategory::from_id(id)
+                    .map_or_else(|| id.to_string(), |category| category.label().to_string())
+            };
+            let top_lists: [(String, &HashMap<String, u64>, &dyn Fn(&str) -> String); 3] = [
+                (
+                    fl!("usage-shortcuts"),
+                    &self.usage.shortcut_views,
+                    &str::to_string,
+                ),
+                (
+                    fl!("usage-categories"),
+                    &self.usage.category_views,
+                    &category_label,
+                ),
+                (
+                    fl!("usage-features"),
+                    &self.usage.feature_uses,
+                    &str::to_string,
+                ),
+            ];
+            for (title, counts, label) in top_lists {
+                if counts.is_empty() {
+                    continue;
+                }
+                children.push(widget::text::caption(title).into());
+                for (name, count) in Usage::top(counts, 5) {
+                    children.push(count_row(label(name), count as usize));
+                }
+            }
+            if self.config.allow_editing {
+                children.push(
+                    widget::button::destructive(fl!("usage-clear"))
+                        .on_press(Message::ClearUsage)
+                        .into(),
+                );
+            }
+        }
+
+        widget::column::with_children(vec![
+            self.view_header(),
+            widget::scrollable(
+                widget::column::with_children(children)
+                    .spacing(8)
+                    .padding([8, 12]),
+            )
+            .into(),
+        ])
+        .into()
+    }
+
+    /// A shortcut with its explanation, all its bindings, where it comes from
+    /// and quick actions.
+    fn view_detail(&self) -> Element<'_, Message> {
+        let Some(shortcut) = &self.detail else {
+            return self.view_header();
+        };
+
+        let mut children =
+            vec![widget::text::title4(self.redacted_description(shortcut).into_owned()).into()];
+        if let Some(explanation) = details::explanation(shortcut) {
+            children.push(widget::text::body(explanation).into());
+        }
+        if let Some(change) = history::hint(shortcut) {
+            children.push(
+                widget::text::caption(fl!(
+                    "default-changed",
+                    was = change.was.as_str(),
+                    until = change.until.as_str()
+                ))
+                .into(),
+            );
+        }
+
+        children.push(widget::text::heading(fl!("detail-bindings")).into());
+        let label = shortcut.display_label();
+        for keys in label.split(" / ") {
+            children.push(keycaps(
+                keys.to_string(),
+                Some(self.category_color(shortcut.category)),
+            ));
+        }
+
+        children.push(widget::text::heading(fl!("detail-source")).into());
+        children.push(widget::text::body(shortcut.sources.join(", ")).into());
+
+        let entry = details::config_entry(shortcut);
+        if let Some(entry) = &entry {
+            children.push(widget::text::heading(fl!("detail-config-entry")).into());
+            children.push(
+                widget::text::caption(redact::redact(entry, &self.redactions).into_owned())
+                    .font(cosmic::iced_core::Font::MONOSPACE)
+                    .into(),
+            );
+        }
+
+        let mut actions = vec![
+            widget::button::standard(fl!("detail-copy-keys"))
+                .on_press(Message::CopyText(label.clone()))
+                .into(),
+        ];
+        if shortcut.category == ShortcutCategory::Custom {
+            actions.push(
+                widget::button::standard(if self.just_copied(&shortcut.command) {
+                    fl!("command-copied")
+                } else {
+                    fl!("detail-copy-command")
+                })
+                .on_press(Message::CopyCommand(shortcut.command.clone()))
+                .into(),
+            );
+        }
+        if let Some(entry) = entry {
+            let entry = redact::redact(&entry, &self.redactions).into_owned();
+            actions.push(
+                widget::button::standard(fl!("detail-copy-entry"))
+                    .on_press(Message::CopyText(entry))
+                    .into(),
+            );
+        }
+        children.push(widget::row::with_children(actions).spacing(8).wrap().into());
+
+        if self.config.allow_editing {
+            let hidden = self.config.hidden_shortcuts.contains(&shortcut.description);
+            let category_hidden = self.config.is_category_hidden(shortcut.category);
+            let visibility = vec![
+                widget::button::standard(if hidden {
+                    fl!("unhide-shortcut")
+                } else {
+                    fl!("hide-shortcut")
+                })
+                .on_press(Message::ToggleHiddenShortcut(shortcut.description.clone()))
+                .into(),
+                widget::button::standard(if category_hidden {
+                    fl!("unhide-category", category = shortcut.category.label())
+                } else {
+                    fl!("hide-category", category = shortcut.category.label())
+                })
+                .on_press(Message::ToggleHiddenCategory(shortcut.category))
+                .into(),
+            ];
+            children.push(
+                widget::row::with_children(visibility)
+                    .spacing(8)
+                    .wrap()
+                    .into(),
+            );
+        }
+
+        widget::column::with_children(vec![
+            self.view_header(),
+            widget::scrollable(
+                widget::column::with_children(children)
+                    .spacing(8)
+                    .padding([8, 12]),
+            )
+            .into(),
+        ])
+        .into()
+    }
+
+    /// The shortcut configuration files of the system and user layers as they
+    /// are on disk, read-only, to debug why a binding doesn't show as expected.
+    fn view_raw_config(&self) -> Element<'_, Message> {
+        let mut children = vec![widget::text::body(fl!("raw-config-description")).into()];
+        if self.raw_files.is_empty() {
+            children.push(widget::text::caption(fl!("raw-config-empty")).into());
+        }
+        for file in &self.raw_files {
+            let layer = match file.layer {
+                layers::Layer::System => fl!("raw-config-system"),
+                layers::Layer::User => fl!("raw-config-user"),
+            };
+            children.push(widget::text::heading(layer).into());
+            children.push(widget::text::caption(file.path.display().to_string()).into());
+            children.push(
+                widget::container(
+                    widget::text::caption(file.text.as_str())
+                        .font(cosmic::iced_core::Font::MONOSPACE)
+                        .wrapping(cosmic::iced::widget::text::Wrapping::Glyph),
+                )
+                .padding(8)
+                .width(cosmic::iced::Length::Fill)
+                .class(cosmic::theme::Container::Card)
+                .into(),
+            );
+        }
+
+        widget::column::with_children(vec![
+            self.view_header(),
+            widget::scrollable(
+                widget::column::with_children(children)
+                    .spacing(8)
+                    .padding([8, 12]),
+            )
+            .into(),
+        ])
+        .into()
+    }
+
+    /// A keyboard whose keys are tinted by how many bindings use them, to spot
+    /// overloaded keys and free ones.
+    fn view_keyboard(&self) -> Element<'_, Message> {
+        let scale = style::interface_scale();
+        let key_size = 30.0 * scale;
+        let key_spacing = 3.0 * scale;
+
+        let per_key: HashMap<_, _> = Stats::compute(&self.shortcuts)
+            .per_key
+            .into_iter()
+            .collect();
+        let max = per_key.values().copied().max().unwrap_or(1);
+
+        let rows = keyboard::ROWS
+            .iter()
+            .map(|row| {
+                let keys = row
+                    .iter()
+                    .map(|key| {
+                        let count = per_key.get(&key.keysym()).copied().unwrap_or(0);
+                        let width = f32::from(key.width) * (key_size + key_spacing) - key_spacing;
+                        widget::container(
+                            widget::column::with_children(vec![
+                                widget::text::caption(key.label).into(),
+                                widget::text::caption(count.to_string()).size(9).into(),
+                            ])
+                            .align_x(cosmic::iced::Alignment::Center),
+                        )
+                        .class(style::heat(count as f32 / max as f32))
+                        .center_x(width)
+                        .height(key_size + 6.0 * scale)
+                        .into()
+                    })
+                    .collect();
+                widget::row::with_children(keys).spacing(key_spacing).into()
+            })
+            .collect();
+
+        widget::column::with_children(vec![
+            self.view_header(),
+            widget::column::with_children(vec![
+                widget::text::body(fl!("keyboard-description")).into(),
+                widget::column::with_children(rows)
+                    .spacing(key_spacing)
+                    .into(),
+            ])
+            .spacing(12)
+            .padding([8, 12])
+            .into(),
+        ])
+        .into()
+    }
+
+    /// Side-by-side differences between this machine's shortcuts and a profile.
+    fn view_compare(&self) -> Element<'_, Message> {
+        let cell = |binding: Option<&format::Binding>| -> Element<'_, Message> {
+            let content: Element<'_, Message> = match binding {
+                Some(binding) => widget::column::with_children(vec![
+                    keycaps(
+                        binding
+                            .all_keys()
+                            .iter()
+                            .map(format::Keys::keys_label)
+                            .collect::<Vec<_>>()
+                            .join(" / "),
+                        None,
+                    ),
+                    widget::text::caption(binding.description.clone()).into(),
+                ])
+                .spacing(2)
+                .into(),
+                None => widget::text::body("—").into(),
+            };
+            widget::container(content)
+                .width(cosmic::iced::Length::FillPortion(1))
+                .into()
+        };
+
+        let mut rows = vec![
+            widget::row::with_children(vec![
+                widget::text::heading(fl!("compare-current"))
+                    .width(cosmic::iced::Length::FillPortion(1))
+                    .into(),
+                widget::text::heading(fl!("compare-profile"))
+                    .width(cosmic::iced::Length::FillPortion(1))
+                    .into(),
+            ])
+            .into(),
+        ];
+        if self.profile_changes.is_empty() {
+            rows.push(widget::text::body(fl!("compare-identical")).into());
+        }
+        for change in &self.profile_changes {
+            let (current, profile) = match change {
+                Change::Added(binding) => (None, Some(binding)),
+                Change::Removed(binding) => (Some(binding), None),
+                Change::Changed { current, profile } => (Some(current), Some(profile)),
+            };
+            rows.push(
+                widget::row::with_children(vec![cell(current), cell(profile)])
+                    .spacing(8)
+                    .into(),
+            );
+        }
+
+        widget::column::with_children(vec![
+            self.view_header(),
+            widget::scrollable(
+                widget::column::with_children(rows)
+                    .spacing(8)
+                    .padding([8, 12]),
+            )
+            .into(),
+        ])
+        .into()
+    }
+
+    /// Version, license and repository information, plus counts of loaded shortcuts.
+    fn view_about(&self) -> Element<'_, Message> {
+        let counts = widget::text::caption(fl!(
+            "loaded-shortcuts",
+            source = "COSMIC",
+            count = self.shortcuts.len()
+        ));
+
+        let about = widget::about(&self.about, |url| Message::OpenUrl(url.to_string()));
+        let mut about_children = vec![about, counts.into()];
+
+        if self.config.http_server && !self.config.http_token.is_empty() {
+            let host = if self.config.http_lan {
+                "<this-computer>"
+            } else {
+                "localhost"
+            };
+            let url = format!(
+                "http://{}:{}/?token={}",
+                host, self.config.http_port, self.config.http_token
+            );
+            about_children.push(
+                widget::text::caption(fl!("http-serving", url = url))
+                    .wrapping(cosmic::iced::widget::text::Wrapping::Glyph)
+                    .into(),
+            );
+        }
+
+        // Settings stay as provisioned in read-only deployments
+        if self.config.allow_editing {
+            about_children.push(widget::text::heading(fl!("panel-appearance")).into());
+            about_children.push(
+                widget::text_input(fl!("panel-icon"), &self.panel_icon_input)
+                    .on_input(Message::PanelIconInput)
+                    .on_submit(|_| Message::SavePanelIcon)
+                    .into(),
+            );
+            about_children.push(
+                widget::text_input(fl!("panel-label"), &self.config.panel_label)
+                    .on_input(Message::PanelLabelInput)
+                    .into(),
+            );
+
+            #[cfg(feature = "sheets")]
+            about_children.push(
+                widget::checkbox(fl!("check-updates"), self.config.check_updates)
+                    .on_toggle(Message::CheckUpdates)
+                    .into(),
+            );
+            about_children.push(
+                widget::checkbox(fl!("notify-actions"), self.config.notify_actions)
+                    .on_toggle(Message::NotifyActions)
+                    .into(),
+            );
+        }
+        #[cfg(feature = "sheets")]
+        about_children.extend(self.sheet_settings());
+
+        let hidden_count = self
+            .shortcuts
+            .iter()
+            .filter(|shortcut| self.config.is_hidden(shortcut))
+            .count();
+        about_children.push(
+            widget::checkbox(
+                fl!("show-hidden", count = hidden_count),
+                self.config.show_hidden,
+            )
+            .on_toggle(Message::ShowHidden)
+            .into(),
+        );
+        about_children.push(
+            widget::button::standard(fl!("raw-config"))
+                .on_press(Message::ShowPage(PopupPage::RawConfig))
+                .into(),
+        );
+
+        // Diagnostics of builds with the `timings` feature
+        if cfg!(feature = "timings") {
+            about_children.push(widget::text::heading(fl!("timings")).into());
+            about_children.push(
+                widget::text::caption(timings::report())
+                    .font(cosmic::iced_core::Font::MONOSPACE)
+                    .into(),
+            );
+        }
+
+        widget::column::with_children(vec![
+            self.view_header(),
+            widget::scrollable(
+                widget::column::with_children(about_children)
+                    .spacing(12)
+                    .padding([8, 12]),
+            )
+            .into(),
+        ])
+        .into()
+    }
+
+    /// First-run tour explaining the main features, one step at a time.
+    fn view_onboarding(&self) -> Element<'_, Message> {
+        let (title, body) = TOUR_STEPS[self.tour_step];
+        let is_last = self.tour_step + 1 == TOUR_STEPS.len();
+
+        let next = if is_last {
+            widget::button::suggested(fl!("tour-done")).on_press(Message::TourFinish)
+        } else {
+            widget::button::suggested(fl!("tour-next")).on_press(Message::TourNext)
+        };
+
+        let controls = widget::row::with_children(vec![
+            widget::checkbox(fl!("tour-dont-show-again"), self.tour_dont_show_again)
+                .on_toggle(Message::TourDontShowAgain)
+                .width(cosmic::iced::Length::Fill)
+                .into(),
+            widget::button::text(fl!("tour-skip"))
+                .on_press(Message::TourFinish)
+                .into(),
+            next.into(),
+        ])
+        .spacing(8)
+        .align_y(cosmic::iced::Alignment::Center);
+
+        widget::column::with_children(vec![
+            widget::text::caption(fl!(
+                "tour-progress",
+                step = self.tour_step + 1,
+                total = TOUR_STEPS.len()
+            ))
+            .into(),
+            widget::text::title4(i18n::LANGUAGE_LOADER.get(title)).into(),
+            widget::text::body(i18n::LANGUAGE_LOADER.get(body))
+                .wrapping(cosmic::iced::widget::text::Wrapping::Word)
+                .into(),
+            controls.into(),
+        ])
+        .spacing(12)
+        .padding([16, 12])
+        .into()
+    }
+
+    /// A single shortcut: binding in bold on top, description below, either
+    /// wrapped or cut to one line with the full text in a tooltip.
+    ///
+    /// Custom (spawn) shortcuts also show their command in monospace with a copy button,
+    /// since their descriptions are often just the command itself or a terse label.
+    fn shortcut_row<'a>(&'a self, index: usize, shortcut: &'a KeyBinding) -> Element<'a, Message> {
+        let revealed = self.revealed_commands.contains(&shortcut.command);
+
+        // Spawn shortcuts without a description fall back to the command itself.
+        let description = self.redacted_description(shortcut);
+        let full_description = description.clone();
+        // Touch screens can't hover, so the full text has to be in the row
+        let touch = self.touch_layout();
+        let description = if self.config.single_line_rows && !touch {
+            truncate(&description, ROW_MAX_DESCRIPTION_CHARS)
+        } else {
+            description.into_owned()
+        };
+        let truncated = description.len() != full_description.len();
+
+        // A warning replaces the icon of shortcuts that can't run
+        let icon = if shortcut.command_missing {
+            Some("dialog-warning-symbolic")
+        } else {
+            shortcut.icon.as_deref()
+        };
+        let light = self.config.light_rendering;
+        let label = if light {
+            let text = widget::text::body(shortcut.display_label()).font(cosmic::iced_core::Font {
+                weight: cosmic::iced_core::font::Weight::Bold,
+                ..Default::default()
+            });
+            if self.selected_row == Some(index) {
+                text.class(cosmic::theme::Text::Accent).into()
+            } else {
+                text.into()
+            }
+        } else if shortcut.tap && shortcut.keybind_display.is_none() {
+            // A bare modifier tap: its keycap, then how to press it
+            widget::row::with_children(vec![
+                keycaps(
+                    shortcut.modifiers.to_string(),
+                    Some(self.category_color(shortcut.category)),
+                ),
+                widget::text::caption(fl!("modifier-tap")).into(),
+            ])
+            .spacing(8)
+            .align_y(cosmic::iced::Alignment::Center)
+            .into()
+        } else {
+            keycaps(
+                shortcut.display_label(),
+                Some(self.category_color(shortcut.category)),
+            )
+        };
+        // Icons given as image paths may be SVGs, which light rendering avoids
+        let icon = icon.filter(|icon| !(light && icon.starts_with('/')));
+        let keys = match icon {
+            Some(icon) => widget::row::with_children(vec![row_icon(icon, light), label])
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .into(),
+            None => label,
+        };
+        // Tell the user's own COSMIC bindings from the system defaults
+        let keys = match shortcut.layer {
+            Some(layer) => {
+                let tag = match layer {
+                    layers::Layer::System => fl!("layer-default"),
+                    layers::Layer::User => fl!("layer-custom"),
+                };
+                widget::row::with_children(vec![keys, widget::text::caption(tag).into()])
+                    .spacing(8)
+                    .align_y(cosmic::iced::Alignment::Center)
+                    .into()
+            }
+            None => keys,
+        };
+
+        let description: Element<'a, Message> = if truncated {
+            widget::tooltip(
+                widget::text::body(description)
+                    .wrapping(cosmic::iced::widget::text::Wrapping::None),
+                widget::text::body(format!(
+                    "{}\n{}",
+                    shortcut.display_label(),
+                    full_description
+                )),
+                widget::tooltip::Position::Bottom,
+            )
+            .into()
+        } else {
+            widget::text::body(description)
+                .wrapping(cosmic::iced::widget::text::Wrapping::Word)
+                .into()
+        };
+        let mut children = vec![keys, description];
+
+        if shortcut.command_missing {
+            let program = command::program(&shortcut.command).unwrap_or_default();
+            children.push(
+                widget::text::caption(fl!("executable-not-found", program = program))
+                    .class(cosmic::theme::Text::Accent)
+                    .into(),
+            );
+        }
+
+        if let Some(change) = history::hint(shortcut) {
+            children.push(
+                widget::text::caption(fl!(
+                    "default-changed",
+                    was = change.was.as_str(),
+                    until = change.until.as_str()
+                ))
+                .into(),
+            );
+        }
+
+        // Only listed while hidden shortcuts are shown
+        if self.config.is_hidden(shortcut) {
+            children.push(
+                widget::text::caption(fl!("hidden"))
+                    .class(cosmic::theme::Text::Accent)
+                    .into(),
+            );
+        }
+
+        if shortcut.sources.len() > 1 {
+            children.push(
+                widget::text::caption(fl!("defined-in", sources = shortcut.sources.join(", ")))
+                    .into(),
+            );
+        }
+
+        if shortcut.category == ShortcutCategory::Custom {
+            let command = if revealed {
+                std::borrow::Cow::Borrowed(shortcut.command.as_str())
+            } else {
+                redact::redact(&shortcut.command, &self.redactions)
+            };
+            let is_redacted = command != shortcut.command;
+
+            let mut preview = vec![command_preview(&command)];
+            if revealed || is_redacted {
+                let icon = if revealed {
+                    "view-conceal-symbolic"
+                } else {
+                    "view-reveal-symbolic"
+                };
+                let reveal = widget::button::icon(widget::icon::from_name(icon))
+                    .on_press(Message::RevealCommand(shortcut.command.clone()));
+                preview.push(if touch {
+                    reveal.padding(TOUCH_BUTTON_PADDING).into()
+                } else {
+                    reveal.into()
+                });
+            }
+            let copy = widget::button::icon(self.copy_icon(&shortcut.command))
+                .on_press(Message::CopyCommand(shortcut.command.clone()));
+            preview.push(if touch {
+                copy.padding(TOUCH_BUTTON_PADDING).into()
+            } else {
+                copy.into()
+            });
+            let preview = widget::row::with_children(preview)
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center);
+            children.push(preview.into());
+        }
+
+        // Row heights follow the interface density like the text does, and
+        // grow to a comfortable tap target in the touch layout
+        let scale = style::interface_scale();
+        let padding = if touch {
+            [16.0 * scale, 16.0 * scale]
+        } else {
+            [8.0 * scale, 12.0 * scale]
+        };
+
+        // Light rendering skips the styled container and hover tracking
+        if light {
+            let row = widget::column::with_children(children)
+                .spacing(4)
+                .padding(padding)
+                .width(cosmic::iced::Length::Fill);
+            return widget::mouse_area(row)
+                .on_press(Message::OpenDetail(index))
+                .into();
+        }
+
+        let row = widget::container(widget::column::with_children(children).spacing(4))
+            .padding(padding)
+            .width(cosmic::iced::Length::Fill)
+            .class(style::row(
+                index % 2 == 1,
+                self.hovered_row == Some(index),
+                self.selected_row == Some(index),
+            ));
+
+        if touch {
+            return widget::mouse_area(row)
+                .on_release(Message::OpenDetail(index))
+                .into();
+        }
+
+        widget::mouse_area(row)
+            .on_enter(Message::HoverRow(Some(index)))
+            .on_exit(Message::HoverRow(None))
+            .on_press(Message::OpenDetail(index))
+            .into()
+    }
+}
+
+/// Loads shortcuts from every source and merges bindings listed more than once.
+/// Also returns the custom sheets that failed to load.
+fn load_shortcuts(config: &Config) -> Loaded {
+    let loaded = load_unmerged_shortcuts(config);
+    Loaded {
+        shortcuts: dedup_bindings(loaded.shortcuts, config.merge_policy),
+        ..loaded
+    }
+}
+
+/// Shortcuts of every source, and what went wrong loading them.
+struct Loaded {
+    shortcuts: Vec<KeyBinding>,
+    #[cfg(feature = "sheets")]
+    sheet_errors: Vec<SheetError>,
+    /// Why some or all COSMIC shortcuts are missing, if they are.
+    error: Option<ShortcutsError>,
+}
+
+impl Loaded {
+    /// Prints the problems for the command line.
+    fn report(&self) {
+        if let Some(e) = &self.error {
+            eprintln!("{e}\n{}", e.remedy());
+        }
+        #[cfg(feature = "sheets")]
+        for error in &self.sheet_errors {
+            eprintln!("{}: {}", error.path.display(), error);
+        }
+    }
+}
+
+/// Loads shortcuts from every source, keeping bindings listed more than once.
+fn load_unmerged_shortcuts(config: &Config) -> Loaded {
+    let load = || load_cosmic_shortcuts(config.primary_binding);
+    let (mut shortcuts, error) = match cache::load_shared(config.primary_binding, load) {
+        // A layer that can't be parsed leaves the other one listed
+        Ok(shortcuts) => (shortcuts, layer_parse_failure()),
+        Err(e) => {
+            log::error!("Failed to load cosmic shortcuts: {}", e);
+            (Vec::new(), Some(e))
+        }
+    };
+    #[cfg(feature = "sheets")]
+    let sheet_errors = {
+        let started = Instant::now();
+        let (sheet_shortcuts, sheet_errors) = sheets::load_sheets(config.sheets_dir());
+        timings::record("sheets", started);
+        shortcuts.extend(sheet_shortcuts);
+        sheet_errors
+    };
+    assign_spawn_groups(&mut shortcuts, &config.spawn_groups);
+    Loaded {
+        shortcuts,
+        #[cfg(feature = "sheets")]
+        sheet_errors,
+        error,
+    }
+}
+
+/// Runs `--check-conflicts`, printing key combinations bound to different
+/// actions and returning the exit code.
+pub fn check_conflicts_cli(config: &Config, json: bool) -> i32 {
+    let loaded = load_unmerged_shortcuts(config);
+    loaded.report();
+    let shortcuts = loaded.shortcuts;
+
+    let redactions = redact::compile_patterns(&config.redaction_patterns);
+    let mut conflicts = conflicts::find(&shortcuts);
+    for binding in conflicts.iter_mut().flat_map(|c| c.bindings.iter_mut()) {
+        if let Some(command) = &mut binding.command {
+            *command = redact::redact(command, &redactions).into_owned();
+            binding.description = redact::redact(&binding.description, &redactions).into_owned();
+        }
+    }
+
+    let code = i32::from(!conflicts.is_empty());
+    if json {
+        let report = conflicts::Report::new(conflicts);
+        match serde_json::to_string_pretty(&report) {
+            Ok(text) => println!("{text}"),
+            Err(e) => {
+                eprintln!("failed to encode the report: {e}");
+                return 2;
+            }
+        }
+    } else if conflicts.is_empty() {
+        println!("No conflicting shortcuts");
+    } else {
+        for conflict in &conflicts {
+            println!("{}:", conflict.keys);
+            for binding in &conflict.bindings {
+                println!("  {} ({})", binding.description, binding.sources.join(", "));
+            }
+        }
+    }
+    code
+}
+
+/// Per-category sections for export, with descriptions from `description`.
+fn export_sections(
+    shortcuts: &[&KeyBinding],
+    description: impl Fn(&KeyBinding) -> String,
+) -> Vec<ExportSection> {
+    group_by_category(shortcuts)
+        .into_iter()
+        .map(|(category, rows)| ExportSection {
+            title: category.label().to_string(),
+            rows: rows
+                .into_iter()
+                .map(|shortcut| ExportRow {
+                    keys: shortcut.display_label(),
+                    description: description(shortcut),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Loads every source and writes the cheat sheet to `path` without opening a
+/// window, for `--render-html` and `--render-md`. Returns the exit code.
+pub fn render_cli(
+    config: &Config,
+    render: fn(&str, &[ExportSection]) -> String,
+    path: &std::path::Path,
+) -> i32 {
+    let loaded = load_shortcuts(config);
+    loaded.report();
+    let shortcuts = loaded.shortcuts;
+
+    let redactions = redact::compile_patterns(&config.redaction_patterns);
+    let shortcuts: Vec<&KeyBinding> = shortcuts.iter().collect();
+    let sections = export_sections(&shortcuts, |shortcut| {
+        if shortcut.category == ShortcutCategory::Custom {
+            redact::redact(&shortcut.description, &redactions).into_owned()
+        } else {
+            shortcut.description.clone()
+        }
+    });
+
+    match std::fs::write(path, render(&fl!("app-title"), &sections)) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("failed to write {}: {e}", path.display());
+            1
+        }
+    }
+}
+
+/// Runs `--export-json`: writes every shortcut, as the applet would list
+/// them, in the exchange format.
+pub fn export_json_cli(config: &Config, path: &std::path::Path) -> i32 {
+    let loaded = load_shortcuts(config);
+    loaded.report();
+
+    let redactions = redact::compile_patterns(&config.redaction_patterns);
+    let bindings = loaded
+        .shortcuts
+        .iter()
+        .map(|shortcut| {
+            let mut binding = format::Binding::from_shortcut(shortcut);
+            if let Some(command) = &mut binding.command {
+                *command = redact::redact(command, &redactions).into_owned();
+            }
+            if shortcut.category == ShortcutCategory::Custom {
+                binding.description =
+                    redact::redact(&shortcut.description, &redactions).into_owned();
+            }
+            binding
+        })
+        .collect();
+
+    match std::fs::write(path, format::Document::new(bindings).to_json()) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("failed to write {}: {e}", path.display());
+            1
+        }
+    }
+}
+
+#[cfg(feature = "timings")]
+/// Runs `--timings`: loads and groups the shortcuts as the applet would and
+/// prints how long each stage took.
+pub fn timings_cli(config: &Config) -> i32 {
+    let shortcuts = load_shortcuts(config).shortcuts;
+    let started = Instant::now();
+    let shortcuts: Vec<&KeyBinding> = shortcuts.iter().collect();
+    let sections = group_by_category(&shortcuts);
+    timings::record("grouping", started);
+
+    print!("{}", timings::report());
+    println!(
+        "{} shortcuts in {} sections",
+        shortcuts.len(),
+        sections.len()
+    );
+    0
+}
+
+/// Splits shortcuts into per-category sections, in the categories' display order.
+fn group_by_category<'a>(
+    shortcuts: &[&'a KeyBinding],
+) -> Vec<(ShortcutCategory, Vec<&'a KeyBinding>)> {
+    ShortcutCategory::all()
+        .iter()
+        .map(|category| {
+            let mut rows = shortcuts
+                .iter()
+                .copied()
+                .filter(|shortcut| shortcut.category == *category)
+                .collect::<Vec<_>>();
+            // Keep grouped rows together, ungrouped ones last; the sort is stable
+            // so rows stay ordered by relevance within each group.
+            rows.sort_by(|a, b| (a.group.is_none(), &a.group).cmp(&(b.group.is_none(), &b.group)));
+            (*category, rows)
+        })
+        .filter(|(_, rows)| !rows.is_empty())
+        .collect()
+}
+
+/// A spawn command in monospace, normalized for reading, with flags highlighted.
+fn command_preview<'a>(command: &str) -> Element<'a, Message> {
+    let words = command::display_tokens(command)
+        .into_iter()
+        .map(|token| {
+            let text = widget::text::caption(token.text).font(cosmic::iced_core::Font::MONOSPACE);
+            let text = match token.kind {
+                TokenKind::Program => text.font(cosmic::iced_core::Font {
+                    weight: cosmic::iced_core::font::Weight::Bold,
+                    ..cosmic::iced_core::Font::MONOSPACE
+                }),
+                TokenKind::Flag => text.class(cosmic::theme::Text::Accent),
+                TokenKind::Argument => text,
+            };
+            text.into()
+        })
+        .collect();
+
+    widget::row::with_children(words)
+        .spacing(6)
+        .wrap()
+        .width(cosmic::iced::Length::Fill)
+        .into()
+}
+
+/// Small icon for a row, from a themed icon name or an image path. With
+/// `prefer_bitmap`, PNG theme icons are picked over SVG ones.
+fn row_icon<'a>(icon: &str, prefer_bitmap: bool) -> Element<'a, Message> {
+    if icon.starts_with('/') {
+        widget::icon(widget::icon::from_path(PathBuf::from(icon)))
+            .size(16)
+            .into()
+    } else {
+        widget::icon::from_name(icon)
+            .size(16)
+            .prefer_svg(!prefer_bitmap)
+            .icon()
+            .into()
+    }
+}
+
+/// Sub-heading for a group of custom commands within a section.
+fn group_header<'a>(group: Option<&str>) -> Element<'a, Message> {
+    let label = group.map_or_else(|| fl!("spawn-group-other"), str::to_string);
+    widget::container(widget::text::caption_heading(label))
+        .padding([6, 12, 2, 36])
+        .into()
+}
+
+/// Splits shortcuts into one section per set of modifiers, fewest modifiers first.
+fn group_by_modifiers<'a>(shortcuts: &[&'a KeyBinding]) -> Vec<(Modifiers, Vec<&'a KeyBinding>)> {
+    let mut sections: Vec<(Modifiers, Vec<&'a KeyBinding>)> = Vec::new();
+    for shortcut in shortcuts {
+        match sections.iter_mut().find(|(m, _)| *m == shortcut.modifiers) {
+            Some((_, rows)) => rows.push(shortcut),
+            None => sections.push((shortcut.modifiers.clone(), vec![shortcut])),
+        }
+    }
+
+    // Order like the keys are displayed: Super before Ctrl before Alt before Shift.
+    sections.sort_by_key(|(m, _)| m.sort_key());
+    sections
+}
+
+/// Shortens `text` to at most `max_chars` characters, ending with an ellipsis if cut.
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Draws a binding label such as "Super + Shift + Q / Ctrl + W" as keycaps,
+/// keeping the "+" and "/" separators as plain text between them.
+fn keycaps<'a>(label: String, color: Option<style::CategoryColor>) -> Element<'a, Message> {
+    let mut children: Vec<Element<'a, Message>> = Vec::new();
+
+    for (i, alternative) in label.split(" / ").enumerate() {
+        if i > 0 {
+            children.push(widget::text::body("/").into());
+        }
+        for (j, key) in alternative.split(" + ").enumerate() {
+            if j > 0 {
+                children.push(widget::text::caption("+").into());
+            }
+            children.push(
+                widget::container(widget::text::body(key.to_string()).font(
+                    cosmic::iced_core::Font {
+                        weight: cosmic::iced_core::font::Weight::Bold,
+                        ..Default::default()
+                    },
+                ))
+                .class(color.map_or_else(style::keycap, style::category_keycap))
+                .padding([2, 6])
+                .into(),
+            );
+        }
+    }
+
+    widget::row::with_children(children)
+        .spacing(4)
+        .align_y(cosmic::iced::Alignment::Center)
+        .wrap()
+        .into()
+}
+
+/// Heading shown above each section; clicking it collapses the section.
+///
+/// Category sections get a marker in the color of the category.
+fn section_header<'a>(
+    section: Section,
+    collapsed: bool,
+    color: Option<style::CategoryColor>,
+) -> Element<'a, Message> {
+    let chevron = if collapsed {
+        "go-next-symbolic"
+    } else {
+        "go-down-symbolic"
+    };
+
+    let mut children = vec![widget::icon::from_name(chevron).size(16).icon().into()];
+    if let Some(color) = color {
+        children.push(
+            widget::container(widget::Space::new(4, 16))
+                .class(style::category_marker(color))
+                .into(),
+        );
+    }
+    children.push(
+        widget::text::heading(section.label())
+            .class(cosmic::theme::Text::Accent)
+            .into(),
+    );
+
+    widget::button::custom(
+        widget::row::with_children(children)
+            .spacing(8)
+            .align_y(cosmic::iced::Alignment::Center),
+    )
+    .class(cosmic::theme::Button::Text)
+    .on_press(Message::ToggleSection(section))
+    .padding([8, 12, 4, 12])
+    .width(cosmic::iced::Length::Fill)
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmic::Application;
+
+    fn shortcut(description: &str, key: char, category: ShortcutCategory) -> KeyBinding {
+        let modifiers = Modifiers {
+            logo: true,
+            ..Modifiers::new()
+        };
+        KeyBinding {
+            category,
+            sources: vec![String::from("test")],
+            ..KeyBinding::for_test(modifiers, Some(key as u32), description)
+        }
+    }
+
+    fn app(shortcuts: Vec<KeyBinding>) -> AppModel {
+        let flags = Flags {
+            standalone: true,
+            provider: Provider::Fixed(shortcuts),
+        };
+        AppModel::init(cosmic::Core::default(), flags).0
+    }
+
+    fn descriptions(app: &AppModel) -> Vec<&str> {
+        app.filtered_shortcuts()
+            .iter()
+            .map(|shortcut| shortcut.description.as_str())
+            .collect()
+    }
+
+    fn sample() -> Vec<KeyBinding> {
+        vec![
+            shortcut("Close window", 'q', ShortcutCategory::WindowManagement),
+            shortcut("Lock the screen", 'l', ShortcutCategory::SystemActions),
+            shortcut("Zoom in", 'z', ShortcutCategory::Accessibility),
+        ]
+    }
+
+    #[test]
+    fn search_narrows_the_list() {
+        let mut app = app(sample());
+        assert_eq!(descriptions(&app).len(), 3);
+
+        let _ = app.update(Message::SearchInput(String::from("lock")));
+        assert_eq!(descriptions(&app), ["Lock the screen"]);
+        let _ = app.view_page();
+
+        let _ = app.update(Message::SearchInput(String::new()));
+        assert_eq!(descriptions(&app).len(), 3);
+    }
+
+    #[test]
+    fn read_only_ignores_changes() {
+        let mut app = app(sample());
+        let dir = std::env::temp_dir().join(format!("keypeek-read-only-{}", std::process::id()));
+        app.config.allow_editing = false;
+        app.config.sync_dir = dir.to_string_lossy().into_owned();
+        app.config.search_history = vec![String::from("lock")];
+        app.config.saved_searches = vec![
+            QuickFilter::new("Saved", "lock", &[]),
+            QuickFilter::new("Other", "close", &[]),
+        ];
+        let config = app.config.clone();
+
+        #[allow(unused_mut)]
+        let mut messages = vec![
+            Message::ExportProfile,
+            Message::PanelIconInput(String::from("edit-symbolic")),
+            Message::SavePanelIcon,
+            Message::PanelLabelInput(String::from("Keys")),
+            Message::ToggleHiddenShortcut(String::from("Close window")),
+            Message::ToggleHiddenCategory(ShortcutCategory::SystemActions),
+            Message::EditSavedSearches(true),
+            Message::SavedSearchNameInput(String::from("New")),
+            Message::SaveSearch,
+            Message::MoveSavedSearch(0, 1),
+            Message::RemoveSavedSearch(0),
+            Message::ClearSearchHistory,
+            Message::UsageStats(true),
+            Message::ClearUsage,
+            Message::NotifyActions(true),
+        ];
+        #[cfg(feature = "export")]
+        messages.push(Message::ExportImage(ImageFormat::Png));
+        #[cfg(feature = "sheets")]
+        messages.extend([
+            Message::CheckUpdates(true),
+            Message::Sheets(SheetMessage::CommunitySheets(true)),
+        ]);
+        for message in messages {
+            let _ = app.update(message);
+        }
+
+        assert_eq!(app.config, config);
+        assert!(app.saved_search_name.is_none());
+        assert!(app.export_status.is_none());
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn toggling_a_category_hides_its_rows() {
+        let mut app = app(sample());
+        let _ = app.update(Message::ToggleCategory(ShortcutCategory::SystemActions));
+        assert!(!descriptions(&app).contains(&"Lock the screen"));
+        assert_eq!(app.list_sections().len(), 2);
+
+        let _ = app.update(Message::ToggleCategory(ShortcutCategory::SystemActions));
+        assert!(descriptions(&app).contains(&"Lock the screen"));
+    }
+
+    #[test]
+    fn swiping_steps_through_categories_with_shortcuts() {
+        let mut app = app(sample());
+        app.config.layout = Layout::Touch;
+        let swipe = |app: &mut AppModel, dx: f32| {
+            let _ = app.update(Message::TouchPressed(Point::new(200.0, 300.0)));
+            let _ = app.update(Message::TouchLifted(Point::new(200.0 + dx, 310.0)));
+        };
+
+        swipe(&mut app, -120.0);
+        assert_eq!(descriptions(&app), ["Close window"]);
+        swipe(&mut app, -120.0);
+        assert_eq!(descriptions(&app), ["Lock the screen"]);
+        swipe(&mut app, 120.0);
+        swipe(&mut app, 120.0);
+        assert_eq!(descriptions(&app), ["Zoom in"]);
+
+        // A short or mostly vertical move is a tap or a scroll
+        swipe(&mut app, 30.0);
+        assert_eq!(descriptions(&app), ["Zoom in"]);
+        let _ = app.view_page();
+    }
+
+    #[test]
+    fn only_a_list_at_its_top_can_be_pulled() {
+        let mut app = app(sample());
+        let _ = app.update(Message::TouchPressed(Point::new(100.0, 100.0)));
+        let _ = app.update(Message::TouchMoved(Point::new(105.0, 250.0)));
+        assert!(app.pull_distance > PULL_DISTANCE);
+        let _ = app.view_page();
+        let _ = app.update(Message::TouchLifted(Point::new(105.0, 250.0)));
+        assert_eq!(app.pull_distance, 0.0);
+        assert_eq!(app.shortcuts.len(), 3);
+
+        let _ = app.update(Message::ListScrolled(0.5, 0.5));
+        let _ = app.update(Message::TouchPressed(Point::new(100.0, 100.0)));
+        let _ = app.update(Message::TouchMoved(Point::new(100.0, 250.0)));
+        assert_eq!(app.pull_distance, 0.0);
+    }
+
+    #[test]
+    fn copied_commands_are_confirmed_for_a_moment() {
+        let mut app = app(sample());
+        let _ = app.update(Message::CopyCommand(String::from("firefox")));
+        assert!(app.just_copied("firefox"));
+        assert!(!app.just_copied("kitty"));
+
+        let (_, started) = app.copied.clone().unwrap();
+        let _ = app.update(Message::AnimationFrame(started + CONFIRMATION_DURATION / 2));
+        assert!(app.just_copied("firefox"));
+        let _ = app.update(Message::AnimationFrame(started + CONFIRMATION_DURATION));
+        assert!(!app.just_copied("firefox"));
+    }
+
+    #[test]
+    fn fixed_shortcuts_are_merged_like_loaded_ones() {
+        let mut shortcuts = sample();
+        shortcuts.push(shortcut(
+            "Close window",
+            'q',
+            ShortcutCategory::WindowManagement,
+        ));
+        let app = app(shortcuts);
+        assert_eq!(app.shortcuts.len(), 3);
+        assert!(app.config_handler.is_none());
     }
 }
@@ -0,0 +1,451 @@
+// SPDX-License-Identifier: MIT
+
+//! Custom and community sheets in the popup: creating, checking, importing
+//! dropped files and browsing the community index. Built with the `sheets`
+//! feature.
+
+use super::{AppModel, Message, PopupPage};
+use crate::community;
+use crate::fl;
+use crate::sheets::{self, SheetError, SheetInfo};
+use cosmic::prelude::*;
+use cosmic::widget;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub enum SheetMessage {
+    CheckSheets,
+    NewSheet,
+    NewSheetFinished(Result<(), String>),
+    FileDropped(PathBuf),
+    ConfirmImport,
+    DismissImport,
+    CommunitySheets(bool),
+    CommunityIndexLoaded(Result<Vec<community::Entry>, String>),
+    PreviewCommunitySheet(community::Entry),
+    PreviewBundledSheet(usize),
+    CommunitySheetLoaded(Result<community::Sheet, String>),
+    InstallCommunitySheet,
+}
+
+/// A sheet file dropped onto the popup or window.
+#[derive(Debug, Clone)]
+pub enum SheetImport {
+    /// Valid and waiting for the user to confirm.
+    Pending(sheets::Import),
+    Invalid(SheetError),
+    Done(Result<PathBuf, String>),
+}
+
+impl AppModel {
+    pub(super) fn update_sheets(&mut self, message: SheetMessage) -> Task<cosmic::Action<Message>> {
+        match message {
+            SheetMessage::CheckSheets => {
+                self.sheet_check = Some(sheets::check_sheets(self.config.sheets_dir()));
+            }
+            SheetMessage::NewSheet if !self.config.allow_editing => {}
+            SheetMessage::NewSheet => {
+                self.new_sheet_error = None;
+                match sheets::create_sheet(self.config.sheets_dir()) {
+                    Ok(path) => {
+                        return Task::perform(sheets::open_sheet(path), |result| {
+                            cosmic::Action::App(Message::Sheets(SheetMessage::NewSheetFinished(
+                                result.map_err(|e| format!("{:#}", e)),
+                            )))
+                        });
+                    }
+                    Err(e) => {
+                        log::error!("Failed to create sheet: {:#}", e);
+                        self.new_sheet_error = Some(format!("{:#}", e));
+                    }
+                }
+            }
+            SheetMessage::NewSheetFinished(result) => {
+                if let Err(e) = result {
+                    log::error!("Failed to create sheet: {}", e);
+                    self.new_sheet_error = Some(e);
+                }
+            }
+            SheetMessage::FileDropped(_) if !self.config.allow_editing => {}
+            SheetMessage::FileDropped(path) => {
+                let import = sheets::prepare_import(&path, self.config.sheets_dir());
+                self.sheet_import = Some(match import {
+                    Ok(import) => SheetImport::Pending(import),
+                    Err(e) => {
+                        log::warn!("Dropped sheet {} is invalid: {}", path.display(), e);
+                        SheetImport::Invalid(e)
+                    }
+                });
+            }
+            SheetMessage::ConfirmImport if !self.config.allow_editing => {}
+            SheetMessage::ConfirmImport => {
+                self.record_feature("import-sheet");
+                if let Some(SheetImport::Pending(import)) = &self.sheet_import {
+                    let result = sheets::import_sheet(import, self.config.sheets_dir())
+                        .map_err(|e| format!("{:#}", e));
+                    if let Err(e) = &result {
+                        log::error!("Failed to import sheet: {}", e);
+                    }
+                    // The sheets watcher reloads the list.
+                    self.sheet_import = Some(SheetImport::Done(result));
+                }
+            }
+            SheetMessage::DismissImport => {
+                self.sheet_import = None;
+            }
+            SheetMessage::CommunitySheets(_) if !self.config.allow_editing => {}
+            SheetMessage::CommunitySheets(enabled) => match &self.config_handler {
+                Some(handler) => {
+                    if let Err(e) = self.config.set_community_sheets(handler, enabled) {
+                        log::error!("Failed to save community sheets setting: {}", e);
+                    }
+                }
+                None => self.config.community_sheets = enabled,
+            },
+            SheetMessage::CommunityIndexLoaded(result) => {
+                if let Err(e) = &result {
+                    log::error!("Failed to load the community sheet index: {}", e);
+                }
+                self.community_index = Some(result);
+            }
+            SheetMessage::PreviewCommunitySheet(entry) => {
+                self.community_preview = None;
+                self.community_status = None;
+                return Task::perform(community::fetch_sheet(entry), |result| {
+                    cosmic::Action::App(Message::Sheets(SheetMessage::CommunitySheetLoaded(
+                        result.map_err(|e| format!("{:#}", e)),
+                    )))
+                });
+            }
+            SheetMessage::PreviewBundledSheet(index) => {
+                self.community_status = None;
+                self.community_preview = self.bundled_sheets.get(index).cloned().map(Ok);
+            }
+            SheetMessage::CommunitySheetLoaded(result) => {
+                if let Err(e) = &result {
+                    log::error!("Failed to download community sheet: {}", e);
+                }
+                self.community_preview = Some(result);
+            }
+            SheetMessage::InstallCommunitySheet if !self.config.allow_editing => {}
+            SheetMessage::InstallCommunitySheet => {
+                self.record_feature("install-community-sheet");
+                if let Some(Ok(sheet)) = &self.community_preview {
+                    let result = sheet
+                        .install(self.config.sheets_dir())
+                        .map_err(|e| format!("{:#}", e));
+                    if let Err(e) = &result {
+                        log::error!("Failed to install community sheet: {}", e);
+                    }
+                    // The sheets watcher reloads the list.
+                    self.community_status = Some(result);
+                }
+            }
+        }
+        Task::none()
+    }
+
+    /// Resets the community page as it opens and fetches the index, if browsing
+    /// is on.
+    pub(super) fn open_community(&mut self) -> Task<cosmic::Action<Message>> {
+        self.bundled_sheets = community::bundled();
+        self.community_preview = None;
+        self.community_status = None;
+        if !self.config.community_sheets {
+            return Task::none();
+        }
+
+        self.community_index = None;
+        let url = self.config.community_index_url.clone();
+        Task::perform(community::fetch_index(url), |result| {
+            cosmic::Action::App(Message::Sheets(SheetMessage::CommunityIndexLoaded(
+                result.map_err(|e| format!("{:#}", e)),
+            )))
+        })
+    }
+
+    /// Custom sheets that failed to load, for the warnings above the list.
+    pub(super) fn sheet_warnings(&self) -> impl Iterator<Item = Element<'_, Message>> {
+        self.sheet_errors.iter().map(|error| {
+            widget::text::caption(fl!(
+                "sheet-error",
+                file = error.file_name(),
+                message = error.to_string()
+            ))
+            .class(cosmic::theme::Text::Accent)
+            .into()
+        })
+    }
+
+    /// Sheet buttons and settings of the about page, with the result of the
+    /// last sheet check.
+    pub(super) fn sheet_settings(&self) -> Vec<Element<'_, Message>> {
+        let mut children = Vec::new();
+
+        let mut sheet_buttons: Vec<Element<'_, Message>> = Vec::new();
+        if self.config.allow_editing {
+            sheet_buttons.push(
+                widget::button::standard(fl!("new-sheet"))
+                    .on_press(Message::Sheets(SheetMessage::NewSheet))
+                    .into(),
+            );
+        }
+        sheet_buttons.push(
+            widget::button::standard(fl!("check-sheets"))
+                .on_press(Message::Sheets(SheetMessage::CheckSheets))
+                .into(),
+        );
+        children.push(widget::row::with_children(sheet_buttons).spacing(8).into());
+
+        if self.config.allow_editing {
+            children.push(
+                widget::checkbox(fl!("community-enable"), self.config.community_sheets)
+                    .on_toggle(|enabled| Message::Sheets(SheetMessage::CommunitySheets(enabled)))
+                    .into(),
+            );
+        }
+        if self.config.community_sheets || cfg!(feature = "bundled-sheets") {
+            children.push(
+                widget::button::standard(fl!("community-browse"))
+                    .on_press(Message::ShowPage(PopupPage::Community))
+                    .into(),
+            );
+        }
+        if let Some(e) = &self.new_sheet_error {
+            children.push(
+                widget::text::caption(fl!("new-sheet-failed", error = e.as_str()))
+                    .class(cosmic::theme::Text::Accent)
+                    .into(),
+            );
+        }
+        if let Some(problems) = &self.sheet_check {
+            if problems.is_empty() {
+                children.push(widget::text::caption(fl!("check-sheets-ok")).into());
+            }
+            for problem in problems {
+                children.push(
+                    widget::text::caption(fl!(
+                        "sheet-error",
+                        file = problem.file_name(),
+                        message = problem.to_string()
+                    ))
+                    .class(cosmic::theme::Text::Accent)
+                    .into(),
+                );
+            }
+        }
+        children
+    }
+
+    /// Confirmation or result of importing a dropped sheet.
+    pub(super) fn view_import(&self, import: &SheetImport) -> Element<'_, Message> {
+        let (message, confirm) = match import {
+            SheetImport::Pending(import) => (
+                fl!(
+                    "import-sheet-confirm",
+                    name = import.info.name.as_str(),
+                    count = import.count
+                ),
+                true,
+            ),
+            SheetImport::Invalid(e) => (
+                fl!("sheet-error", file = e.file_name(), message = e.to_string()),
+                false,
+            ),
+            SheetImport::Done(Ok(path)) => (
+                fl!("import-sheet-done", path = path.display().to_string()),
+                false,
+            ),
+            SheetImport::Done(Err(e)) => (fl!("import-sheet-failed", error = e.as_str()), false),
+        };
+
+        let mut buttons: Vec<Element<'_, Message>> = Vec::new();
+        if confirm {
+            buttons.push(
+                widget::button::suggested(fl!("import-sheet"))
+                    .on_press(Message::Sheets(SheetMessage::ConfirmImport))
+                    .into(),
+            );
+        }
+        buttons.push(
+            widget::button::standard(if confirm {
+                fl!("cancel")
+            } else {
+                fl!("dismiss")
+            })
+            .on_press(Message::Sheets(SheetMessage::DismissImport))
+            .into(),
+        );
+
+        let mut children = vec![
+            widget::text::body(message)
+                .wrapping(cosmic::iced::widget::text::Wrapping::Word)
+                .into(),
+        ];
+        if let SheetImport::Pending(import) = import {
+            children.extend(sheet_details(&import.info));
+        }
+        children.push(widget::row::with_children(buttons).spacing(8).into());
+
+        widget::container(widget::column::with_children(children).spacing(8))
+            .padding([8, 12])
+            .class(cosmic::theme::Container::Card)
+            .into()
+    }
+
+    /// The community sheet index, with a preview of the selected sheet.
+    pub(super) fn view_community(&self) -> Element<'_, Message> {
+        let mut children = vec![
+            widget::text::body(fl!("community-description"))
+                .wrapping(cosmic::iced::widget::text::Wrapping::Word)
+                .into(),
+        ];
+
+        if !self.bundled_sheets.is_empty() {
+            children.push(widget::text::heading(fl!("community-bundled")).into());
+        }
+        for (index, sheet) in self.bundled_sheets.iter().enumerate() {
+            children.push(
+                widget::button::custom(
+                    widget::column::with_children(vec![
+                        widget::text::body(sheet.entry.name.as_str()).into(),
+                        widget::text::caption(fl!(
+                            "community-shortcut-count",
+                            count = sheet.shortcuts.len()
+                        ))
+                        .into(),
+                    ])
+                    .spacing(2),
+                )
+                .width(cosmic::iced::Length::Fill)
+                .class(cosmic::theme::Button::Text)
+                .on_press(Message::Sheets(SheetMessage::PreviewBundledSheet(index)))
+                .into(),
+            );
+        }
+
+        match &self.community_index {
+            // Only the bundled sheets are offered while browsing is off
+            _ if !self.config.community_sheets => {}
+            None => children.push(widget::text::caption(fl!("community-loading")).into()),
+            Some(Err(e)) => children.push(
+                widget::text::caption(fl!("community-failed", error = e.as_str()))
+                    .class(cosmic::theme::Text::Accent)
+                    .into(),
+            ),
+            Some(Ok(entries)) if entries.is_empty() => {
+                children.push(widget::text::caption(fl!("community-empty")).into());
+            }
+            Some(Ok(entries)) => {
+                for entry in entries {
+                    children.push(
+                        widget::button::custom(
+                            widget::column::with_children(vec![
+                                widget::text::body(entry.name.as_str()).into(),
+                                widget::text::caption(entry.description.as_str())
+                                    .wrapping(cosmic::iced::widget::text::Wrapping::Word)
+                                    .into(),
+                            ])
+                            .spacing(2),
+                        )
+                        .width(cosmic::iced::Length::Fill)
+                        .class(cosmic::theme::Button::Text)
+                        .on_press(Message::Sheets(SheetMessage::PreviewCommunitySheet(
+                            entry.clone(),
+                        )))
+                        .into(),
+                    );
+                }
+            }
+        }
+
+        match &self.community_preview {
+            Some(Ok(sheet)) => {
+                children.push(widget::text::heading(sheet.entry.name.as_str()).into());
+                children.extend(sheet_details(&sheet.info));
+                for shortcut in &sheet.shortcuts {
+                    children.push(
+                        widget::row::with_children(vec![
+                            widget::text::caption(shortcut.display_label())
+                                .font(cosmic::iced_core::Font::MONOSPACE)
+                                .width(cosmic::iced::Length::FillPortion(2))
+                                .into(),
+                            widget::text::caption(shortcut.description.as_str())
+                                .width(cosmic::iced::Length::FillPortion(3))
+                                .into(),
+                        ])
+                        .spacing(8)
+                        .into(),
+                    );
+                }
+                if self.config.allow_editing {
+                    children.push(
+                        widget::button::suggested(fl!("community-install"))
+                            .on_press(Message::Sheets(SheetMessage::InstallCommunitySheet))
+                            .into(),
+                    );
+                }
+            }
+            Some(Err(e)) => children.push(
+                widget::text::caption(fl!("community-failed", error = e.as_str()))
+                    .class(cosmic::theme::Text::Accent)
+                    .into(),
+            ),
+            None => {}
+        }
+
+        match &self.community_status {
+            Some(Ok(path)) => children.push(
+                widget::text::caption(fl!("import-sheet-done", path = path.display().to_string()))
+                    .into(),
+            ),
+            Some(Err(e)) => children.push(
+                widget::text::caption(fl!("import-sheet-failed", error = e.as_str()))
+                    .class(cosmic::theme::Text::Accent)
+                    .into(),
+            ),
+            None => {}
+        }
+
+        widget::column::with_children(vec![
+            self.view_header(),
+            widget::scrollable(
+                widget::column::with_children(children)
+                    .spacing(8)
+                    .padding([8, 12]),
+            )
+            .into(),
+        ])
+        .into()
+    }
+}
+
+/// Version, author and homepage of a sheet, where it gives them; nothing for
+/// sheets without metadata.
+fn sheet_details<'a>(info: &SheetInfo) -> Option<Element<'a, Message>> {
+    let mut children: Vec<Element<'a, Message>> = Vec::new();
+    if let Some(version) = &info.version {
+        children
+            .push(widget::text::caption(fl!("sheet-version", version = version.as_str())).into());
+    }
+    if let Some(author) = &info.author {
+        children.push(widget::text::caption(fl!("sheet-author", author = author.as_str())).into());
+    }
+    if let Some(homepage) = &info.homepage {
+        children.push(
+            widget::button::link(fl!("sheet-homepage"))
+                .on_press(Message::OpenUrl(homepage.clone()))
+                .into(),
+        );
+    }
+    if children.is_empty() {
+        return None;
+    }
+    Some(
+        widget::row::with_children(children)
+            .spacing(12)
+            .align_y(cosmic::iced::Alignment::Center)
+            .wrap()
+            .into(),
+    )
+}
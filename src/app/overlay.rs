@@ -0,0 +1,233 @@
+// SPDX-License-Identifier: MIT
+
+//! Hold to peek: the shortcut grid shown on an overlay layer surface while
+//! Super is held, driven by events from `keypeek-daemon`. Built with the
+//! `overlay` feature.
+
+use super::{AppModel, Message, group_by_category, keycaps, truncate};
+use crate::daemon::DaemonEvent;
+use crate::shortcuts::KeyBinding;
+use crate::style;
+use cosmic::iced::window::Id;
+use cosmic::iced_runtime::platform_specific::wayland::layer_surface::{
+    IcedMargin, IcedOutput, SctkLayerSurfaceSettings,
+};
+use cosmic::iced_winit::commands::layer_surface::{
+    KeyboardInteractivity, Layer, destroy_layer_surface, get_layer_surface,
+};
+use cosmic::prelude::*;
+use cosmic::widget;
+use std::time::Duration;
+
+/// Rough average width of a body text character at standard density, for sizing
+/// overlay columns.
+const OVERLAY_CHAR_WIDTH: f32 = 7.5;
+/// Horizontal space taken by keycap borders, padding and spacing in an overlay cell.
+const OVERLAY_CELL_PADDING: f32 = 64.0;
+/// Descriptions longer than this are truncated in the overlay.
+const OVERLAY_MAX_DESCRIPTION_CHARS: usize = 40;
+const OVERLAY_MAX_COLUMNS: usize = 4;
+
+#[derive(Debug, Clone)]
+pub enum OverlayMessage {
+    Daemon(DaemonEvent),
+    PeekTimeout(u64),
+    Resized(Id, cosmic::iced::Size),
+}
+
+impl AppModel {
+    pub(super) fn update_overlay(
+        &mut self,
+        message: OverlayMessage,
+    ) -> Task<cosmic::Action<Message>> {
+        match message {
+            OverlayMessage::Daemon(event) => {
+                // Any key event cancels a pending peek
+                self.peek_generation += 1;
+                match event {
+                    DaemonEvent::SuperDown => {
+                        let generation = self.peek_generation;
+                        let delay = Duration::from_millis(self.config.hold_to_peek_delay_ms);
+                        return Task::perform(tokio::time::sleep(delay), move |_| {
+                            cosmic::Action::App(Message::Overlay(OverlayMessage::PeekTimeout(
+                                generation,
+                            )))
+                        });
+                    }
+                    DaemonEvent::SuperUp => {
+                        if let Some(id) = self.overlay.take() {
+                            self.overlay_size = None;
+                            self.release_memory();
+                            return destroy_layer_surface(id);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            OverlayMessage::PeekTimeout(generation) => {
+                if generation == self.peek_generation
+                    && self.popup.is_none()
+                    && self.overlay.is_none()
+                {
+                    return self.open_overlay();
+                }
+            }
+            OverlayMessage::Resized(id, size) => {
+                if self.overlay == Some(id) {
+                    self.overlay_size = Some(size);
+                }
+            }
+        }
+        Task::none()
+    }
+
+    /// Opens the shortcut list on an overlay layer surface, above fullscreen windows
+    /// and independent of where the panel is.
+    fn open_overlay(&mut self) -> Task<cosmic::Action<Message>> {
+        let id = Id::unique();
+        self.overlay = Some(id);
+        if self.shortcuts.is_empty() {
+            self.reload_shortcuts();
+        }
+
+        let margin = self.config.overlay_margin;
+        get_layer_surface(SctkLayerSurfaceSettings {
+            id,
+            layer: Layer::Overlay,
+            keyboard_interactivity: KeyboardInteractivity::None,
+            anchor: self.config.overlay_anchor.anchor(),
+            namespace: String::from("keypeek-overlay"),
+            margin: IcedMargin {
+                top: margin,
+                right: margin,
+                bottom: margin,
+                left: margin,
+            },
+            size: Some((
+                Some(self.config.overlay_width),
+                Some(self.config.overlay_height),
+            )),
+            exclusive_zone: self.config.overlay_exclusive_zone,
+            output: IcedOutput::Active,
+            ..Default::default()
+        })
+    }
+
+    /// Shortcuts laid out as a grid on the overlay, with as many columns as fit.
+    ///
+    /// Each category gets its own column count, from the widest binding and
+    /// description in it; in single-line mode, descriptions longer than a
+    /// column are truncated and shown in full in a tooltip.
+    pub(super) fn view_overlay(&self) -> Element<'_, Message> {
+        let width = self
+            .overlay_size
+            .map_or(self.config.overlay_width as f32, |size| size.width);
+        let filtered_shortcuts = self.filtered_shortcuts();
+        let scale = style::interface_scale();
+
+        let mut sections: Vec<Element<'_, Message>> = Vec::new();
+        for (category, rows) in group_by_category(&filtered_shortcuts) {
+            let labels: Vec<String> = rows.iter().map(|row| row.display_label()).collect();
+            let widest_binding = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+            let widest_description = rows
+                .iter()
+                .map(|row| row.description.chars().count())
+                .max()
+                .unwrap_or(0)
+                .min(OVERLAY_MAX_DESCRIPTION_CHARS);
+
+            let cell_width = ((widest_binding + widest_description) as f32 * OVERLAY_CHAR_WIDTH
+                + OVERLAY_CELL_PADDING)
+                * scale;
+            let columns = ((width / cell_width) as usize).clamp(1, OVERLAY_MAX_COLUMNS);
+
+            let color = self.category_color(category);
+            let mut grid = widget::column().spacing(4);
+            let cells: Vec<(String, &KeyBinding)> = labels.into_iter().zip(rows).collect();
+            for chunk in cells.chunks(columns) {
+                let mut line: Vec<Element<'_, Message>> = chunk
+                    .iter()
+                    .map(|(label, shortcut)| {
+                        let single_line = self.config.overlay_single_line;
+                        overlay_cell(label.clone(), *shortcut, single_line, color)
+                    })
+                    .collect();
+                // Pad the last line so its cells keep the same width as the others
+                while line.len() < columns {
+                    line.push(
+                        widget::column::with_children(vec![])
+                            .width(cosmic::iced::Length::FillPortion(1))
+                            .into(),
+                    );
+                }
+                grid = grid.push(widget::row::with_children(line).spacing(12));
+            }
+
+            sections.push(
+                widget::column::with_children(vec![
+                    widget::row::with_children(vec![
+                        widget::container(widget::Space::new(4, 16))
+                            .class(style::category_marker(color))
+                            .into(),
+                        widget::text::heading(category.label())
+                            .class(cosmic::theme::Text::Accent)
+                            .into(),
+                    ])
+                    .spacing(8)
+                    .align_y(cosmic::iced::Alignment::Center)
+                    .into(),
+                    grid.into(),
+                ])
+                .spacing(8)
+                .into(),
+            );
+        }
+
+        widget::scrollable(
+            widget::column::with_children(sections)
+                .spacing(16)
+                .padding(12),
+        )
+        .into()
+    }
+}
+
+/// One binding in the overlay grid: keycaps and the description, cut to one
+/// line if `single_line` is set.
+fn overlay_cell<'a>(
+    label: String,
+    shortcut: &'a KeyBinding,
+    single_line: bool,
+    color: style::CategoryColor,
+) -> Element<'a, Message> {
+    let description = if single_line {
+        truncate(&shortcut.description, OVERLAY_MAX_DESCRIPTION_CHARS)
+    } else {
+        shortcut.description.clone()
+    };
+    let truncated = description.len() != shortcut.description.len();
+
+    let cell = widget::row::with_children(vec![
+        keycaps(label, Some(color)),
+        widget::text::body(description)
+            .wrapping(cosmic::iced::widget::text::Wrapping::Word)
+            .into(),
+    ])
+    .spacing(8)
+    .align_y(cosmic::iced::Alignment::Center);
+
+    let cell: Element<'a, Message> = if truncated {
+        widget::tooltip(
+            cell,
+            widget::text::body(&shortcut.description),
+            widget::tooltip::Position::Top,
+        )
+        .into()
+    } else {
+        cell.into()
+    };
+
+    widget::container(cell)
+        .width(cosmic::iced::Length::FillPortion(1))
+        .into()
+}
@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: MIT
+
+//! Optional helper that watches keyboard devices and tells the applet when the Super
+//! key is held, which the applet can't observe from inside a panel popup.
+//!
+//! Only Super presses/releases and an anonymous "other key" marker are forwarded,
+//! never which other key was pressed. Reading `/dev/input` requires membership in
+//! the `input` group (or an equivalent udev rule).
+
+use std::io::{self, Read, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::{env, fs, process, thread};
+
+/// `EV_KEY` event type from linux/input-event-codes.h.
+const EV_KEY: u16 = 1;
+const KEY_LEFTMETA: u16 = 125;
+const KEY_RIGHTMETA: u16 = 126;
+
+/// Layout of `struct input_event`: a `timeval` followed by type, code and value.
+const TIMEVAL_SIZE: usize = 2 * std::mem::size_of::<std::ffi::c_long>();
+const EVENT_SIZE: usize = TIMEVAL_SIZE + 8;
+
+type Clients = Arc<Mutex<Vec<UnixStream>>>;
+
+fn main() {
+    env_logger::init();
+
+    let Some(socket) = socket_path() else {
+        eprintln!("XDG_RUNTIME_DIR is not set; refusing to create the socket elsewhere");
+        process::exit(1);
+    };
+    if UnixStream::connect(&socket).is_ok() {
+        eprintln!("keypeek-daemon is already running ({})", socket.display());
+        process::exit(1);
+    }
+    // Nothing answers, so the socket, if any, was left behind by a daemon that crashed.
+    let _ = fs::remove_file(&socket);
+    let listener = match UnixListener::bind(&socket) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("failed to bind {}: {}", socket.display(), e);
+            process::exit(1);
+        }
+    };
+    if let Err(e) = fs::set_permissions(&socket, fs::Permissions::from_mode(0o600)) {
+        log::warn!("failed to restrict socket permissions: {}", e);
+    }
+
+    let clients = Clients::default();
+    if open_keyboards(&clients) == 0 {
+        let _ = fs::remove_file(&socket);
+        process::exit(1);
+    }
+
+    for stream in listener.incoming() {
+        match stream {
+            // Writes never block, so a client that stops reading can't stall the
+            // reader threads; it's dropped once its buffer fills up instead.
+            Ok(stream) => match stream.set_nonblocking(true) {
+                Ok(()) => clients.lock().unwrap().push(stream),
+                Err(e) => log::warn!("failed to set up client: {}", e),
+            },
+            Err(e) => log::warn!("failed to accept client: {}", e),
+        }
+    }
+}
+
+/// Starts a reader thread for every readable input device, returning how many were opened.
+fn open_keyboards(clients: &Clients) -> usize {
+    let entries = match fs::read_dir("/dev/input") {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("failed to list /dev/input: {}", e);
+            return 0;
+        }
+    };
+
+    let mut opened = 0;
+    let mut denied = 0;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_event_device = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("event"));
+        if !is_event_device {
+            continue;
+        }
+
+        match fs::File::open(&path) {
+            Ok(device) => {
+                opened += 1;
+                let clients = clients.clone();
+                thread::spawn(move || read_events(device, clients));
+            }
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => denied += 1,
+            Err(e) => log::debug!("skipping {}: {}", path.display(), e),
+        }
+    }
+
+    if opened == 0 && denied > 0 {
+        eprintln!(
+            "permission denied reading /dev/input; add your user to the `input` group \
+             or install a udev rule granting access"
+        );
+    }
+
+    opened
+}
+
+/// Forwards Super key state changes from one device to all connected clients.
+fn read_events(mut device: fs::File, clients: Clients) {
+    let mut buf = [0u8; EVENT_SIZE];
+
+    while device.read_exact(&mut buf).is_ok() {
+        let kind = u16::from_ne_bytes([buf[TIMEVAL_SIZE], buf[TIMEVAL_SIZE + 1]]);
+        let code = u16::from_ne_bytes([buf[TIMEVAL_SIZE + 2], buf[TIMEVAL_SIZE + 3]]);
+        let value = i32::from_ne_bytes([
+            buf[TIMEVAL_SIZE + 4],
+            buf[TIMEVAL_SIZE + 5],
+            buf[TIMEVAL_SIZE + 6],
+            buf[TIMEVAL_SIZE + 7],
+        ]);
+
+        if kind != EV_KEY {
+            continue;
+        }
+
+        // Values are 0 for release, 1 for press and 2 for autorepeat.
+        let line = match (code, value) {
+            (KEY_LEFTMETA | KEY_RIGHTMETA, 1) => "super-down",
+            (KEY_LEFTMETA | KEY_RIGHTMETA, 0) => "super-up",
+            (_, 1) => "other-key",
+            _ => continue,
+        };
+
+        clients
+            .lock()
+            .unwrap()
+            .retain_mut(|client| writeln!(client, "{}", line).is_ok());
+    }
+}
+
+/// Must match `daemon::socket_path` in the applet.
+fn socket_path() -> Option<PathBuf> {
+    env::var_os("XDG_RUNTIME_DIR").map(|dir| PathBuf::from(dir).join("keypeek-daemon.sock"))
+}
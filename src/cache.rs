@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: MIT
+
+//! On-disk cache of the COSMIC shortcuts, shared by every instance of the
+//! applet, e.g. when it is added to two panels.
+//!
+//! The cache is keyed by a fingerprint of the files the shortcuts are built
+//! from. A lock file serializes refreshes: the first instance to notice a change
+//! reloads and writes the cache while the others wait and then reuse it.
+
+use crate::layers::{self, SHORTCUTS_CONFIG};
+use crate::shortcuts::KeyBinding;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheFile {
+    fingerprint: u64,
+    bindings: Vec<KeyBinding>,
+}
+
+/// Returns the cached shortcuts if they are still current, otherwise calls
+/// `load` and caches its result. Falls back to `load` if there is no usable
+/// cache directory; only errors of `load` are returned.
+///
+/// `options` are the settings `load` depends on; the cache is only used for
+/// the same ones.
+pub fn load_shared<E>(
+    options: impl Hash,
+    load: impl FnOnce() -> Result<Vec<KeyBinding>, E>,
+) -> Result<Vec<KeyBinding>, E> {
+    let Some(dir) = dirs::cache_dir() else {
+        return load();
+    };
+    let dir = dir.join("keypeek");
+    match lock(&dir) {
+        Ok(lock) => load_with(&dir, &lock, fingerprint(options), load),
+        Err(e) => {
+            log::warn!("not caching shortcuts: {:#}", e);
+            load()
+        }
+    }
+}
+
+/// Takes the cache lock, which is released when the file is dropped.
+fn lock(dir: &Path) -> anyhow::Result<File> {
+    std::fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let lock = File::create(dir.join("shortcuts.lock")).context("failed to open the cache lock")?;
+    lock.lock().context("failed to lock the cache")?;
+    Ok(lock)
+}
+
+fn load_with<E>(
+    dir: &Path,
+    _lock: &File,
+    fingerprint: u64,
+    load: impl FnOnce() -> Result<Vec<KeyBinding>, E>,
+) -> Result<Vec<KeyBinding>, E> {
+    let path = dir.join("shortcuts.json");
+    if let Some(cached) = std::fs::read(&path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<CacheFile>(&bytes).ok())
+        .filter(|cached| cached.fingerprint == fingerprint)
+    {
+        log::debug!("using cached shortcuts from {}", path.display());
+        return Ok(cached.bindings);
+    }
+
+    let bindings = load()?;
+    let cache = CacheFile {
+        fingerprint,
+        bindings,
+    };
+    // Write to a temporary file first so readers never see a partial cache.
+    let tmp = dir.join("shortcuts.json.tmp");
+    let written = serde_json::to_vec(&cache)
+        .map_err(anyhow::Error::from)
+        .and_then(|bytes| Ok(std::fs::write(&tmp, bytes)?))
+        .and_then(|()| Ok(std::fs::rename(&tmp, &path)?));
+    if let Err(e) = written {
+        log::warn!("failed to write {}: {:#}", path.display(), e);
+    }
+    Ok(cache.bindings)
+}
+
+/// Hash of everything the loaded shortcuts depend on: the shortcut configs,
+/// the installed applications (for names, icons and missing executables), the
+/// locale, the applet version and the loading `options`.
+fn fingerprint(options: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    options.hash(&mut hasher);
+    for var in ["LC_ALL", "LC_MESSAGES", "LANG", "PATH", "XDG_DATA_DIRS"] {
+        std::env::var_os(var).hash(&mut hasher);
+    }
+
+    let mut data_dirs: Vec<PathBuf> = dirs::data_dir().into_iter().collect();
+    data_dirs.extend(layers::system_dirs());
+
+    if let Some(config) = dirs::config_dir() {
+        hash_tree(&config.join(SHORTCUTS_CONFIG), &mut hasher);
+    }
+    for dir in &data_dirs {
+        hash_tree(&dir.join(SHORTCUTS_CONFIG), &mut hasher);
+        // Installing or removing an application changes the directory itself.
+        hash_modified(&dir.join("applications"), &mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Hashes the paths, sizes and modification times of the files under `dir`.
+fn hash_tree(dir: &Path, hasher: &mut DefaultHasher) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            hash_tree(&path, hasher);
+        } else {
+            hash_modified(&path, hasher);
+        }
+    }
+}
+
+fn hash_modified(path: &Path, hasher: &mut DefaultHasher) {
+    path.hash(hasher);
+    if let Ok(metadata) = std::fs::metadata(path) {
+        metadata.len().hash(hasher);
+        metadata.modified().ok().hash(hasher);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_with_reuses_cache_until_fingerprint_changes() {
+        let dir = std::env::temp_dir().join(format!("keypeek-cache-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let lock = lock(&dir).unwrap();
+        let loaded = load_with(&dir, &lock, 1, || Ok::<_, ()>(Vec::new()));
+        assert!(loaded.unwrap().is_empty());
+        let cached = load_with(&dir, &lock, 1, || -> Result<_, ()> {
+            panic!("cache should be used")
+        });
+        assert!(cached.unwrap().is_empty());
+
+        let mut reloaded = false;
+        load_with(&dir, &lock, 2, || {
+            reloaded = true;
+            Ok::<_, ()>(Vec::new())
+        })
+        .unwrap();
+        assert!(reloaded);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
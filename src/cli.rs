@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: MIT
+
+//! Command-line flags for running without the panel, e.g. in dotfile CI or
+//! documentation pipelines. Without flags the applet starts normally.
+
+use crate::launcher::Mode;
+use clap::{CommandFactory, Parser};
+use clap_complete::Shell;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(name = "keypeek-applet", version, about)]
+pub struct Cli {
+    /// A keypeek:// link to open in the running applet, e.g. keypeek://search?q=screenshot
+    #[arg(value_name = "LINK")]
+    pub link: Option<String>,
+    /// Open in a regular window, even when started by the panel
+    #[arg(long, conflicts_with = "link")]
+    pub window: bool,
+    /// Validate the custom sheets and exit
+    #[cfg(feature = "sheets")]
+    #[arg(long)]
+    pub check_sheets: bool,
+    /// Report key combinations bound to different actions; exits with 1 if any
+    #[arg(long)]
+    pub check_conflicts: bool,
+    /// Print the conflict report as JSON
+    #[arg(long, requires = "check_conflicts")]
+    pub json: bool,
+    /// Write the cheat sheet as HTML to FILE and exit
+    #[arg(long, value_name = "FILE")]
+    pub render_html: Option<PathBuf>,
+    /// Write the cheat sheet as Markdown to FILE and exit
+    #[arg(long, value_name = "FILE", conflicts_with = "render_html")]
+    pub render_md: Option<PathBuf>,
+    /// Write every shortcut in the exchange format to FILE and exit
+    #[arg(long, value_name = "FILE")]
+    pub export_json: Option<PathBuf>,
+    /// Load the shortcuts, print how long each stage took and exit
+    #[cfg(feature = "timings")]
+    #[arg(long)]
+    pub timings: bool,
+    /// Print a completion script for SHELL and exit
+    #[arg(long, value_name = "SHELL")]
+    pub generate_completions: Option<Shell>,
+    /// Print the desktop entry for MODE and exit, for packaging
+    #[arg(long, value_name = "MODE")]
+    pub generate_desktop_entry: Option<Mode>,
+    /// Print the man page and exit
+    #[arg(long)]
+    pub generate_man: bool,
+}
+
+/// Writes the completion script for `shell` to stdout.
+pub fn print_completions(shell: Shell) {
+    let mut command = Cli::command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+}
+
+/// Writes the man page, in roff, to stdout.
+pub fn print_man_page() -> std::io::Result<()> {
+    clap_mangen::Man::new(Cli::command()).render(&mut std::io::stdout())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+        let cli = Cli::parse_from(["keypeek-applet", "--render-md", "out.md"]);
+        assert_eq!(cli.render_md, Some(PathBuf::from("out.md")));
+        assert!(
+            Cli::try_parse_from(["keypeek-applet", "--render-md", "a", "--render-html", "b"])
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,211 @@
+// SPDX-License-Identifier: MIT
+
+//! Readable display of spawn commands: shell quoting is removed, `~` and
+//! `$HOME` are expanded, paths under the home directory are shown relative to
+//! `~`, and long paths are shortened. Other variables are shown as written:
+//! their values could be secrets that redaction already passed over, and the
+//! applet's environment isn't the one the compositor runs commands in.
+
+/// Paths longer than this many characters have their middle elided.
+const MAX_PATH_CHARS: usize = 40;
+
+/// What a word of a command line is, for highlighting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Program,
+    Flag,
+    Argument,
+}
+
+/// A word of a command line, ready for display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub text: String,
+    pub kind: TokenKind,
+}
+
+/// The program a command line runs, skipping `env` and variable assignments,
+/// e.g. `/usr/bin/firefox` for `env MOZ_X=1 /usr/bin/firefox --new-window`.
+pub fn program(command: &str) -> Option<&str> {
+    command
+        .split_whitespace()
+        .find(|word| *word != "env" && !word.contains('='))
+}
+
+/// Splits a command line into display tokens.
+pub fn display_tokens(command: &str) -> Vec<Token> {
+    let home = dirs::home_dir().map(|home| home.to_string_lossy().into_owned());
+    let mut seen_program = false;
+
+    split_words(command)
+        .into_iter()
+        .map(|word| {
+            let kind = if word.starts_with('-') && word.len() > 1 {
+                TokenKind::Flag
+            } else if !seen_program && !word.contains('=') {
+                seen_program = true;
+                TokenKind::Program
+            } else {
+                TokenKind::Argument
+            };
+
+            let word = shorten_path(&expand(&word, home.as_deref()), home.as_deref());
+            let text = if word.contains(char::is_whitespace) || word.is_empty() {
+                format!("\"{word}\"")
+            } else {
+                word
+            };
+            Token { text, kind }
+        })
+        .collect()
+}
+
+/// Splits a command line into words the way a POSIX shell would, honoring
+/// single and double quotes and backslash escapes.
+fn split_words(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') | (None, '\\') => {
+                if let Some(next) = chars.next() {
+                    word.push(next);
+                }
+                in_word = true;
+            }
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+
+    words
+}
+
+/// Expands a leading `~` and `$HOME`/`${HOME}` references to `home`.
+fn expand(word: &str, home: Option<&str>) -> String {
+    let Some(home) = home else {
+        return word.to_string();
+    };
+    let word = match word.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("{home}{rest}"),
+        _ => word.to_string(),
+    };
+
+    let mut out = String::with_capacity(word.len());
+    let mut rest = word.as_str();
+    while let Some(start) = rest.find('$') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let len = if after.starts_with("{HOME}") {
+            "{HOME}".len()
+        } else if after.starts_with("HOME")
+            && !after["HOME".len()..].starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+        {
+            "HOME".len()
+        } else {
+            0
+        };
+
+        if len == 0 {
+            out.push('$');
+        } else {
+            out.push_str(home);
+        }
+        rest = &after[len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Shows paths under the home directory relative to `~` and elides the middle
+/// components of long paths, e.g. `/usr/…/libexec/helper`.
+fn shorten_path(word: &str, home: Option<&str>) -> String {
+    let word = match home.and_then(|home| word.strip_prefix(home)) {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("~{rest}"),
+        _ => word.to_string(),
+    };
+
+    if word.chars().count() <= MAX_PATH_CHARS || !word.contains('/') {
+        return word;
+    }
+
+    let parts: Vec<&str> = word.split('/').collect();
+    if parts.len() <= 4 {
+        return word;
+    }
+    let (head, tail) = (&parts[..2], &parts[parts.len() - 2..]);
+    format!("{}/…/{}", head.join("/"), tail.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(command: &str) -> Vec<String> {
+        display_tokens(command)
+            .into_iter()
+            .map(|t| t.text)
+            .collect()
+    }
+
+    #[test]
+    fn split_words_removes_quoting() {
+        assert_eq!(
+            split_words(r#"sh -c 'notify-send "hi there"' a\ b """#),
+            ["sh", "-c", r#"notify-send "hi there""#, "a b", ""]
+        );
+    }
+
+    #[test]
+    fn display_tokens_classifies_and_shortens() {
+        let tokens =
+            display_tokens("FOO=1 /usr/lib/x86_64-linux-gnu/libexec/helper/bin/tool --flag x");
+        let kinds: Vec<TokenKind> = tokens.iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::Argument,
+                TokenKind::Program,
+                TokenKind::Flag,
+                TokenKind::Argument
+            ]
+        );
+        assert_eq!(tokens[1].text, "/usr/…/bin/tool");
+    }
+
+    #[test]
+    fn expand_only_replaces_home() {
+        let home = Some("/home/me");
+        assert_eq!(
+            expand("~/bin/$HOME/${HOME}", home),
+            "/home/me/bin//home/me//home/me"
+        );
+        // Set or not, other variables stay as written
+        assert_eq!(
+            expand("$PATH:${KEYPEEK_UNSET_VAR}:$HOMEDIR", home),
+            "$PATH:${KEYPEEK_UNSET_VAR}:$HOMEDIR"
+        );
+        assert_eq!(texts("echo ~nobody"), ["echo", "~nobody"]);
+    }
+}
@@ -0,0 +1,224 @@
+// SPDX-License-Identifier: MIT
+
+//! Community sheets: cheat sheets of other applications, listed in an index
+//! fetched with [`net`] and installed into the sheets directory.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "sheets": [
+//!     {
+//!       "id": "firefox",
+//!       "name": "Firefox",
+//!       "description": "Tabs, navigation and developer tools",
+//!       "url": "https://example.org/sheets/firefox.toml",
+//!       "sha256": "…"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Builds with the `bundled-sheets` feature also embed the curated sheets of
+//! `community/sheets/`, for installing them without network access.
+
+use crate::net;
+use crate::sheets::SheetInfo;
+use crate::shortcuts::KeyBinding;
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// Sheets embedded with the `bundled-sheets` feature.
+#[cfg(feature = "bundled-sheets")]
+#[derive(rust_embed::RustEmbed)]
+#[folder = "community/sheets/"]
+struct BundledSheets;
+
+/// Newest index version this build understands.
+const INDEX_VERSION: u32 = 1;
+
+/// Largest sheet downloaded; sheets are a few kilobytes.
+const MAX_SHEET_SIZE: usize = 256 * 1024;
+
+#[derive(Debug, Deserialize)]
+struct Index {
+    version: u32,
+    #[serde(default)]
+    sheets: Vec<Entry>,
+}
+
+/// A sheet listed in the index.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Entry {
+    /// File name the sheet is installed as, without extension.
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub url: String,
+    /// Hex encoded SHA-256 of the sheet file.
+    pub sha256: String,
+}
+
+/// A downloaded and verified sheet, ready to be previewed and installed.
+#[derive(Debug, Clone)]
+pub struct Sheet {
+    pub entry: Entry,
+    /// Author, version and homepage given by the sheet itself.
+    pub info: SheetInfo,
+    pub shortcuts: Vec<KeyBinding>,
+    text: String,
+}
+
+/// Fetches the index at `url`, which must use HTTPS.
+pub async fn fetch_index(url: String) -> Result<Vec<Entry>> {
+    let body = net::get(&url).await?;
+    let index: Index = serde_json::from_slice(&body).context("not a sheet index")?;
+    if index.version > INDEX_VERSION {
+        bail!(
+            "index version {} is newer than this build supports",
+            index.version
+        );
+    }
+
+    // Ids become file names, so keep them to plain names.
+    Ok(index
+        .sheets
+        .into_iter()
+        .filter(|entry| {
+            let valid = !entry.id.is_empty()
+                && entry
+                    .id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid {
+                log::warn!("Skipping community sheet with invalid id {:?}", entry.id);
+            }
+            valid
+        })
+        .collect())
+}
+
+/// Downloads the sheet of `entry`, checking it against the index checksum and
+/// parsing it.
+pub async fn fetch_sheet(entry: Entry) -> Result<Sheet> {
+    let body = net::get(&entry.url).await?;
+    if body.len() > MAX_SHEET_SIZE {
+        bail!("{} is too large for a sheet", entry.url);
+    }
+    verify(&body, &entry.sha256)?;
+
+    let text = String::from_utf8(body).context("sheet is not UTF-8")?;
+    let (info, shortcuts) = crate::sheets::parse_text(&text, &entry.name)
+        .map_err(|e| anyhow::anyhow!("invalid sheet: {e}"))?;
+
+    Ok(Sheet {
+        entry,
+        info,
+        shortcuts,
+        text,
+    })
+}
+
+/// The curated sheets embedded at build time, sorted by name; empty without
+/// the `bundled-sheets` feature.
+pub fn bundled() -> Vec<Sheet> {
+    #[cfg(feature = "bundled-sheets")]
+    let files: Vec<(String, String)> = BundledSheets::iter()
+        .filter_map(|file| {
+            let text = String::from_utf8(BundledSheets::get(&file)?.data.into_owned()).ok()?;
+            Some((file.into_owned(), text))
+        })
+        .collect();
+    #[cfg(not(feature = "bundled-sheets"))]
+    let files: Vec<(String, String)> = Vec::new();
+
+    let mut sheets: Vec<Sheet> = files
+        .into_iter()
+        .filter_map(|(file, text)| {
+            let id = file.strip_suffix(".toml")?.to_string();
+            match crate::sheets::parse_text(&text, &id) {
+                Ok((info, shortcuts)) => Some(Sheet {
+                    entry: Entry {
+                        id,
+                        name: info.name.clone(),
+                        description: String::new(),
+                        url: String::new(),
+                        sha256: String::new(),
+                    },
+                    info,
+                    shortcuts,
+                    text,
+                }),
+                Err(e) => {
+                    log::error!("Bundled sheet {} is invalid: {}", file, e);
+                    None
+                }
+            }
+        })
+        .collect();
+    sheets.sort_by(|a, b| a.entry.name.cmp(&b.entry.name));
+    sheets
+}
+
+/// Text of the bundled sheet `id`, for sheets extending it.
+#[cfg(feature = "bundled-sheets")]
+pub fn bundled_text(id: &str) -> Option<String> {
+    let file = BundledSheets::get(&format!("{id}.toml"))?;
+    String::from_utf8(file.data.into_owned()).ok()
+}
+
+#[cfg(not(feature = "bundled-sheets"))]
+pub fn bundled_text(_id: &str) -> Option<String> {
+    None
+}
+
+impl Sheet {
+    /// Writes the sheet to the sheets directory, next to the user's own.
+    pub fn install(&self, dir: Option<std::path::PathBuf>) -> Result<std::path::PathBuf> {
+        crate::sheets::write_new_sheet(dir, &self.entry.id, &self.text)
+    }
+}
+
+fn verify(data: &[u8], sha256: &str) -> Result<()> {
+    let digest: String = Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    if !digest.eq_ignore_ascii_case(sha256.trim()) {
+        bail!("checksum mismatch: expected {sha256}, got {digest}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_compares_hex_digests() {
+        let empty = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        assert!(verify(b"", empty).is_ok());
+        assert!(verify(b"", &empty.to_uppercase()).is_ok());
+        assert!(verify(b"x", empty).is_err());
+    }
+
+    #[test]
+    fn index_lists_curated_sheets_with_their_checksums() {
+        let index: Index = serde_json::from_str(include_str!("../community/index.json")).unwrap();
+        assert!(index.version <= INDEX_VERSION);
+        for entry in index.sheets {
+            let path = format!(
+                "{}/community/sheets/{}.toml",
+                env!("CARGO_MANIFEST_DIR"),
+                entry.id
+            );
+            let text = std::fs::read_to_string(&path).unwrap();
+            verify(text.as_bytes(), &entry.sha256).unwrap();
+            assert!(
+                crate::sheets::parse_text(&text, &entry.id).is_ok(),
+                "{path}"
+            );
+        }
+    }
+}
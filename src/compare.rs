@@ -0,0 +1,179 @@
+// SPDX-License-Identifier: MIT
+
+//! Comparison of the loaded shortcuts with a profile exported on another machine.
+
+use crate::format::{Binding, Document, Keys};
+#[cfg(feature = "dbus")]
+use anyhow::Context;
+use anyhow::Result;
+#[cfg(feature = "dbus")]
+use ashpd::desktop::file_chooser::{FileFilter, SelectedFiles};
+use std::collections::{HashMap, HashSet};
+
+/// A difference between the current shortcuts and a profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// Only in the profile.
+    Added(Binding),
+    /// Only in the current shortcuts.
+    Removed(Binding),
+    /// In both, with another action or other alternate keys.
+    Changed { current: Binding, profile: Binding },
+}
+
+/// Lists the bindings that differ. Bindings are matched by their key
+/// combinations, so descriptions that differ only in wording or language
+/// don't pair up unrelated bindings, and keys bound to another action show as
+/// changed.
+pub fn diff(current: &Document, profile: &Document) -> Vec<Change> {
+    let mut profile_bindings: HashMap<String, usize> = HashMap::new();
+    for (i, binding) in profile.bindings.iter().enumerate() {
+        for keys in binding.all_keys() {
+            profile_bindings.entry(combination(&keys)).or_insert(i);
+        }
+    }
+    let current_keys: HashSet<String> = current
+        .bindings
+        .iter()
+        .flat_map(Binding::all_keys)
+        .map(|keys| combination(&keys))
+        .collect();
+
+    let mut changes = Vec::new();
+    for binding in &current.bindings {
+        let other = binding
+            .all_keys()
+            .iter()
+            .find_map(|keys| profile_bindings.get(&combination(keys)))
+            .map(|&i| &profile.bindings[i]);
+        match other {
+            None => changes.push(Change::Removed(binding.clone())),
+            Some(other)
+                if other.description.to_lowercase() != binding.description.to_lowercase()
+                    || combinations(other) != combinations(binding) =>
+            {
+                changes.push(Change::Changed {
+                    current: binding.clone(),
+                    profile: other.clone(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    changes.extend(
+        profile
+            .bindings
+            .iter()
+            .filter(|binding| {
+                !binding
+                    .all_keys()
+                    .iter()
+                    .any(|keys| current_keys.contains(&combination(keys)))
+            })
+            .map(|binding| Change::Added(binding.clone())),
+    );
+
+    changes
+}
+
+/// A key combination with its modifiers in display order, whatever order a
+/// profile lists them in.
+fn combination(keys: &Keys) -> String {
+    let mut keys = keys.clone();
+    keys.modifiers.sort();
+    keys.modifiers.dedup();
+    keys.keys_label()
+}
+
+fn combinations(binding: &Binding) -> HashSet<String> {
+    binding.all_keys().iter().map(combination).collect()
+}
+
+/// Asks the user for a profile through the file chooser portal and reads it.
+#[cfg(feature = "dbus")]
+pub async fn open_profile(title: String) -> Result<Document> {
+    let response = SelectedFiles::open_file()
+        .title(title.as_str())
+        .modal(true)
+        .filter(FileFilter::new("JSON").mimetype("application/json"))
+        .send()
+        .await
+        .context("file chooser portal is not available")?
+        .response()
+        .context("no profile was chosen")?;
+
+    let path = response
+        .uris()
+        .first()
+        .and_then(|uri| uri.to_file_path().ok())
+        .context("the chosen profile is not a local file")?;
+    let text = tokio::fs::read_to_string(&path)
+        .await
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    Document::from_json(&text)
+}
+
+#[cfg(not(feature = "dbus"))]
+pub async fn open_profile(_title: String) -> Result<Document> {
+    Err(crate::utils::no_dbus())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Modifier;
+
+    fn binding(key: &str, description: &str) -> Binding {
+        Binding {
+            modifiers: vec![Modifier::Super],
+            key: Some(key.to_string()),
+            pointer: None,
+            chord: None,
+            description: description.to_string(),
+            category: String::from("custom"),
+            sources: Vec::new(),
+            command: None,
+            notes: None,
+            alternates: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn diff_matches_bindings_by_keys() {
+        let mut files = binding("f", "Files");
+        files.alternates.push(Keys {
+            modifiers: vec![Modifier::Ctrl, Modifier::Super],
+            key: Some(String::from("e")),
+            pointer: None,
+            chord: None,
+        });
+        let current = Document::new(vec![
+            binding("t", "Terminal"),
+            binding("b", "Browser"),
+            binding("e", "Editor"),
+            binding("f", "Files"),
+        ]);
+        let profile = Document::new(vec![
+            binding("t", "terminal"),
+            binding("b", "Mail"),
+            binding("w", "Browser"),
+            files.clone(),
+        ]);
+
+        assert_eq!(
+            diff(&current, &profile),
+            [
+                Change::Changed {
+                    current: binding("b", "Browser"),
+                    profile: binding("b", "Mail"),
+                },
+                Change::Removed(binding("e", "Editor")),
+                Change::Changed {
+                    current: binding("f", "Files"),
+                    profile: files,
+                },
+                Change::Added(binding("w", "Browser")),
+            ]
+        );
+    }
+}
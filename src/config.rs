@@ -1,9 +1,509 @@
 // SPDX-License-Identifier: MIT
 
+use crate::shortcuts::{
+    KeyBinding, MergePolicy, ModifierFilter, PrimaryBinding, ShortcutCategory, SpawnGroup,
+};
 use cosmic::cosmic_config::{self, CosmicConfigEntry, cosmic_config_derive::CosmicConfigEntry};
+use cosmic::iced_winit::commands::layer_surface::Anchor;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Default, Clone, CosmicConfigEntry, Eq, PartialEq)]
+/// Screen edge (or center) the overlay is attached to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum OverlayAnchor {
+    #[default]
+    Center,
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl OverlayAnchor {
+    pub fn anchor(self) -> Anchor {
+        match self {
+            OverlayAnchor::Center => Anchor::empty(),
+            OverlayAnchor::Top => Anchor::TOP,
+            OverlayAnchor::Bottom => Anchor::BOTTOM,
+            OverlayAnchor::Left => Anchor::LEFT,
+            OverlayAnchor::Right => Anchor::RIGHT,
+        }
+    }
+}
+
+/// How the shortcut list is split into sections.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum GroupBy {
+    /// One section per category.
+    #[default]
+    Category,
+    /// One section per set of modifiers, e.g. all Super + Shift bindings.
+    Modifiers,
+}
+
+/// Layout of the popup and window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Layout {
+    /// The touch layout on small screens, the standard one otherwise.
+    #[default]
+    Auto,
+    Standard,
+    /// Larger tap targets, the search field at the bottom, swiping between
+    /// categories and nothing that needs a pointer hovering.
+    Touch,
+}
+
+/// A one-tap filter above the shortcut list: a search query, the categories
+/// to show and the modifiers bindings must use. Also used for saved searches.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct QuickFilter {
+    pub name: String,
+    #[serde(default)]
+    pub query: String,
+    /// Category ids; empty shows every category.
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub modifiers: Vec<ModifierFilter>,
+}
+
+impl QuickFilter {
+    pub fn new(name: &str, query: &str, categories: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            query: query.to_string(),
+            categories: categories.iter().map(|id| id.to_string()).collect(),
+            modifiers: Vec::new(),
+        }
+    }
+
+    /// The categories this filter shows; unknown ids are ignored.
+    pub fn category_set(&self) -> HashSet<ShortcutCategory> {
+        if self.categories.is_empty() {
+            return ShortcutCategory::all().iter().copied().collect();
+        }
+        self.categories
+            .iter()
+            .filter_map(|id| ShortcutCategory::from_id(id))
+            .collect()
+    }
+}
+
+/// System-wide defaults provisioned by administrators. Keys the user has set
+/// in their own config take precedence.
+const SYSTEM_CONFIG: &str = "/etc/keypeek/config.toml";
+
+#[derive(Debug, Clone, CosmicConfigEntry, Eq, PartialEq, Deserialize, Serialize)]
 #[version = 1]
 pub struct Config {
     demo: String,
+    /// Set once the first-run tour has been dismissed with "don't show again".
+    pub onboarding_complete: bool,
+    /// Split the shortcut list into sections.
+    pub group_by_category: bool,
+    /// What the sections of the shortcut list are based on.
+    pub group_by: GroupBy,
+    /// Ids of the category sections the user has collapsed.
+    pub collapsed_categories: Vec<String>,
+    /// Regular expressions whose matches are masked in spawn commands before they are
+    /// displayed or copied. A capture group limits the mask to that group.
+    pub redaction_patterns: Vec<String>,
+    /// How the same binding listed by several sources is shown.
+    pub merge_policy: MergePolicy,
+    /// Which binding of an action with several is shown first.
+    pub primary_binding: PrimaryBinding,
+    /// Rules grouping custom shortcuts by their command; the first match wins.
+    pub spawn_groups: Vec<SpawnGroup>,
+    /// Recent search queries, most recent first.
+    pub search_history: Vec<String>,
+    /// Filters offered as buttons above the shortcut list.
+    pub quick_filters: Vec<QuickFilter>,
+    /// Searches the user saved, pinned after the quick filters in this order.
+    pub saved_searches: Vec<QuickFilter>,
+    /// Open the popup while Super is held, using events from `keypeek-daemon`.
+    pub hold_to_peek: bool,
+    /// How long Super has to be held before the popup opens.
+    pub hold_to_peek_delay_ms: u64,
+    /// Where the hold-to-peek overlay appears on screen.
+    pub overlay_anchor: OverlayAnchor,
+    /// Distance in pixels between the overlay and the anchored screen edge.
+    pub overlay_margin: i32,
+    /// Space the overlay reserves from other surfaces; -1 lets it overlap panels.
+    pub overlay_exclusive_zone: i32,
+    /// Size of the overlay surface in logical pixels.
+    pub overlay_width: u32,
+    pub overlay_height: u32,
+    /// Cut long descriptions to one line in the popup list; the full text is
+    /// shown on hover.
+    pub single_line_rows: bool,
+    /// Same for the overlay grid.
+    pub overlay_single_line: bool,
+    /// Serve a read-only HTML cheat sheet over HTTP.
+    pub http_server: bool,
+    /// Listen on all interfaces instead of only localhost.
+    pub http_lan: bool,
+    pub http_port: u16,
+    /// Token clients must present; generated when the server is first enabled.
+    pub http_token: String,
+    /// Directory for custom sheets and saved profiles, e.g. a dotfiles repository.
+    /// Relative paths and `~` are resolved against the home directory; empty uses
+    /// `~/.config/keypeek`.
+    pub sync_dir: String,
+    /// Offer features that change files or settings, such as new sheets. Managed
+    /// deployments turn this off to keep the applet read-only.
+    pub allow_editing: bool,
+    /// Load shortcuts only while the popup or overlay is open and free them
+    /// afterwards, for memory-constrained devices. Ignored while the HTTP server
+    /// is enabled, since it serves the shortcuts at any time.
+    pub low_memory: bool,
+    /// Draw with fewer, cheaper widgets for weak GPUs: a pre-rendered panel
+    /// icon, bitmap row icons and rows without keycaps or backgrounds.
+    pub light_rendering: bool,
+    /// Icon shown in the panel instead of the bundled one: an icon theme name,
+    /// or the path of an SVG or PNG. Relative paths and `~` are resolved against
+    /// the home directory.
+    pub panel_icon: String,
+    /// Short text shown next to the panel icon, e.g. "Keys".
+    pub panel_label: String,
+    /// Categories left out of the list, by id.
+    pub hidden_categories: Vec<String>,
+    /// Shortcuts left out of the list, by description.
+    pub hidden_shortcuts: Vec<String>,
+    /// List hidden categories and shortcuts anyway, so they can be shown again.
+    pub show_hidden: bool,
+    /// Colors of categories as `#rrggbb`, by category id, replacing the ones
+    /// derived from the theme.
+    pub category_colors: HashMap<String, String>,
+    /// Skip the animations when sections collapse or the detail page opens.
+    pub reduce_motion: bool,
+    pub layout: Layout,
+    /// Offer browsing community sheets, which fetches their index from the web.
+    pub community_sheets: bool,
+    /// HTTPS address of the community sheet index.
+    pub community_index_url: String,
+    /// Check GitHub for new releases at startup.
+    pub check_updates: bool,
+    /// Also confirm copied commands with a desktop notification.
+    pub notify_actions: bool,
+    /// Release whose banner was dismissed, so it isn't shown again.
+    pub dismissed_release: String,
+    /// Keep local statistics of searches and of the shortcuts and features
+    /// used; see [`crate::usage`].
+    pub usage_stats: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            demo: String::new(),
+            onboarding_complete: false,
+            group_by_category: true,
+            group_by: GroupBy::default(),
+            collapsed_categories: Vec::new(),
+            redaction_patterns: vec![String::from(
+                r"(?i)(?:password|passwd|token|secret|api[-_]?key)[= ](\S+)",
+            )],
+            merge_policy: MergePolicy::default(),
+            primary_binding: PrimaryBinding::default(),
+            // Whole words only, so e.g. "determine" isn't a terminal
+            spawn_groups: vec![
+                SpawnGroup::new(
+                    "Browsers",
+                    r"(?i)\b(firefox|librewolf|brave|chrome|chromium|epiphany|vivaldi)\b",
+                ),
+                SpawnGroup::new(
+                    "Terminals",
+                    r"(?i)\b(cosmic-term|gnome-terminal|konsole|xterm|alacritty|kitty|wezterm|foot|ghostty)\b",
+                ),
+                SpawnGroup::new(
+                    "Files",
+                    r"(?i)\b(cosmic-files|nautilus|dolphin|thunar|nemo)\b",
+                ),
+                SpawnGroup::new(
+                    "Editors",
+                    r"(?i)\b(cosmic-edit|code|codium|zed|gedit|n?vim|emacs)\b",
+                ),
+            ],
+            search_history: Vec::new(),
+            quick_filters: vec![
+                QuickFilter::new("Windows", "", &["window-management", "window-movement"]),
+                QuickFilter::new("Workspaces", "", &["workspace-navigation"]),
+                QuickFilter::new("Screenshots", "screenshot", &[]),
+                QuickFilter::new("Media", "", &["media"]),
+                QuickFilter::new("Launch apps", "", &["applications", "custom"]),
+            ],
+            saved_searches: Vec::new(),
+            hold_to_peek: false,
+            hold_to_peek_delay_ms: 500,
+            overlay_anchor: OverlayAnchor::default(),
+            overlay_margin: 32,
+            overlay_exclusive_zone: -1,
+            overlay_width: 960,
+            overlay_height: 640,
+            single_line_rows: false,
+            overlay_single_line: true,
+            http_server: false,
+            http_lan: false,
+            http_port: 8472,
+            http_token: String::new(),
+            sync_dir: String::new(),
+            allow_editing: true,
+            low_memory: false,
+            light_rendering: false,
+            panel_icon: String::new(),
+            panel_label: String::new(),
+            hidden_categories: Vec::new(),
+            hidden_shortcuts: Vec::new(),
+            show_hidden: false,
+            category_colors: HashMap::new(),
+            reduce_motion: false,
+            layout: Layout::default(),
+            community_sheets: false,
+            community_index_url: String::from(
+                "https://raw.githubusercontent.com/l-const/keypeek-applet/main/community/index.json",
+            ),
+            check_updates: false,
+            notify_actions: false,
+            dismissed_release: String::new(),
+            usage_stats: false,
+        }
+    }
+}
+
+impl Config {
+    /// Reads the config, falling back to the defaults for missing or invalid entries.
+    pub fn load(app_id: &str) -> (Option<cosmic_config::Config>, Self) {
+        let started = std::time::Instant::now();
+        let handler = cosmic_config::Config::new(app_id, Self::VERSION).ok();
+        let config = match &handler {
+            Some(handler) => Self::read(handler, app_id),
+            None => Self::default().with_system_defaults(app_id),
+        };
+        crate::timings::record("config open", started);
+        (handler, config)
+    }
+
+    /// Reads the config from `handler`, e.g. again after it changed.
+    pub fn read(handler: &cosmic_config::Config, app_id: &str) -> Self {
+        let config = match Self::get_entry(handler) {
+            Ok(config) => config,
+            Err((errors, config)) => {
+                for why in errors {
+                    log::error!("error loading app config: {why}");
+                }
+                config
+            }
+        };
+        config.with_system_defaults(app_id)
+    }
+
+    /// Fills in keys the user has not set from [`SYSTEM_CONFIG`]. Other keys
+    /// keep their value, including defaults cosmic-config read from
+    /// `/usr/share/cosmic`.
+    pub fn with_system_defaults(self, app_id: &str) -> Self {
+        let system = match std::fs::read_to_string(SYSTEM_CONFIG) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return self,
+            Err(e) => {
+                log::error!("failed to read {SYSTEM_CONFIG}: {e}");
+                return self;
+            }
+        };
+        let system = match system.parse::<toml::Table>() {
+            Ok(system) => system,
+            Err(e) => {
+                log::error!("invalid {SYSTEM_CONFIG}: {e}");
+                return self;
+            }
+        };
+
+        // cosmic-config stores every key the user has set as its own file.
+        let user_dir = dirs::config_dir().map(|dir| {
+            dir.join("cosmic")
+                .join(app_id)
+                .join(format!("v{}", Self::VERSION))
+        });
+        apply_system_defaults(self, system, |key| {
+            user_dir.as_ref().is_some_and(|dir| dir.join(key).exists())
+        })
+    }
+
+    /// Directory holding custom sheets and saved profiles.
+    #[cfg(feature = "sheets")]
+    pub fn data_dir(&self) -> Option<PathBuf> {
+        resolve_path(&self.sync_dir, dirs::home_dir().as_deref())
+            .or_else(|| dirs::config_dir().map(|dir| dir.join("keypeek")))
+    }
+
+    #[cfg(feature = "sheets")]
+    pub fn sheets_dir(&self) -> Option<PathBuf> {
+        self.data_dir().map(|dir| dir.join("sheets"))
+    }
+
+    /// Where profiles are saved; `None` keeps them in the documents directory
+    /// unless a sync directory is set.
+    pub fn profiles_dir(&self) -> Option<PathBuf> {
+        resolve_path(&self.sync_dir, dirs::home_dir().as_deref()).map(|dir| dir.join("profiles"))
+    }
+
+    /// Whether `shortcut` or its category was hidden from the list.
+    pub fn is_hidden(&self, shortcut: &KeyBinding) -> bool {
+        self.hidden_shortcuts.contains(&shortcut.description)
+            || self.is_category_hidden(shortcut.category)
+    }
+
+    pub fn is_category_hidden(&self, category: ShortcutCategory) -> bool {
+        self.hidden_categories.iter().any(|id| id == category.id())
+    }
+
+    /// The panel icon if it is a file rather than an icon theme name.
+    pub fn panel_icon_path(&self) -> Option<PathBuf> {
+        let icon = self.panel_icon.trim();
+        let is_image = |ext: &std::ffi::OsStr| {
+            ext.eq_ignore_ascii_case("svg") || ext.eq_ignore_ascii_case("png")
+        };
+        let is_path = icon.contains('/')
+            || icon.starts_with('~')
+            || Path::new(icon).extension().is_some_and(is_image);
+        resolve_path(icon, dirs::home_dir().as_deref()).filter(|_| is_path)
+    }
+
+    /// The panel icon if it is an icon theme name.
+    pub fn panel_icon_name(&self) -> Option<&str> {
+        let icon = self.panel_icon.trim();
+        (!icon.is_empty() && self.panel_icon_path().is_none()).then_some(icon)
+    }
+}
+
+/// Overrides the keys of `config` that `user_has_set` reports as unset with the
+/// values from `system`. The config is left unchanged if a value has the wrong type.
+fn apply_system_defaults(
+    config: Config,
+    system: toml::Table,
+    user_has_set: impl Fn(&str) -> bool,
+) -> Config {
+    let Ok(mut table) = toml::Table::try_from(&config) else {
+        return config;
+    };
+    for (key, value) in system {
+        if !table.contains_key(&key) {
+            log::warn!("unknown key {key} in {SYSTEM_CONFIG}");
+        } else if !user_has_set(&key) {
+            table.insert(key, value);
+        }
+    }
+    table.try_into().unwrap_or_else(|e| {
+        log::error!("invalid value in {SYSTEM_CONFIG}: {e}");
+        config
+    })
+}
+
+/// Resolves a user-entered path: `~` and relative paths are taken from
+/// the home directory. Returns `None` for an empty path.
+fn resolve_path(path: &str, home: Option<&Path>) -> Option<PathBuf> {
+    let path = path.trim();
+    if path.is_empty() {
+        return None;
+    }
+    let relative = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => rest.trim_start_matches('/'),
+        _ if Path::new(path).is_absolute() => return Some(PathBuf::from(path)),
+        _ => path,
+    };
+    Some(match home {
+        Some(home) => home.join(relative),
+        None => PathBuf::from(relative),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::{Modifiers, assign_spawn_groups};
+
+    #[test]
+    fn resolve_path_uses_home_for_relative_paths() {
+        let home = Path::new("/home/me");
+        assert_eq!(resolve_path("  ", Some(home)), None);
+        assert_eq!(
+            resolve_path("~", Some(home)),
+            Some(PathBuf::from("/home/me"))
+        );
+        assert_eq!(
+            resolve_path("~/dotfiles/keypeek", Some(home)),
+            Some(PathBuf::from("/home/me/dotfiles/keypeek"))
+        );
+        assert_eq!(
+            resolve_path("dotfiles/keypeek", Some(home)),
+            Some(PathBuf::from("/home/me/dotfiles/keypeek"))
+        );
+        assert_eq!(
+            resolve_path("/srv/keypeek", Some(home)),
+            Some(PathBuf::from("/srv/keypeek"))
+        );
+    }
+
+    #[test]
+    fn panel_icon_is_a_name_or_a_path() {
+        let config = |icon: &str| Config {
+            panel_icon: icon.to_string(),
+            ..Config::default()
+        };
+        assert_eq!(config("").panel_icon_name(), None);
+        assert_eq!(config("").panel_icon_path(), None);
+        assert_eq!(
+            config("input-keyboard").panel_icon_name(),
+            Some("input-keyboard")
+        );
+        assert_eq!(config("io.github.l-const.keypeek").panel_icon_path(), None);
+        assert!(config("keys.svg").panel_icon_path().is_some());
+        assert!(config("~/icons/keys.png").panel_icon_path().is_some());
+        assert_eq!(config("~/icons/keys.png").panel_icon_name(), None);
+    }
+
+    #[test]
+    fn quick_filter_without_categories_shows_all() {
+        let all = QuickFilter::new("Screenshots", "screenshot", &[]).category_set();
+        assert_eq!(all.len(), ShortcutCategory::all().len());
+
+        let media = QuickFilter::new("Media", "", &["media", "unknown"]).category_set();
+        assert_eq!(media, HashSet::from([ShortcutCategory::MediaControl]));
+    }
+
+    #[test]
+    fn default_spawn_groups_match_whole_words() {
+        let group = |command: &str| {
+            let mut bindings = [KeyBinding {
+                command: command.to_string(),
+                category: ShortcutCategory::Custom,
+                ..KeyBinding::for_test(Modifiers::new(), None, "")
+            }];
+            assign_spawn_groups(&mut bindings, &Config::default().spawn_groups);
+            bindings[0].group.clone()
+        };
+        assert_eq!(group("cosmic-term").as_deref(), Some("Terminals"));
+        assert_eq!(
+            group("flatpak run org.mozilla.firefox").as_deref(),
+            Some("Browsers")
+        );
+        assert_eq!(group("nvim notes.md").as_deref(), Some("Editors"));
+        assert_eq!(group("determine-layout --footer"), None);
+        assert_eq!(group("encode-video"), None);
+    }
+
+    #[test]
+    fn system_defaults_do_not_override_user_keys() {
+        let system: toml::Table = "http_port = 9000\nhold_to_peek = true\nallow_editing = false\n"
+            .parse()
+            .unwrap();
+        let config = apply_system_defaults(Config::default(), system, |key| key == "hold_to_peek");
+        assert_eq!(config.http_port, 9000);
+        assert!(!config.hold_to_peek);
+        assert!(!config.allow_editing);
+
+        let wrong_type: toml::Table = "http_port = \"high\"\n".parse().unwrap();
+        let config = apply_system_defaults(Config::default(), wrong_type, |_| false);
+        assert_eq!(config, Config::default());
+    }
 }
@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: MIT
+
+//! Key combinations that several sources bind to different actions, reported by
+//! `--check-conflicts`.
+
+use crate::format::{Binding, FORMAT_VERSION};
+use crate::shortcuts::{BindingId, COSMIC_SOURCE, KeyBinding};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// One key combination with more than one action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Conflict {
+    /// The key combination, e.g. "Super + q".
+    pub keys: String,
+    /// Every binding of the combination, in load order.
+    pub bindings: Vec<Binding>,
+}
+
+/// Printed by `--check-conflicts --json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Report {
+    pub version: u32,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Report {
+    pub fn new(conflicts: Vec<Conflict>) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            conflicts,
+        }
+    }
+}
+
+/// Finds key combinations bound to actions with different descriptions. The
+/// same action listed by several sources is not a conflict, and neither are
+/// two applications' sheets binding the same keys, since only one application
+/// has focus at a time; COSMIC bindings are compared with each other and with
+/// every sheet.
+pub fn find(shortcuts: &[KeyBinding]) -> Vec<Conflict> {
+    let mut groups: Vec<Vec<&KeyBinding>> = Vec::new();
+    let mut index: HashMap<BindingId, usize> = HashMap::new();
+    for shortcut in shortcuts {
+        match index.get(&shortcut.id()) {
+            Some(&i) => groups[i].push(shortcut),
+            None => {
+                index.insert(shortcut.id(), groups.len());
+                groups.push(vec![shortcut]);
+            }
+        }
+    }
+
+    let is_cosmic = |s: &KeyBinding| s.sources.iter().any(|source| source == COSMIC_SOURCE);
+    groups
+        .into_iter()
+        .filter_map(|group| {
+            let cosmic: Vec<String> = group
+                .iter()
+                .filter(|s| is_cosmic(s))
+                .map(|s| s.description.to_lowercase())
+                .collect();
+            if cosmic.is_empty() {
+                return None;
+            }
+            // Sheets only count where they disagree with COSMIC
+            Some(
+                group
+                    .into_iter()
+                    .filter(|s| {
+                        is_cosmic(s) || cosmic.iter().any(|d| *d != s.description.to_lowercase())
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .filter(|group| {
+            let first = group[0].description.to_lowercase();
+            group.iter().any(|s| s.description.to_lowercase() != first)
+        })
+        .map(|group| Conflict {
+            keys: group[0].display_label(),
+            bindings: group.into_iter().map(Binding::from_shortcut).collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::{Modifiers, ShortcutCategory};
+
+    fn binding(key: u32, description: &str, source: &str) -> KeyBinding {
+        let modifiers = Modifiers {
+            logo: true,
+            ..Modifiers::new()
+        };
+        KeyBinding {
+            category: ShortcutCategory::Applications,
+            sources: vec![source.to_string()],
+            ..KeyBinding::for_test(modifiers, Some(key), description)
+        }
+    }
+
+    #[test]
+    fn find_reports_different_actions_only() {
+        let shortcuts = [
+            binding(0x71, "Close window", "COSMIC"),
+            binding(0x74, "Open terminal", "COSMIC"),
+            binding(0x71, "Quit", "Firefox"),
+            binding(0x74, "open terminal", "Dotfiles"),
+            binding(0x66, "Find", "Firefox"),
+            binding(0x66, "Forward", "Vim"),
+        ];
+
+        let conflicts = find(&shortcuts);
+        assert_eq!(conflicts.len(), 1);
+        let descriptions: Vec<&str> = conflicts[0]
+            .bindings
+            .iter()
+            .map(|b| b.description.as_str())
+            .collect();
+        assert_eq!(descriptions, ["Close window", "Quit"]);
+    }
+}
@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: MIT
+
+//! Client for the optional `keypeek-daemon` helper, which reports Super key state.
+
+use futures_util::Stream;
+use std::path::PathBuf;
+use tokio::io::AsyncBufReadExt;
+
+/// Key state changes reported by the daemon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DaemonEvent {
+    SuperDown,
+    SuperUp,
+    /// Any other key was pressed; which one is deliberately not reported.
+    OtherKey,
+}
+
+impl DaemonEvent {
+    fn parse(line: &str) -> Option<Self> {
+        match line.trim() {
+            "super-down" => Some(Self::SuperDown),
+            "super-up" => Some(Self::SuperUp),
+            "other-key" => Some(Self::OtherKey),
+            _ => None,
+        }
+    }
+}
+
+/// Path of the daemon's socket, in the user's runtime directory. The daemon
+/// doesn't start without one, so neither is there anything to connect to.
+pub fn socket_path() -> Option<PathBuf> {
+    std::env::var_os("XDG_RUNTIME_DIR").map(|dir| PathBuf::from(dir).join("keypeek-daemon.sock"))
+}
+
+/// Connects to the daemon and streams its events until it disconnects.
+pub async fn connect() -> std::io::Result<impl Stream<Item = DaemonEvent>> {
+    let path = socket_path().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::NotFound, "XDG_RUNTIME_DIR is not set")
+    })?;
+    let stream = tokio::net::UnixStream::connect(path).await?;
+    log::info!("connected to keypeek-daemon");
+
+    let lines = tokio::io::BufReader::new(stream).lines();
+    Ok(futures_util::stream::unfold(
+        lines,
+        |mut lines| async move {
+            loop {
+                match lines.next_line().await {
+                    Ok(Some(line)) => {
+                        if let Some(event) = DaemonEvent::parse(&line) {
+                            return Some((event, lines));
+                        }
+                    }
+                    _ => {
+                        log::info!("disconnected from keypeek-daemon");
+                        return None;
+                    }
+                }
+            }
+        },
+    ))
+}
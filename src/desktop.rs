@@ -0,0 +1,197 @@
+// SPDX-License-Identifier: MIT
+
+//! Lookup of the desktop entries (`.desktop` files) that spawn commands launch.
+//!
+//! Entries are read from the XDG data directories on first use and cached
+//! until [`release`] is called.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// The parts of a desktop entry shown in the shortcut list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DesktopEntry {
+    /// `Name`, localized for the current locale.
+    pub name: Option<String>,
+    /// `Icon`: an icon name or an absolute path to an image.
+    pub icon: Option<String>,
+}
+
+/// Desktop entries, keyed by the file name of the executable they run.
+static ENTRIES: Mutex<Option<Arc<HashMap<String, DesktopEntry>>>> = Mutex::new(None);
+
+/// Desktop entry of the application a command line starts, if one is installed.
+pub fn lookup(command: &str) -> Option<DesktopEntry> {
+    let program = executable_name(command)?;
+    let entries = {
+        let mut cached = ENTRIES.lock().unwrap_or_else(|e| e.into_inner());
+        cached.get_or_insert_with(|| Arc::new(scan())).clone()
+    };
+    entries.get(program).cloned()
+}
+
+/// Drops the cached entries; they are scanned again on the next lookup.
+pub fn release() {
+    *ENTRIES.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// File name of the program a command line runs, e.g. `firefox` for
+/// `env MOZ_X=1 /usr/bin/firefox --new-window`.
+fn executable_name(command: &str) -> Option<&str> {
+    Path::new(crate::command::program(command)?)
+        .file_name()?
+        .to_str()
+}
+
+/// Directories holding desktop entries, most important first.
+fn application_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(data) = dirs::data_dir() {
+        dirs.push(data.join("applications"));
+    }
+    let system = std::env::var("XDG_DATA_DIRS")
+        .unwrap_or_else(|_| String::from("/usr/local/share:/usr/share"));
+    dirs.extend(
+        system
+            .split(':')
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| Path::new(dir).join("applications")),
+    );
+    dirs
+}
+
+/// Locale names to look for in `Name[...]` keys, most specific first, following
+/// the desktop entry specification: `lang_COUNTRY`, then `lang`.
+fn locales() -> Vec<String> {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+    // Drop the encoding and modifier, e.g. "de_DE.UTF-8@euro" -> "de_DE".
+    let locale = locale.split(['.', '@']).next().unwrap_or_default();
+
+    let mut locales = Vec::new();
+    if !locale.is_empty() && locale != "C" && locale != "POSIX" {
+        locales.push(locale.to_string());
+        if let Some((lang, _)) = locale.split_once('_') {
+            locales.push(lang.to_string());
+        }
+    }
+    locales
+}
+
+fn scan() -> HashMap<String, DesktopEntry> {
+    let locales = locales();
+    let mut entries = HashMap::new();
+    for dir in application_dirs() {
+        let Ok(files) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in files.filter_map(|file| file.ok().map(|file| file.path())) {
+            if path.extension().is_none_or(|ext| ext != "desktop") {
+                continue;
+            }
+            let Ok(text) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            if let Some((exec, entry)) = parse(&text, &locales) {
+                if let Some(program) = executable_name(&exec) {
+                    // Earlier directories take precedence over later ones.
+                    entries.entry(program.to_string()).or_insert(entry);
+                }
+            }
+        }
+    }
+    log::debug!("found {} desktop entries", entries.len());
+    entries
+}
+
+/// Reads `Exec`, `Name` and `Icon` from the `[Desktop Entry]` group.
+fn parse(text: &str, locales: &[String]) -> Option<(String, DesktopEntry)> {
+    let mut in_entry = false;
+    let mut exec = None;
+    let mut icon = None;
+    // Best name so far, with its rank: an index into `locales`, or
+    // `locales.len()` for the unlocalized name.
+    let mut name: Option<(usize, String)> = None;
+
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key.trim() {
+            "Exec" => exec = Some(value),
+            "Icon" => icon = Some(value).filter(|icon| !icon.is_empty()),
+            key => {
+                let rank = match key.strip_prefix("Name") {
+                    Some("") => locales.len(),
+                    Some(localized) => {
+                        let Some(locale) = localized
+                            .strip_prefix('[')
+                            .and_then(|rest| rest.strip_suffix(']'))
+                        else {
+                            continue;
+                        };
+                        match locales.iter().position(|l| l == locale) {
+                            Some(rank) => rank,
+                            None => continue,
+                        }
+                    }
+                    None => continue,
+                };
+                if name.as_ref().is_none_or(|(best, _)| rank < *best) {
+                    name = Some((rank, value));
+                }
+            }
+        }
+    }
+
+    Some((
+        exec?,
+        DesktopEntry {
+            name: name.map(|(_, name)| name).filter(|name| !name.is_empty()),
+            icon,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn executable_name_skips_env_and_paths() {
+        assert_eq!(executable_name("firefox --new-window"), Some("firefox"));
+        assert_eq!(
+            executable_name("env GDK_BACKEND=x11 /usr/bin/gimp %U"),
+            Some("gimp")
+        );
+        assert_eq!(executable_name("  "), None);
+    }
+
+    #[test]
+    fn parse_prefers_most_specific_locale_and_ignores_other_groups() {
+        let text = "[Desktop Entry]\nName=Files\nName[de]=Dateien\nName[de_AT]=Dateien (AT)\n\
+                    Name[fr]=Fichiers\nExec=cosmic-files %U\nIcon=com.system76.CosmicFiles\n\
+                    [Desktop Action new]\nName=New window\nExec=cosmic-files --new\nIcon=other\n";
+        let locales = vec![String::from("de_AT"), String::from("de")];
+
+        let (exec, entry) = parse(text, &locales).unwrap();
+        assert_eq!(exec, "cosmic-files %U");
+        assert_eq!(entry.name.as_deref(), Some("Dateien (AT)"));
+        assert_eq!(entry.icon.as_deref(), Some("com.system76.CosmicFiles"));
+
+        let (_, entry) = parse(text, &[]).unwrap();
+        assert_eq!(entry.name.as_deref(), Some("Files"));
+    }
+}
@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: MIT
+
+//! What the detail page tells about a shortcut beyond its row: a longer
+//! explanation from the embedded `resources/action-details.toml` and the entry
+//! the binding would have in the COSMIC shortcuts configuration.
+
+use crate::shortcuts::{COSMIC_SOURCE, KeyBinding, ShortcutCategory};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use xkbcommon::xkb;
+
+const DATA: &str = include_str!("../resources/action-details.toml");
+
+static DETAILS: LazyLock<Details> = LazyLock::new(|| match toml::from_str(DATA) {
+    Ok(details) => details,
+    Err(e) => {
+        log::error!("Failed to read the action explanations: {}", e);
+        Details::default()
+    }
+});
+
+#[derive(Default, Deserialize)]
+struct Details {
+    /// Explanations of compositor actions.
+    #[serde(default)]
+    actions: HashMap<String, String>,
+    /// Explanations of system actions, which are configured as `System(..)`.
+    #[serde(default)]
+    system: HashMap<String, String>,
+}
+
+/// Name of the action of a COSMIC shortcut, e.g. `Focus` for `Focus(Left)`, or
+/// `Spawn` for custom commands.
+fn action_name(shortcut: &KeyBinding) -> Option<&str> {
+    if !shortcut
+        .sources
+        .iter()
+        .any(|source| source == COSMIC_SOURCE)
+    {
+        return None;
+    }
+    if shortcut.category == ShortcutCategory::Custom {
+        return Some("Spawn");
+    }
+    shortcut.command.split('(').next()
+}
+
+/// Longer explanation of what the shortcut does.
+pub fn explanation(shortcut: &KeyBinding) -> Option<&'static str> {
+    let name = action_name(shortcut)?;
+    DETAILS
+        .actions
+        .get(name)
+        .or_else(|| DETAILS.system.get(name))
+        .map(String::as_str)
+}
+
+/// The binding as written in the COSMIC shortcuts configuration, e.g.
+/// `(modifiers: [Super], key: "t"): Spawn("cosmic-term")`.
+pub fn config_entry(shortcut: &KeyBinding) -> Option<String> {
+    let name = action_name(shortcut)?;
+    let m = &shortcut.modifiers;
+    let modifiers: Vec<&str> = [
+        (m.logo, "Super"),
+        (m.ctrl, "Ctrl"),
+        (m.alt, "Alt"),
+        (m.shift, "Shift"),
+    ]
+    .into_iter()
+    .filter_map(|(held, modifier)| held.then_some(modifier))
+    .collect();
+    let key = shortcut
+        .key
+        .map(|key| format!(", key: {:?}", xkb::keysym_get_name(key)))
+        .unwrap_or_default();
+
+    let action = if name == "Spawn" {
+        format!("Spawn({:?})", shortcut.command)
+    } else if DETAILS.system.contains_key(name) {
+        format!("System({})", shortcut.command)
+    } else {
+        shortcut.command.clone()
+    };
+    Some(format!(
+        "(modifiers: [{}]{key}): {action}",
+        modifiers.join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::Modifiers;
+
+    #[test]
+    fn explains_and_writes_config_entries_of_cosmic_shortcuts() {
+        let modifiers = Modifiers {
+            logo: true,
+            ..Modifiers::new()
+        };
+        let mut shortcut = KeyBinding {
+            command: String::from("cosmic-term"),
+            category: ShortcutCategory::Custom,
+            sources: vec![String::from(COSMIC_SOURCE)],
+            ..KeyBinding::for_test(modifiers, Some(0x74), "Terminal")
+        };
+        assert!(explanation(&shortcut).is_some());
+        assert_eq!(
+            config_entry(&shortcut).as_deref(),
+            Some(r#"(modifiers: [Super], key: "t"): Spawn("cosmic-term")"#)
+        );
+
+        shortcut.category = ShortcutCategory::Applications;
+        shortcut.command = String::from("Launcher");
+        assert_eq!(
+            config_entry(&shortcut).as_deref(),
+            Some(r#"(modifiers: [Super], key: "t"): System(Launcher)"#)
+        );
+
+        shortcut.sources = vec![String::from("Firefox")];
+        assert_eq!(explanation(&shortcut), None);
+        assert_eq!(config_entry(&shortcut), None);
+    }
+}
@@ -0,0 +1,65 @@
+// SPDX-License-Identifier: MIT
+
+//! Why the COSMIC shortcuts couldn't be loaded, specific enough for the popup
+//! to suggest a fix.
+
+use crate::fl;
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShortcutsError {
+    /// The COSMIC settings config couldn't be opened, e.g. outside COSMIC.
+    ConfigUnavailable(String),
+    /// A shortcut configuration file isn't valid RON, or uses actions this
+    /// build doesn't know. Only that file's shortcuts are missing.
+    ParseFailure { source: PathBuf, detail: String },
+    /// A shortcut configuration file or directory can't be read.
+    PermissionDenied(PathBuf),
+    /// Running under Flatpak without access to the COSMIC configuration.
+    SandboxBlocked,
+}
+
+impl ShortcutsError {
+    /// What the user can do about it, for the popup and the command line.
+    pub fn remedy(&self) -> String {
+        match self {
+            Self::ConfigUnavailable(_) => fl!("error-config-unavailable-remedy"),
+            Self::ParseFailure { source, .. } => fl!(
+                "error-parse-failure-remedy",
+                path = source.display().to_string()
+            ),
+            Self::PermissionDenied(path) => fl!(
+                "error-permission-denied-remedy",
+                path = path.display().to_string()
+            ),
+            Self::SandboxBlocked => fl!("error-sandbox-blocked-remedy"),
+        }
+    }
+}
+
+impl fmt::Display for ShortcutsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ConfigUnavailable(detail) => {
+                write!(f, "the COSMIC shortcut settings are unavailable: {detail}")
+            }
+            Self::ParseFailure { source, detail } => {
+                write!(
+                    f,
+                    "{} is not a valid shortcut file: {detail}",
+                    source.display()
+                )
+            }
+            Self::PermissionDenied(path) => write!(f, "permission denied: {}", path.display()),
+            Self::SandboxBlocked => {
+                write!(
+                    f,
+                    "the sandbox blocks access to the COSMIC shortcut settings"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShortcutsError {}
@@ -0,0 +1,229 @@
+// SPDX-License-Identifier: MIT
+
+//! The event bus: one subscription that runs every background source — config
+//! changes, file watches, logind signals, links and the daemon — and forwards
+//! what they report as typed [`Event`]s.
+//!
+//! Each source is supervised: when it fails or ends, for example because a
+//! watched directory doesn't exist yet or the daemon isn't running, it is
+//! restarted with an increasing delay, unless it failed in a way retrying
+//! can't fix, such as logind not knowing the session. Events are sent through
+//! a bounded channel, so a busy applet slows sources down instead of queueing
+//! without limit; file changes are coalesced, as one reload covers any number
+//! of them.
+
+use crate::config::Config;
+#[cfg(feature = "overlay")]
+use crate::daemon::{self, DaemonEvent};
+use crate::launcher::APP_ID;
+use crate::layers::SHORTCUTS_CONFIG;
+use crate::link::{self, Link};
+#[cfg(feature = "dbus")]
+use crate::session::{self, Resume};
+use anyhow::{Context, Result};
+use cosmic::cosmic_config::{self, CosmicConfigEntry};
+use cosmic::iced::Subscription;
+use futures_util::channel::mpsc::Sender;
+use futures_util::{SinkExt, Stream, StreamExt};
+use notify::{RecursiveMode, Watcher};
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+/// Events waiting to be handled before sources have to wait.
+const CAPACITY: usize = 16;
+
+const MIN_RESTART_DELAY: Duration = Duration::from_secs(1);
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(30);
+
+/// Error of a source that would fail the same way every time it is started;
+/// [`supervise`] stops restarting it.
+#[cfg(feature = "dbus")]
+#[derive(Debug)]
+struct Permanent(anyhow::Error);
+
+#[cfg(feature = "dbus")]
+impl std::fmt::Display for Permanent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#}", self.0)
+    }
+}
+
+#[cfg(feature = "dbus")]
+impl std::error::Error for Permanent {}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// The applet's config changed.
+    Config(Config),
+    /// COSMIC shortcuts or custom sheets changed on disk.
+    ShortcutsChanged,
+    /// The session resumed; sources may have changed in the meantime.
+    #[cfg(feature = "dbus")]
+    Resumed(Resume),
+    Link(Link),
+    #[cfg(feature = "overlay")]
+    Daemon(DaemonEvent),
+}
+
+/// Which sources run. The bus restarts with every source when this changes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Sources {
+    #[cfg(feature = "sheets")]
+    pub sheets_dir: Option<PathBuf>,
+    /// Receive `keypeek://` links; only one instance can.
+    pub links: bool,
+    /// Follow the Super key through `keypeek-daemon`.
+    #[cfg(feature = "overlay")]
+    pub daemon: bool,
+}
+
+type Source = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+pub fn subscription(sources: Sources) -> Subscription<Event> {
+    Subscription::run_with_id(
+        sources.clone(),
+        cosmic::iced::stream::channel(CAPACITY, move |tx| async move {
+            let mut running: Vec<Source> = vec![
+                supervise("config", tx.clone(), watch_config),
+                supervise("COSMIC shortcuts", tx.clone(), |tx| {
+                    let dir = dirs::config_dir().map(|config| config.join(SHORTCUTS_CONFIG));
+                    watch_dir(tx, dir, RecursiveMode::Recursive)
+                }),
+            ];
+            #[cfg(feature = "dbus")]
+            running.push(supervise("session", tx.clone(), |tx| async move {
+                let resumes = session::resumes().await.map_err(|e| {
+                    if session::is_permanent(&e) {
+                        anyhow::Error::new(Permanent(e.into()))
+                    } else {
+                        e.into()
+                    }
+                })?;
+                forward(tx, resumes, Event::Resumed).await
+            }));
+            #[cfg(feature = "sheets")]
+            let sheets_dir = sources.sheets_dir.clone();
+            #[cfg(feature = "sheets")]
+            running.push(supervise("sheets", tx.clone(), move |tx| {
+                let dir = sheets_dir.clone();
+                async move {
+                    // Create the directory so sheets added later are picked up too.
+                    if let Some(dir) = &dir {
+                        let _ = std::fs::create_dir_all(dir);
+                    }
+                    watch_dir(tx, dir, RecursiveMode::NonRecursive).await
+                }
+            }));
+            if sources.links {
+                running.push(supervise("links", tx.clone(), |tx| async move {
+                    forward(tx, link::listen()?, Event::Link).await
+                }));
+            }
+            #[cfg(feature = "overlay")]
+            if sources.daemon {
+                running.push(supervise("keypeek-daemon", tx.clone(), |tx| async move {
+                    forward(tx, daemon::connect().await?, Event::Daemon).await
+                }));
+            }
+
+            // Sources stop with the subscription, as they run in its task.
+            futures_util::future::join_all(running).await;
+        }),
+    )
+}
+
+/// Runs `source` until the subscription ends, restarting it whenever it fails
+/// or ends. The delay doubles up to [`MAX_RESTART_DELAY`] and is reset once a
+/// source ran for longer than that.
+fn supervise<F, Fut>(name: &'static str, tx: Sender<Event>, mut source: F) -> Source
+where
+    F: FnMut(Sender<Event>) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<()>> + Send,
+{
+    Box::pin(async move {
+        let mut delay = MIN_RESTART_DELAY;
+        loop {
+            let started = Instant::now();
+            match source(tx.clone()).await {
+                Ok(()) => log::info!("{name} source ended"),
+                #[cfg(feature = "dbus")]
+                Err(e) if e.is::<Permanent>() => {
+                    log::warn!("{name} source failed, not retrying: {e:#}");
+                    return;
+                }
+                Err(e) => log::warn!("{name} source failed: {e:#}"),
+            }
+            if tx.is_closed() {
+                return;
+            }
+
+            if started.elapsed() > MAX_RESTART_DELAY {
+                delay = MIN_RESTART_DELAY;
+            }
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(MAX_RESTART_DELAY);
+        }
+    })
+}
+
+/// Sends every item of `stream` as an event, waiting while the bus is full.
+async fn forward<T>(
+    mut tx: Sender<Event>,
+    stream: impl Stream<Item = T>,
+    event: fn(T) -> Event,
+) -> Result<()> {
+    let mut stream = std::pin::pin!(stream);
+    while let Some(item) = stream.next().await {
+        tx.send(event(item)).await.context("event bus closed")?;
+    }
+    Ok(())
+}
+
+/// Reports [`Event::ShortcutsChanged`] whenever a file in `dir` is created,
+/// changed or removed. Fails if the directory can't be watched, e.g. before it
+/// exists.
+async fn watch_dir(mut tx: Sender<Event>, dir: Option<PathBuf>, mode: RecursiveMode) -> Result<()> {
+    let dir = dir.context("no directory to watch")?;
+    let (changed, mut changes) = tokio::sync::mpsc::channel(1);
+    let mut watcher = notify::RecommendedWatcher::new(
+        move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                let kind = event.kind;
+                if kind.is_modify() || kind.is_create() || kind.is_remove() {
+                    // A pending change already triggers a reload.
+                    let _ = changed.try_send(());
+                }
+            }
+        },
+        notify::Config::default().with_poll_interval(Duration::from_millis(100)),
+    )?;
+    watcher
+        .watch(&dir, mode)
+        .with_context(|| format!("failed to watch {}", dir.display()))?;
+
+    while changes.recv().await.is_some() {
+        tx.send(Event::ShortcutsChanged)
+            .await
+            .context("event bus closed")?;
+    }
+    Ok(())
+}
+
+/// Reports the config whenever it changes, with system defaults applied.
+async fn watch_config(mut tx: Sender<Event>) -> Result<()> {
+    let handler = cosmic_config::Config::new(APP_ID, Config::VERSION)?;
+    let (changed, mut changes) = tokio::sync::mpsc::channel(1);
+    let _watcher = handler.watch(move |_, _keys| {
+        let _ = changed.try_send(());
+    })?;
+
+    while changes.recv().await.is_some() {
+        let config = Config::read(&handler, APP_ID);
+        tx.send(Event::Config(config))
+            .await
+            .context("event bus closed")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,199 @@
+// SPDX-License-Identifier: MIT
+
+//! Renders the shortcut list to files that can be printed or shared.
+
+use crate::format;
+use anyhow::{Context, Result};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+#[cfg(feature = "export")]
+mod image;
+#[cfg(feature = "export")]
+mod pdf;
+
+#[cfg(feature = "export")]
+pub use image::{ImageFormat, save_image};
+#[cfg(feature = "export")]
+pub use pdf::render_pdf;
+
+/// A category of shortcuts, ready to be written out.
+#[derive(Debug, Clone)]
+pub struct ExportSection {
+    pub title: String,
+    pub rows: Vec<ExportRow>,
+}
+
+/// One shortcut, with redaction already applied.
+#[derive(Debug, Clone)]
+pub struct ExportRow {
+    pub keys: String,
+    pub description: String,
+}
+
+/// Text formats the list can be copied to the clipboard as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextFormat {
+    /// Keys and descriptions in aligned columns.
+    Plain,
+    /// One Markdown table per section.
+    Markdown,
+}
+
+impl TextFormat {
+    /// Renders the sections without a title, for pasting into notes or chats.
+    pub fn render(self, sections: &[ExportSection]) -> String {
+        match self {
+            TextFormat::Plain => render_plain(sections),
+            TextFormat::Markdown => markdown_tables(sections),
+        }
+    }
+}
+
+/// Saves shortcuts in the exchange format to `dir`, or the documents directory,
+/// so they can be compared on another machine.
+pub fn save_profile(document: &format::Document, dir: Option<PathBuf>) -> Result<PathBuf> {
+    let dir = match dir {
+        Some(dir) => {
+            std::fs::create_dir_all(&dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+            dir
+        }
+        None => dirs::document_dir()
+            .or_else(dirs::home_dir)
+            .context("no documents or home directory")?,
+    };
+    let path = dir.join("keypeek-profile.json");
+    std::fs::write(&path, document.to_json())
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Escapes text for use inside SVG/XML elements.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders the sections as a standalone HTML page with one table per category.
+pub fn render_html(title: &str, sections: &[ExportSection]) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n<style>\n\
+         body {{ font-family: sans-serif; margin: 2rem auto; max-width: 60rem; padding: 0 1rem; }}\n\
+         table {{ border-collapse: collapse; width: 100%; margin-bottom: 1.5rem; }}\n\
+         td {{ padding: 0.3rem 0.5rem; border-bottom: 1px solid #ddd; vertical-align: top; }}\n\
+         td:first-child {{ white-space: nowrap; font-weight: bold; width: 1%; }}\n\
+         kbd {{ border: 1px solid #bbb; border-radius: 4px; padding: 0 0.3rem; }}\n\
+         @media (prefers-color-scheme: dark) {{ body {{ background: #1b1b1b; color: #ddd; }} td {{ border-color: #333; }} }}\n\
+         </style>\n</head>\n<body>\n<h1>{title}</h1>\n",
+        title = escape(title)
+    );
+
+    for section in sections {
+        let _ = writeln!(html, "<h2>{}</h2>\n<table>", escape(&section.title));
+        for row in &section.rows {
+            let keys = row
+                .keys
+                .split(" + ")
+                .map(|key| format!("<kbd>{}</kbd>", escape(key)))
+                .collect::<Vec<_>>()
+                .join(" + ");
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td></tr>",
+                keys,
+                escape(&row.description)
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Renders the sections as a Markdown document with one table per category.
+pub fn render_markdown(title: &str, sections: &[ExportSection]) -> String {
+    format!("# {title}\n{}", markdown_tables(sections))
+}
+
+/// One Markdown table per section, under a heading unless it has no title.
+fn markdown_tables(sections: &[ExportSection]) -> String {
+    let mut md = String::new();
+    for section in sections {
+        if !section.title.is_empty() {
+            let _ = write!(md, "\n## {}\n", section.title);
+        }
+        md.push_str("\n| Keys | Action |\n| --- | --- |\n");
+        for row in &section.rows {
+            let keys = row
+                .keys
+                .split(" + ")
+                .map(|key| format!("<kbd>{}</kbd>", escape(key)))
+                .collect::<Vec<_>>()
+                .join(" + ");
+            let description = escape(&row.description).replace('|', "\\|");
+            let _ = writeln!(md, "| {keys} | {description} |");
+        }
+    }
+    md
+}
+
+/// Renders the sections as plain text, with the descriptions of all sections
+/// aligned in one column.
+fn render_plain(sections: &[ExportSection]) -> String {
+    let width = sections
+        .iter()
+        .flat_map(|section| &section.rows)
+        .map(|row| row.keys.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut text = String::new();
+    for (i, section) in sections.iter().enumerate() {
+        if i > 0 {
+            text.push('\n');
+        }
+        if !section.title.is_empty() {
+            let _ = writeln!(text, "{}", section.title);
+        }
+        for row in &section.rows {
+            let _ = writeln!(text, "  {:width$}  {}", row.keys, row.description);
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_text_aligns_descriptions() {
+        let row = |keys: &str, description: &str| ExportRow {
+            keys: keys.to_string(),
+            description: description.to_string(),
+        };
+        let sections = [
+            ExportSection {
+                title: String::from("Windows"),
+                rows: vec![row("Super + Q", "Close window")],
+            },
+            ExportSection {
+                title: String::from("Media"),
+                rows: vec![row("Mute", "Mute audio")],
+            },
+        ];
+
+        assert_eq!(
+            TextFormat::Plain.render(&sections),
+            "Windows\n  Super + Q  Close window\n\nMedia\n  Mute       Mute audio\n"
+        );
+    }
+}
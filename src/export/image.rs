@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: MIT
+
+//! SVG and PNG cheat sheets, built with the `export` feature.
+
+use super::{ExportSection, escape};
+use anyhow::{Context, Result};
+use resvg::{tiny_skia, usvg};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Image formats the list can be saved as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Svg => "svg",
+        }
+    }
+}
+
+const SVG_WIDTH: u32 = 800;
+const SVG_MARGIN: u32 = 24;
+const SVG_KEYS_WIDTH: u32 = 280;
+const SVG_TITLE_HEIGHT: u32 = 48;
+const SVG_HEADING_HEIGHT: u32 = 40;
+const SVG_ROW_HEIGHT: u32 = 24;
+
+/// Draws the sections as a two-column SVG cheat sheet.
+pub fn render_svg(title: &str, sections: &[ExportSection]) -> String {
+    let rows: u32 = sections.iter().map(|s| s.rows.len() as u32).sum();
+    let height = SVG_MARGIN * 2
+        + SVG_TITLE_HEIGHT
+        + sections.len() as u32 * SVG_HEADING_HEIGHT
+        + rows * SVG_ROW_HEIGHT;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{SVG_WIDTH}" height="{height}" viewBox="0 0 {SVG_WIDTH} {height}" font-family="sans-serif">"#
+    );
+    let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+
+    let mut y = SVG_MARGIN + 28;
+    let _ = writeln!(
+        svg,
+        r#"<text x="{SVG_MARGIN}" y="{y}" font-size="24" font-weight="bold">{}</text>"#,
+        escape(title)
+    );
+    y += SVG_TITLE_HEIGHT - 28;
+
+    for section in sections {
+        y += SVG_HEADING_HEIGHT - 12;
+        let _ = writeln!(
+            svg,
+            r##"<text x="{SVG_MARGIN}" y="{y}" font-size="18" font-weight="bold" fill="#1f5f8b">{}</text>"##,
+            escape(&section.title)
+        );
+        y += 12;
+
+        for row in &section.rows {
+            y += SVG_ROW_HEIGHT;
+            let _ = writeln!(
+                svg,
+                r#"<text x="{SVG_MARGIN}" y="{y}" font-size="14" font-weight="bold">{}</text>"#,
+                escape(&row.keys)
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{}" y="{y}" font-size="14">{}</text>"#,
+                SVG_MARGIN + SVG_KEYS_WIDTH,
+                escape(&row.description)
+            );
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Saves the sections as an image in the user's pictures directory, returning its path.
+/// Earlier exports are kept: the name gets a number if it's taken.
+pub fn save_image(title: &str, sections: &[ExportSection], format: ImageFormat) -> Result<PathBuf> {
+    let dir = dirs::picture_dir()
+        .or_else(dirs::home_dir)
+        .context("no pictures or home directory")?;
+
+    let svg = render_svg(title, sections);
+    let data = match format {
+        ImageFormat::Svg => svg.into_bytes(),
+        ImageFormat::Png => {
+            let mut options = usvg::Options::default();
+            options.fontdb_mut().load_system_fonts();
+
+            let tree = usvg::Tree::from_str(&svg, &options).context("failed to parse SVG")?;
+            let size = tree.size().to_int_size();
+            let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+                .context("image is too large")?;
+            resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+            pixmap.encode_png().context("failed to encode PNG")?
+        }
+    };
+
+    let (mut file, path) = create_unique(&dir, "keypeek-shortcuts", format.extension())?;
+    file.write_all(&data)
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Creates `stem.extension` in `dir`, or `stem-2.extension` and so on if it
+/// exists, without ever replacing a file.
+fn create_unique(dir: &Path, stem: &str, extension: &str) -> Result<(File, PathBuf)> {
+    for n in 1.. {
+        let name = match n {
+            1 => format!("{stem}.{extension}"),
+            n => format!("{stem}-{n}.{extension}"),
+        };
+        let path = dir.join(name);
+        match File::create_new(&path) {
+            Ok(file) => return Ok((file, path)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                return Err(e).with_context(|| format!("failed to create {}", path.display()));
+            }
+        }
+    }
+    unreachable!("ran out of file names")
+}
@@ -0,0 +1,195 @@
+// SPDX-License-Identifier: MIT
+
+//! PDF cheat sheets for printing, built with the `export` feature.
+
+use super::ExportSection;
+
+/// A4 page size in PDF points.
+const PDF_PAGE_WIDTH: f32 = 595.0;
+const PDF_PAGE_HEIGHT: f32 = 842.0;
+const PDF_MARGIN: f32 = 50.0;
+const PDF_KEYS_WIDTH: f32 = 200.0;
+const PDF_TITLE_HEIGHT: f32 = 36.0;
+const PDF_HEADING_HEIGHT: f32 = 28.0;
+const PDF_ROW_HEIGHT: f32 = 16.0;
+
+/// A line of text placed on a PDF page.
+struct PdfText {
+    x: f32,
+    y: f32,
+    size: f32,
+    bold: bool,
+    text: String,
+}
+
+/// Lays the sections out on A4 pages.
+///
+/// A category that doesn't fit in the rest of a page starts on a new one, unless
+/// it wouldn't fit on a page of its own either; categories split across pages
+/// repeat their heading.
+fn paginate(title: &str, sections: &[ExportSection]) -> Vec<Vec<PdfText>> {
+    let top = PDF_PAGE_HEIGHT - PDF_MARGIN;
+    let usable = PDF_PAGE_HEIGHT - 2.0 * PDF_MARGIN;
+
+    let mut pages = vec![Vec::new()];
+    let mut y = top - PDF_TITLE_HEIGHT;
+    pages[0].push(PdfText {
+        x: PDF_MARGIN,
+        y: top - 20.0,
+        size: 20.0,
+        bold: true,
+        text: title.to_string(),
+    });
+
+    for section in sections {
+        let needed = PDF_HEADING_HEIGHT + section.rows.len() as f32 * PDF_ROW_HEIGHT;
+        if needed > y - PDF_MARGIN && needed <= usable {
+            pages.push(Vec::new());
+            y = top;
+        }
+
+        let heading = |page: &mut Vec<PdfText>, y: &mut f32, continued: bool| {
+            *y -= PDF_HEADING_HEIGHT;
+            let text = if continued {
+                format!("{} (cont.)", section.title)
+            } else {
+                section.title.clone()
+            };
+            page.push(PdfText {
+                x: PDF_MARGIN,
+                y: *y + 8.0,
+                size: 14.0,
+                bold: true,
+                text,
+            });
+        };
+
+        heading(pages.last_mut().unwrap(), &mut y, false);
+        for row in &section.rows {
+            if y - PDF_ROW_HEIGHT < PDF_MARGIN {
+                pages.push(Vec::new());
+                y = top;
+                heading(pages.last_mut().unwrap(), &mut y, true);
+            }
+
+            y -= PDF_ROW_HEIGHT;
+            let page = pages.last_mut().unwrap();
+            page.push(PdfText {
+                x: PDF_MARGIN,
+                y,
+                size: 10.0,
+                bold: true,
+                text: row.keys.clone(),
+            });
+            page.push(PdfText {
+                x: PDF_MARGIN + PDF_KEYS_WIDTH,
+                y,
+                size: 10.0,
+                bold: false,
+                text: row.description.clone(),
+            });
+        }
+    }
+
+    pages
+}
+
+/// Renders the sections as a paginated PDF document using the standard Helvetica fonts.
+pub fn render_pdf(title: &str, sections: &[ExportSection]) -> Vec<u8> {
+    let pages = paginate(title, sections);
+
+    let mut out = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+
+    // Objects 1-4 are the catalog, page tree and fonts; each page then takes two
+    // objects, the page itself and its content stream.
+    let kids = (0..pages.len())
+        .map(|i| format!("{} 0 R", 5 + 2 * i))
+        .collect::<Vec<_>>()
+        .join(" ");
+    push_object(&mut out, &mut offsets, b"<< /Type /Catalog /Pages 2 0 R >>");
+    push_object(
+        &mut out,
+        &mut offsets,
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids, pages.len()).as_bytes(),
+    );
+    push_object(
+        &mut out,
+        &mut offsets,
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>",
+    );
+    push_object(
+        &mut out,
+        &mut offsets,
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>",
+    );
+
+    for (i, page) in pages.iter().enumerate() {
+        let page_object = format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            PDF_PAGE_WIDTH,
+            PDF_PAGE_HEIGHT,
+            6 + 2 * i
+        );
+        push_object(&mut out, &mut offsets, page_object.as_bytes());
+
+        let mut content = Vec::new();
+        for text in page {
+            let font = if text.bold { "F2" } else { "F1" };
+            content.extend_from_slice(
+                format!("BT /{} {} Tf {} {} Td (", font, text.size, text.x, text.y).as_bytes(),
+            );
+            content.extend(pdf_string(&text.text));
+            content.extend_from_slice(b") Tj ET\n");
+        }
+
+        let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+        stream.extend(content);
+        stream.extend_from_slice(b"\nendstream");
+        push_object(&mut out, &mut offsets, &stream);
+    }
+
+    let xref_offset = out.len();
+    out.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1).as_bytes(),
+    );
+    for offset in &offsets {
+        out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    out.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            offsets.len() + 1,
+            xref_offset
+        )
+        .as_bytes(),
+    );
+
+    out
+}
+
+/// Appends the next numbered object, recording its offset for the xref table.
+fn push_object(out: &mut Vec<u8>, offsets: &mut Vec<usize>, body: &[u8]) {
+    offsets.push(out.len());
+    out.extend_from_slice(format!("{} 0 obj\n", offsets.len()).as_bytes());
+    out.extend_from_slice(body);
+    out.extend_from_slice(b"\nendobj\n");
+}
+
+/// Encodes text for a PDF string literal in the standard fonts' Latin-1 range,
+/// replacing other characters with '?'.
+fn pdf_string(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                bytes.push(b'\\');
+                bytes.push(c as u8);
+            }
+            c if (c as u32) < 0x100 => bytes.push(c as u8),
+            _ => bytes.push(b'?'),
+        }
+    }
+    bytes
+}
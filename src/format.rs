@@ -0,0 +1,260 @@
+// SPDX-License-Identifier: MIT
+
+//! Versioned JSON format for exchanging shortcuts.
+//!
+//! The format is described by `resources/keypeek-shortcuts.schema.json` and is
+//! shared by every feature that writes shortcuts out of the applet. Readers must
+//! ignore unknown fields; changes that would break existing readers bump
+//! [`FORMAT_VERSION`].
+
+use crate::shortcuts::{BindingId, KeyBinding, Modifiers, Pointer, ShortcutCategory};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use xkbcommon::xkb;
+
+/// Version written to the `version` field of every document.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A set of shortcuts and the sources they were read from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Document {
+    pub version: u32,
+    #[serde(default)]
+    pub sources: Vec<Source>,
+    #[serde(default)]
+    pub bindings: Vec<Binding>,
+}
+
+/// Where bindings come from, such as the COSMIC settings or a custom sheet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Source {
+    pub id: String,
+}
+
+/// Modifier keys, in the order they are displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Modifier {
+    Super,
+    Ctrl,
+    Alt,
+    AltGr,
+    Shift,
+}
+
+/// A single shortcut.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Binding {
+    #[serde(default)]
+    pub modifiers: Vec<Modifier>,
+    /// XKB keysym name, e.g. `t` or `Print`; absent for modifier-only bindings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Mouse, pen or pad button, or scroll direction, bound instead of a key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pointer: Option<Pointer>,
+    /// Non-keyboard combination written as-is, e.g. "Guide + A".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chord: Option<String>,
+    pub description: String,
+    /// Category id, see [`ShortcutCategory::id`].
+    pub category: String,
+    /// Ids of the sources defining this binding.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<String>,
+    /// Command run by custom (spawn) shortcuts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Free-form notes about the binding.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Other key combinations of the same action.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternates: Vec<Keys>,
+}
+
+/// A further key combination of a binding.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keys {
+    #[serde(default)]
+    pub modifiers: Vec<Modifier>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pointer: Option<Pointer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chord: Option<String>,
+}
+
+impl Keys {
+    fn from_id(id: &BindingId) -> Self {
+        Self {
+            modifiers: modifiers(&id.modifiers),
+            key: id.key.map(xkb::keysym_get_name),
+            pointer: id.pointer,
+            chord: id.chord.clone(),
+        }
+    }
+
+    /// The key combination as written in the list, e.g. "Super + Shift + t".
+    pub fn keys_label(&self) -> String {
+        if let Some(chord) = &self.chord {
+            return chord.clone();
+        }
+        let mut parts: Vec<&str> = self
+            .modifiers
+            .iter()
+            .map(|modifier| match modifier {
+                Modifier::Super => "Super",
+                Modifier::Ctrl => "Ctrl",
+                Modifier::Alt => "Alt",
+                Modifier::AltGr => "AltGr",
+                Modifier::Shift => "Shift",
+            })
+            .collect();
+        let pointer = self.pointer.map(|pointer| pointer.to_string());
+        parts.extend(self.key.as_deref());
+        parts.extend(pointer.as_deref());
+        parts.join(" + ")
+    }
+}
+
+/// The held modifiers, in display order.
+fn modifiers(m: &Modifiers) -> Vec<Modifier> {
+    [
+        (m.logo, Modifier::Super),
+        (m.ctrl, Modifier::Ctrl),
+        (m.alt, Modifier::Alt),
+        (m.altgr, Modifier::AltGr),
+        (m.shift, Modifier::Shift),
+    ]
+    .into_iter()
+    .filter_map(|(held, modifier)| held.then_some(modifier))
+    .collect()
+}
+
+impl Binding {
+    /// Converts a loaded shortcut; `command` is only kept for custom shortcuts.
+    pub fn from_shortcut(shortcut: &KeyBinding) -> Self {
+        Self {
+            modifiers: modifiers(&shortcut.modifiers),
+            key: shortcut.key.map(xkb::keysym_get_name),
+            pointer: shortcut.pointer,
+            chord: shortcut.chord.clone(),
+            description: shortcut.description.clone(),
+            category: shortcut.category.id().to_string(),
+            sources: shortcut.sources.clone(),
+            command: (shortcut.category == ShortcutCategory::Custom)
+                .then(|| shortcut.command.clone()),
+            notes: None,
+            alternates: shortcut.alternates.iter().map(Keys::from_id).collect(),
+        }
+    }
+
+    /// The binding's first key combination.
+    pub fn keys(&self) -> Keys {
+        Keys {
+            modifiers: self.modifiers.clone(),
+            key: self.key.clone(),
+            pointer: self.pointer,
+            chord: self.chord.clone(),
+        }
+    }
+
+    /// The first key combination as written in the list, e.g. "Super + Shift + t".
+    pub fn keys_label(&self) -> String {
+        self.keys().keys_label()
+    }
+
+    /// Every key combination of the binding, the first one first.
+    pub fn all_keys(&self) -> Vec<Keys> {
+        let mut keys = vec![self.keys()];
+        keys.extend(self.alternates.iter().cloned());
+        keys
+    }
+}
+
+impl Document {
+    /// Creates a document listing every source referenced by `bindings`.
+    pub fn new(bindings: Vec<Binding>) -> Self {
+        let mut sources: Vec<Source> = Vec::new();
+        for id in bindings.iter().flat_map(|binding| &binding.sources) {
+            if !sources.iter().any(|source| &source.id == id) {
+                sources.push(Source { id: id.clone() });
+            }
+        }
+
+        Self {
+            version: FORMAT_VERSION,
+            sources,
+            bindings,
+        }
+    }
+
+    /// Parses a document, rejecting versions newer than this build understands.
+    pub fn from_json(text: &str) -> Result<Self> {
+        let document: Self = serde_json::from_str(text).context("not a KeyPeek shortcuts file")?;
+        if document.version > FORMAT_VERSION {
+            bail!(
+                "format version {} is newer than the supported version {}",
+                document.version,
+                FORMAT_VERSION
+            );
+        }
+        Ok(document)
+    }
+
+    /// Serializes the document as indented JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("document is always serializable")
+    }
+
+    /// Serializes the document as JSON without whitespace, e.g. for QR codes.
+    pub fn to_compact_json(&self) -> String {
+        serde_json::to_string(self).expect("document is always serializable")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_round_trips_and_skips_empty_fields() {
+        let document = Document::new(vec![Binding {
+            modifiers: vec![Modifier::Super, Modifier::Shift],
+            key: Some(String::from("t")),
+            pointer: None,
+            chord: None,
+            description: String::from("Open a terminal"),
+            category: String::from("custom"),
+            sources: vec![String::from("COSMIC")],
+            command: Some(String::from("cosmic-term")),
+            notes: None,
+            alternates: vec![Keys {
+                modifiers: vec![Modifier::Ctrl, Modifier::Alt],
+                key: Some(String::from("t")),
+                pointer: None,
+                chord: None,
+            }],
+        }]);
+
+        let json = document.to_compact_json();
+        assert_eq!(
+            json,
+            r#"{"version":1,"sources":[{"id":"COSMIC"}],"bindings":[{"modifiers":["super","shift"],"key":"t","description":"Open a terminal","category":"custom","sources":["COSMIC"],"command":"cosmic-term","alternates":[{"modifiers":["ctrl","alt"],"key":"t"}]}]}"#
+        );
+        assert_eq!(Document::from_json(&json).unwrap(), document);
+        assert_eq!(document.bindings[0].keys_label(), "Super + Shift + t");
+        assert_eq!(
+            document.bindings[0].alternates[0].keys_label(),
+            "Ctrl + Alt + t"
+        );
+    }
+
+    #[test]
+    fn from_json_rejects_newer_versions() {
+        assert!(Document::from_json(r#"{"version":2}"#).is_err());
+        assert!(Document::from_json(r#"{"version":1}"#).is_ok());
+    }
+}
@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: MIT
+
+//! Default COSMIC bindings that changed between releases, read from the
+//! embedded `resources/binding-history.toml`, to hint at what a shortcut used
+//! to be.
+
+use crate::shortcuts::{COSMIC_SOURCE, KeyBinding, ShortcutCategory};
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+const DATA: &str = include_str!("../resources/binding-history.toml");
+
+static CHANGES: LazyLock<Vec<Change>> = LazyLock::new(|| match parse(DATA) {
+    Ok(changes) => changes,
+    Err(e) => {
+        log::error!("Failed to read the binding history: {}", e);
+        Vec::new()
+    }
+});
+
+/// A default binding that changed.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Change {
+    /// The action, as in [`KeyBinding::command`] of COSMIC shortcuts.
+    pub action: String,
+    /// The previous default, e.g. "Super + w".
+    pub was: String,
+    /// The last release with the previous default.
+    pub until: String,
+}
+
+#[derive(Deserialize)]
+struct History {
+    #[serde(default, rename = "change")]
+    changes: Vec<Change>,
+}
+
+fn parse(text: &str) -> Result<Vec<Change>, toml::de::Error> {
+    toml::from_str::<History>(text).map(|history| history.changes)
+}
+
+/// The change of the default of `shortcut`, if it is a COSMIC shortcut that is
+/// no longer bound to its previous default.
+pub fn hint(shortcut: &KeyBinding) -> Option<&'static Change> {
+    find(&CHANGES, shortcut)
+}
+
+fn find<'a>(changes: &'a [Change], shortcut: &KeyBinding) -> Option<&'a Change> {
+    if shortcut.category == ShortcutCategory::Custom
+        || !shortcut
+            .sources
+            .iter()
+            .any(|source| source == COSMIC_SOURCE)
+    {
+        return None;
+    }
+    let label = shortcut.display_label();
+    changes.iter().find(|change| {
+        change.action == shortcut.command
+            && !label.split(" / ").any(|keys| same_keys(keys, &change.was))
+    })
+}
+
+/// Compares key combinations ignoring case and spacing, e.g. "Super+W" and
+/// "Super + w".
+fn same_keys(a: &str, b: &str) -> bool {
+    let normalize = |keys: &str| -> String {
+        keys.chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    normalize(a) == normalize(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shortcuts::Modifiers;
+    use xkbcommon::xkb;
+
+    #[test]
+    fn hint_skips_shortcuts_still_on_the_previous_default() {
+        assert!(parse(DATA).is_ok());
+        let changes = parse(
+            r#"
+            [[change]]
+            action = "WorkspaceOverview"
+            was = "Super+W"
+            until = "1.0.0-alpha.7"
+            "#,
+        )
+        .unwrap();
+
+        let modifiers = Modifiers {
+            logo: true,
+            ..Modifiers::new()
+        };
+        let mut shortcut = KeyBinding {
+            command: String::from("WorkspaceOverview"),
+            category: ShortcutCategory::SystemActions,
+            sources: vec![String::from(COSMIC_SOURCE)],
+            ..KeyBinding::for_test(modifiers, Some(0x77), "")
+        };
+        assert_eq!(find(&changes, &shortcut), None);
+
+        shortcut.key = Some(xkb::Keysym::new(0x73));
+        assert_eq!(find(&changes, &shortcut), changes.first());
+    }
+}
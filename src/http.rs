@@ -0,0 +1,161 @@
+// SPDX-License-Identifier: MIT
+
+//! Opt-in HTTP server serving a read-only HTML cheat sheet, so the shortcuts can
+//! be kept open on another device. The same data is available in the exchange
+//! format at `/shortcuts.json`.
+//!
+//! Every request must carry the configured token, either as a `token` query
+//! parameter or as an `Authorization: Bearer` header.
+
+use cosmic::iced::Subscription;
+use futures_util::SinkExt;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Content served to clients, replaced whenever the shortcuts change.
+#[derive(Debug, Default)]
+pub struct Page {
+    pub html: String,
+    pub json: String,
+}
+
+pub type SharedPage = Arc<RwLock<Page>>;
+
+/// Largest request head accepted; anything bigger is rejected.
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+/// Generates a random 128-bit token from the system's random source, hex encoded.
+pub fn generate_token() -> Result<String, getrandom::Error> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes)?;
+    Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+/// Compares a token from a request with the configured one in time that
+/// doesn't depend on where they differ.
+fn token_matches(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Serves `page` on `addr` for as long as the subscription is active, reporting
+/// errors that stop the server.
+pub fn subscription(addr: SocketAddr, token: String, page: SharedPage) -> Subscription<String> {
+    Subscription::run_with_id(
+        ("keypeek-http", addr, token.clone()),
+        cosmic::iced::stream::channel(1, move |mut channel| async move {
+            let listener = match TcpListener::bind(addr).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    let _ = channel
+                        .send(format!("failed to listen on {}: {}", addr, e))
+                        .await;
+                    return futures_util::future::pending().await;
+                }
+            };
+            log::info!("serving shortcuts on http://{}/", addr);
+
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let token = token.clone();
+                        let page = page.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = handle(stream, &token, &page).await {
+                                log::debug!("http request failed: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => log::warn!("failed to accept http connection: {}", e),
+                }
+            }
+        }),
+    )
+}
+
+async fn handle(mut stream: TcpStream, token: &str, page: &SharedPage) -> std::io::Result<()> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 || request.len() + n > MAX_REQUEST_SIZE {
+            return respond(&mut stream, "400 Bad Request", "text/plain", "bad request").await;
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut lines = request.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default();
+    let target = request_line.next().unwrap_or_default();
+
+    if method != "GET" {
+        return respond(
+            &mut stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            "read-only",
+        )
+        .await;
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query_token = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+        .map(crate::link::decode);
+    let header_token = lines.find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if !name.trim().eq_ignore_ascii_case("authorization") {
+            return None;
+        }
+        value.trim().strip_prefix("Bearer ").map(str::trim)
+    });
+
+    let authorized = query_token.is_some_and(|given| token_matches(&given, token))
+        || header_token.is_some_and(|given| token_matches(given, token));
+    if !authorized {
+        return respond(
+            &mut stream,
+            "401 Unauthorized",
+            "text/plain",
+            "missing or wrong token",
+        )
+        .await;
+    }
+
+    let (content_type, body) = {
+        let Ok(page) = page.read() else {
+            return respond(&mut stream, "500 Internal Server Error", "text/plain", "").await;
+        };
+        match path {
+            "/" => ("text/html; charset=utf-8", page.html.clone()),
+            "/shortcuts.json" => ("application/json", page.json.clone()),
+            _ => return respond(&mut stream, "404 Not Found", "text/plain", "not found").await,
+        }
+    };
+    respond(&mut stream, "200 OK", content_type, &body).await
+}
+
+async fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await
+}
@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: MIT
+
+//! Icons shown next to shortcut rows.
+//!
+//! Spawn commands get the icon of the application they launch, from its desktop
+//! entry; system actions get a symbolic icon.
+
+use cosmic::widget::icon;
+use cosmic_settings_config::shortcuts::Action;
+use cosmic_settings_config::shortcuts::action::System as SystemAction;
+#[cfg(feature = "export")]
+use resvg::{tiny_skia, usvg};
+
+/// Icon of accessibility actions without one of their own.
+const ACCESSIBILITY_ICON: &str = "preferences-desktop-accessibility-symbolic";
+
+/// Icon for the row of `action`: an icon name, or an absolute path for desktop
+/// entries that point to an image file.
+pub fn action_icon(action: &Action) -> Option<String> {
+    match action {
+        Action::Spawn(command) => spawn_icon(command),
+        Action::System(system) => system_icon(system).map(str::to_string),
+        Action::ZoomIn => Some(String::from("zoom-in-symbolic")),
+        Action::ZoomOut => Some(String::from("zoom-out-symbolic")),
+        _ if crate::shortcuts::is_accessibility_action(action) => {
+            Some(String::from(ACCESSIBILITY_ICON))
+        }
+        _ => None,
+    }
+}
+
+fn system_icon(action: &SystemAction) -> Option<&'static str> {
+    let name = match action {
+        SystemAction::AppLibrary | SystemAction::Launcher => "view-app-grid-symbolic",
+        SystemAction::BrightnessDown | SystemAction::BrightnessUp => "display-brightness-symbolic",
+        SystemAction::KeyboardBrightnessDown | SystemAction::KeyboardBrightnessUp => {
+            "keyboard-brightness-symbolic"
+        }
+        SystemAction::HomeFolder => "user-home-symbolic",
+        SystemAction::InputSourceSwitch => "input-keyboard-symbolic",
+        SystemAction::LockScreen => "system-lock-screen-symbolic",
+        SystemAction::LogOut => "system-log-out-symbolic",
+        SystemAction::Mute => "audio-volume-muted-symbolic",
+        SystemAction::MuteMic => "microphone-disabled-symbolic",
+        SystemAction::PlayPause => "media-playback-start-symbolic",
+        SystemAction::PlayNext => "media-skip-forward-symbolic",
+        SystemAction::PlayPrev => "media-skip-backward-symbolic",
+        SystemAction::PowerOff => "system-shutdown-symbolic",
+        SystemAction::Screenshot => "camera-photo-symbolic",
+        SystemAction::Suspend => "system-suspend-symbolic",
+        SystemAction::ScreenReader => ACCESSIBILITY_ICON,
+        SystemAction::Terminal => "utilities-terminal-symbolic",
+        SystemAction::TouchpadToggle => "input-touchpad-symbolic",
+        SystemAction::VolumeLower => "audio-volume-low-symbolic",
+        SystemAction::VolumeRaise => "audio-volume-high-symbolic",
+        SystemAction::WebBrowser => "web-browser-symbolic",
+        SystemAction::WindowSwitcher | SystemAction::WindowSwitcherPrevious => {
+            "focus-windows-symbolic"
+        }
+        SystemAction::WorkspaceOverview => "view-grid-symbolic",
+        SystemAction::DisplayToggle => "video-display-symbolic",
+    };
+    Some(name)
+}
+
+fn spawn_icon(command: &str) -> Option<String> {
+    crate::desktop::lookup(command)?.icon
+}
+
+/// Renders an SVG once to a `size`×`size` bitmap, for the low-overhead
+/// rendering path that avoids decoding SVGs while drawing.
+#[cfg(feature = "export")]
+pub fn rasterize_svg(data: &[u8], size: u32) -> Option<icon::Handle> {
+    let tree = usvg::Tree::from_data(data, &usvg::Options::default()).ok()?;
+    let mut pixmap = tiny_skia::Pixmap::new(size, size)?;
+    let scale = size as f32 / tree.size().width().max(tree.size().height());
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+
+    // The pixmap is premultiplied; images expect straight alpha.
+    let pixels: Vec<u8> = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    Some(icon::from_raster_pixels(size, size, pixels))
+}
+
+/// Without resvg, SVG icons are always drawn as vectors.
+#[cfg(not(feature = "export"))]
+pub fn rasterize_svg(_data: &[u8], _size: u32) -> Option<icon::Handle> {
+    None
+}
@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: MIT
+
+//! Physical layout of a US ANSI keyboard, used to draw the keyboard heatmap.
+
+use xkbcommon::xkb;
+
+/// A key on the drawn keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutKey {
+    /// Keysym the key produces without modifiers.
+    pub keysym: u32,
+    /// Short label printed on the key.
+    pub label: &'static str,
+    /// Width in units of a regular key.
+    pub width: u16,
+}
+
+impl LayoutKey {
+    pub fn keysym(&self) -> xkb::Keysym {
+        xkb::Keysym::new(self.keysym)
+    }
+}
+
+const fn key(keysym: u32, label: &'static str) -> LayoutKey {
+    LayoutKey {
+        keysym,
+        label,
+        width: 1,
+    }
+}
+
+const fn wide(keysym: u32, label: &'static str, width: u16) -> LayoutKey {
+    LayoutKey {
+        keysym,
+        label,
+        width,
+    }
+}
+
+/// Rows of the keyboard from top to bottom.
+pub const ROWS: &[&[LayoutKey]] = &[
+    &[
+        key(0xff1b, "Esc"),
+        key(0xffbe, "F1"),
+        key(0xffbf, "F2"),
+        key(0xffc0, "F3"),
+        key(0xffc1, "F4"),
+        key(0xffc2, "F5"),
+        key(0xffc3, "F6"),
+        key(0xffc4, "F7"),
+        key(0xffc5, "F8"),
+        key(0xffc6, "F9"),
+        key(0xffc7, "F10"),
+        key(0xffc8, "F11"),
+        key(0xffc9, "F12"),
+    ],
+    &[
+        key(0x60, "`"),
+        key(0x31, "1"),
+        key(0x32, "2"),
+        key(0x33, "3"),
+        key(0x34, "4"),
+        key(0x35, "5"),
+        key(0x36, "6"),
+        key(0x37, "7"),
+        key(0x38, "8"),
+        key(0x39, "9"),
+        key(0x30, "0"),
+        key(0x2d, "-"),
+        key(0x3d, "="),
+        wide(0xff08, "⌫", 2),
+    ],
+    &[
+        wide(0xff09, "Tab", 2),
+        key(0x71, "Q"),
+        key(0x77, "W"),
+        key(0x65, "E"),
+        key(0x72, "R"),
+        key(0x74, "T"),
+        key(0x79, "Y"),
+        key(0x75, "U"),
+        key(0x69, "I"),
+        key(0x6f, "O"),
+        key(0x70, "P"),
+        key(0x5b, "["),
+        key(0x5d, "]"),
+        key(0x5c, "\\"),
+    ],
+    &[
+        key(0x61, "A"),
+        key(0x73, "S"),
+        key(0x64, "D"),
+        key(0x66, "F"),
+        key(0x67, "G"),
+        key(0x68, "H"),
+        key(0x6a, "J"),
+        key(0x6b, "K"),
+        key(0x6c, "L"),
+        key(0x3b, ";"),
+        key(0x27, "'"),
+        wide(0xff0d, "Enter", 2),
+    ],
+    &[
+        key(0x7a, "Z"),
+        key(0x78, "X"),
+        key(0x63, "C"),
+        key(0x76, "V"),
+        key(0x62, "B"),
+        key(0x6e, "N"),
+        key(0x6d, "M"),
+        key(0x2c, ","),
+        key(0x2e, "."),
+        key(0x2f, "/"),
+    ],
+    &[
+        wide(0x20, "Space", 6),
+        key(0xff51, "←"),
+        key(0xff52, "↑"),
+        key(0xff54, "↓"),
+        key(0xff53, "→"),
+    ],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_uses_lowercase_keysyms() {
+        for key in ROWS.iter().flat_map(|row| row.iter()) {
+            assert_eq!(
+                xkb::keysym_to_lower(key.keysym()),
+                key.keysym(),
+                "{}",
+                key.label
+            );
+        }
+    }
+}
@@ -0,0 +1,86 @@
+// SPDX-License-Identifier: MIT
+
+//! How KeyPeek is started: the desktop entries of the panel applet and of the
+//! standalone window, and detecting which of the two launched the process.
+//!
+//! The entries in `resources/` are generated from [`Mode::desktop_entry`], so
+//! packagers can install them as they are for either mode.
+
+use clap::ValueEnum;
+
+/// App id shared by both modes, so docks match the window to its launcher.
+pub const APP_ID: &str = "io.github.l-const.keypeek";
+
+/// Environment variables the COSMIC panel sets for the applets it starts.
+const PANEL_VARS: [&str; 2] = ["COSMIC_PANEL_NAME", "COSMIC_PANEL_ANCHOR"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Mode {
+    /// In the panel, dock or applet menu.
+    Applet,
+    /// As a regular window, e.g. from the app library.
+    Window,
+}
+
+impl Mode {
+    /// The window is used when asked for or when no panel started the process.
+    pub fn detect(window: bool) -> Self {
+        let in_panel = PANEL_VARS.iter().any(|var| std::env::var_os(var).is_some());
+        if window || !in_panel {
+            Self::Window
+        } else {
+            Self::Applet
+        }
+    }
+
+    /// Contents of the desktop entry. The applet entry is hidden from the app
+    /// library and marked for the panel; the window entry is a regular launcher.
+    pub fn desktop_entry(self) -> String {
+        let exec = match self {
+            Self::Applet => String::from(
+                "Exec=keypeek-applet %u\n\
+                 Terminal=false\n\
+                 StartupNotify=true\n\
+                 Categories=COSMIC\n\
+                 Keywords=COSMIC\n\
+                 NoDisplay=true\n\
+                 X-CosmicApplet=true\n\
+                 X-CosmicHoverPopup=Auto\n\
+                 MimeType=x-scheme-handler/keypeek;\n",
+            ),
+            Self::Window => format!(
+                "Exec=keypeek-applet --window\n\
+                 Terminal=false\n\
+                 StartupNotify=true\n\
+                 StartupWMClass={APP_ID}\n\
+                 Categories=COSMIC;Utility;\n\
+                 Keywords=keyboard;shortcuts;keybindings;\n"
+            ),
+        };
+        format!(
+            "[Desktop Entry]\n\
+             Name=KeyPeek\n\
+             Comment=Quick view and search for keyboard shortcuts\n\
+             Type=Application\n\
+             Icon={APP_ID}\n\
+             {exec}"
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resources_match_generated_desktop_entries() {
+        assert_eq!(
+            include_str!("../resources/app.desktop"),
+            Mode::Applet.desktop_entry()
+        );
+        assert_eq!(
+            include_str!("../resources/window.desktop"),
+            Mode::Window.desktop_entry()
+        );
+    }
+}
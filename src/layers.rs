@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: MIT
+
+//! The layers of the COSMIC shortcut configuration: system defaults under the
+//! data directories and the user's changes under the config directory. The raw
+//! configuration page shows their files as they are on disk.
+
+use std::path::{Path, PathBuf};
+
+/// Config directory of the COSMIC shortcuts, under a config or data directory.
+pub const SHORTCUTS_CONFIG: &str = "cosmic/com.system76.CosmicSettings.Shortcuts";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Layer {
+    System,
+    User,
+}
+
+/// A configuration file and its contents, in RON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawFile {
+    pub layer: Layer,
+    pub path: PathBuf,
+    pub text: String,
+}
+
+/// Data directories searched for system defaults, including the host's under
+/// Flatpak.
+pub fn system_dirs() -> Vec<PathBuf> {
+    let system = std::env::var("XDG_DATA_DIRS")
+        .unwrap_or_else(|_| String::from("/usr/local/share:/usr/share"));
+    let mut dirs: Vec<PathBuf> = system
+        .split(':')
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .collect();
+    dirs.push(PathBuf::from("/run/host/usr/share"));
+    dirs
+}
+
+/// Version of the shortcut config, as opened by `cosmic-settings-config`.
+const SHORTCUTS_VERSION: &str = "v1";
+
+/// Whether either layer exists. Under Flatpak without access to the host's
+/// configuration, neither does.
+pub fn available() -> bool {
+    let user = dirs::config_dir().map(|config| config.join(SHORTCUTS_CONFIG));
+    user.into_iter()
+        .chain(
+            system_dirs()
+                .into_iter()
+                .map(|dir| dir.join(SHORTCUTS_CONFIG)),
+        )
+        .any(|dir| dir.exists())
+}
+
+/// The file `key` is read from: the user's if they changed it, otherwise the
+/// first system default, or where the user's would be if there is neither.
+pub fn key_path(key: &str) -> PathBuf {
+    let user = dirs::config_dir()
+        .unwrap_or_default()
+        .join(SHORTCUTS_CONFIG)
+        .join(SHORTCUTS_VERSION)
+        .join(key);
+    if user.exists() {
+        return user;
+    }
+    system_dirs()
+        .into_iter()
+        .map(|dir| dir.join(SHORTCUTS_CONFIG).join(SHORTCUTS_VERSION).join(key))
+        .find(|path| path.exists())
+        .unwrap_or(user)
+}
+
+/// Reads the files of both layers, system first, in the order they are merged.
+pub fn read() -> Vec<RawFile> {
+    let mut files = Vec::new();
+    for dir in system_dirs() {
+        read_tree(&dir.join(SHORTCUTS_CONFIG), Layer::System, &mut files);
+    }
+    if let Some(config) = dirs::config_dir() {
+        read_tree(&config.join(SHORTCUTS_CONFIG), Layer::User, &mut files);
+    }
+    files
+}
+
+fn read_tree(dir: &Path, layer: Layer, files: &mut Vec<RawFile>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            read_tree(&path, layer, files);
+            continue;
+        }
+        match std::fs::read_to_string(&path) {
+            Ok(text) => files.push(RawFile { layer, path, text }),
+            Err(e) => log::debug!("skipping {}: {}", path.display(), e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_tree_lists_files_in_order() {
+        let dir = std::env::temp_dir().join(format!("keypeek-layers-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("v1")).unwrap();
+        std::fs::write(dir.join("v1/defaults"), "{}").unwrap();
+        std::fs::write(dir.join("v1/custom"), "{}").unwrap();
+
+        let mut files = Vec::new();
+        read_tree(&dir, Layer::User, &mut files);
+        let names: Vec<_> = files.iter().map(|f| f.path.file_name().unwrap()).collect();
+        assert_eq!(names, ["custom", "defaults"]);
+        assert!(
+            files
+                .iter()
+                .all(|f| f.layer == Layer::User && f.text == "{}")
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,156 @@
+// SPDX-License-Identifier: MIT
+
+//! `keypeek://` links, such as `keypeek://search?q=screenshot`, that open the
+//! popup from documentation or notifications.
+//!
+//! The desktop entry registers the scheme, so opening a link starts
+//! `keypeek-applet <link>`, which hands it to the applet running in the panel
+//! over a socket in the user's runtime directory.
+
+use crate::shortcuts::ShortcutCategory;
+use anyhow::{Context, Result, bail};
+use futures_util::Stream;
+use std::io::Write;
+use std::path::PathBuf;
+use tokio::io::AsyncBufReadExt;
+
+/// What a link asks the applet to show.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Link {
+    /// `keypeek://` or `keypeek://open`: the popup as it was.
+    Open,
+    /// `keypeek://search?q=...&category=...`: the list filtered by a query and,
+    /// optionally, categories given by id.
+    Search {
+        query: String,
+        categories: Vec<ShortcutCategory>,
+    },
+}
+
+impl Link {
+    pub fn parse(uri: &str) -> Result<Self> {
+        let rest = uri
+            .strip_prefix("keypeek://")
+            .or_else(|| uri.strip_prefix("keypeek:"))
+            .context("not a keypeek:// link")?;
+        let (action, params) = rest.split_once('?').unwrap_or((rest, ""));
+
+        match action.trim_end_matches('/') {
+            "" | "open" => Ok(Self::Open),
+            "search" => {
+                let mut query = String::new();
+                let mut categories = Vec::new();
+                for param in params.split('&').filter(|p| !p.is_empty()) {
+                    let (key, value) = param.split_once('=').unwrap_or((param, ""));
+                    let value = decode(value);
+                    match key {
+                        "q" => query = value,
+                        "category" => categories.extend(ShortcutCategory::from_id(&value)),
+                        _ => log::debug!("ignoring link parameter {key}"),
+                    }
+                }
+                Ok(Self::Search { query, categories })
+            }
+            other => bail!("unknown link action {other:?}"),
+        }
+    }
+}
+
+/// Decodes `%XX` escapes and `+` as a space; invalid escapes are kept as is.
+pub(crate) fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => {
+                let hex = text
+                    .get(i + 1..i + 3)
+                    .and_then(|h| u8::from_str_radix(h, 16).ok());
+                if let Some(byte) = hex {
+                    out.push(byte);
+                    i += 3;
+                    continue;
+                }
+                out.push(b'%');
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Path of the socket the applet listens on, in the user's runtime directory.
+/// Without one, links aren't handed over at all rather than through a shared
+/// directory.
+fn socket_path() -> std::io::Result<PathBuf> {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(|dir| PathBuf::from(dir).join("keypeek-applet.sock"))
+        .ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "XDG_RUNTIME_DIR is not set")
+        })
+}
+
+/// Hands a link to the running applet.
+pub fn send(uri: &str) -> Result<()> {
+    Link::parse(uri)?;
+    let path = socket_path()?;
+    let mut stream = std::os::unix::net::UnixStream::connect(&path)
+        .with_context(|| format!("the applet is not running ({})", path.display()))?;
+    writeln!(stream, "{uri}").context("failed to send the link")
+}
+
+/// Listens for links handed over by [`send`]. The stream ends if the socket
+/// stops accepting connections. With the applet on several panels, the first
+/// instance gets the links; the others fail here until it exits.
+pub fn listen() -> std::io::Result<impl Stream<Item = Link>> {
+    let path = socket_path()?;
+    if std::os::unix::net::UnixStream::connect(&path).is_ok() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AddrInUse,
+            "another instance is handling links",
+        ));
+    }
+    // Nothing answers, so the socket, if any, was left behind by an applet
+    // that crashed and blocks binding.
+    let _ = std::fs::remove_file(&path);
+    let listener = tokio::net::UnixListener::bind(&path)?;
+
+    Ok(futures_util::stream::unfold(
+        listener,
+        |listener| async move {
+            loop {
+                let (stream, _) = listener.accept().await.ok()?;
+                let mut lines = tokio::io::BufReader::new(stream).lines();
+                if let Ok(Some(line)) = lines.next_line().await {
+                    match Link::parse(line.trim()) {
+                        Ok(link) => return Some((link, listener)),
+                        Err(e) => log::warn!("ignoring link {line:?}: {e:#}"),
+                    }
+                }
+            }
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_search_decodes_query_and_categories() {
+        assert_eq!(
+            Link::parse("keypeek://search?q=take%20a+screenshot&category=system&x=1").unwrap(),
+            Link::Search {
+                query: String::from("take a screenshot"),
+                categories: vec![ShortcutCategory::SystemActions],
+            }
+        );
+        assert_eq!(Link::parse("keypeek://").unwrap(), Link::Open);
+        assert!(Link::parse("keypeek://delete").is_err());
+        assert!(Link::parse("https://example.com").is_err());
+        assert_eq!(decode("100%"), "100%");
+    }
+}
@@ -1,19 +1,130 @@
 // SPDX-License-Identifier: MIT
 
 mod app;
+mod cache;
+mod cli;
+mod command;
+#[cfg(feature = "sheets")]
+mod community;
+mod compare;
 mod config;
+mod conflicts;
+#[cfg(feature = "overlay")]
+mod daemon;
+mod desktop;
+mod details;
+mod error;
+mod events;
+mod export;
+mod format;
+mod history;
+mod http;
 mod i18n;
+mod icons;
+mod keyboard;
+mod launcher;
+mod layers;
+mod link;
+#[cfg(feature = "sheets")]
+mod net;
+mod notifications;
+#[cfg(feature = "export")]
+mod print;
+mod redact;
+mod search;
+#[cfg(feature = "dbus")]
+mod session;
+#[cfg(feature = "sheets")]
+mod sheets;
 mod shortcuts;
+mod state;
+mod stats;
+mod style;
+mod timings;
+mod usage;
 mod utils;
 
 fn main() -> cosmic::iced::Result {
+    timings::start();
     env_logger::init();
+
+    let cli = <cli::Cli as clap::Parser>::parse();
+    if let Some(shell) = cli.generate_completions {
+        cli::print_completions(shell);
+        return Ok(());
+    }
+    if let Some(mode) = cli.generate_desktop_entry {
+        print!("{}", mode.desktop_entry());
+        return Ok(());
+    }
+    if cli.generate_man {
+        if let Err(e) = cli::print_man_page() {
+            eprintln!("failed to write the man page: {e}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let app_id = <app::AppModel as cosmic::Application>::APP_ID;
+
+    // Opened as the handler of a keypeek:// link: pass it to the running applet.
+    if let Some(uri) = &cli.link {
+        if let Err(e) = link::send(uri) {
+            eprintln!("{e:#}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Validate custom sheets without starting the applet, e.g. in dotfile CI.
+    #[cfg(feature = "sheets")]
+    if cli.check_sheets {
+        let (_, config) = config::Config::load(app_id);
+        std::process::exit(sheets::check_sheets_cli(config.sheets_dir()));
+    }
+    #[cfg(feature = "timings")]
+    if cli.timings {
+        let (_, config) = config::Config::load(app_id);
+        std::process::exit(app::timings_cli(&config));
+    }
+    if cli.check_conflicts {
+        let (_, config) = config::Config::load(app_id);
+        std::process::exit(app::check_conflicts_cli(&config, cli.json));
+    }
+
     // Get the system's preferred languages.
     let requested_languages = i18n_embed::DesktopLanguageRequester::requested_languages();
 
     // Enable localizations to be applied.
     i18n::init(&requested_languages);
 
-    // Starts the applet's event loop with `()` as the application's flags.
-    cosmic::applet::run::<app::AppModel>(())
+    // Render the cheat sheet without a compositor, e.g. for documentation pipelines.
+    let render: Option<(fn(&str, &[export::ExportSection]) -> String, _)> = cli
+        .render_html
+        .map(|path| (export::render_html as _, path))
+        .or(cli
+            .render_md
+            .map(|path| (export::render_markdown as _, path)));
+    if let Some((render, path)) = render {
+        let (_, config) = config::Config::load(app_id);
+        std::process::exit(app::render_cli(&config, render, &path));
+    }
+    if let Some(path) = cli.export_json {
+        let (_, config) = config::Config::load(app_id);
+        std::process::exit(app::export_json_cli(&config, &path));
+    }
+
+    // Open in a regular window when launched from the app library rather than
+    // by the panel.
+    if launcher::Mode::detect(cli.window) == launcher::Mode::Window {
+        let settings = cosmic::app::Settings::default().size(cosmic::iced::Size::new(480.0, 640.0));
+        let flags = app::Flags {
+            standalone: true,
+            ..Default::default()
+        };
+        return cosmic::app::run::<app::AppModel>(settings, flags);
+    }
+
+    // Starts the applet's event loop.
+    cosmic::applet::run::<app::AppModel>(app::Flags::default())
 }
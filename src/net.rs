@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: MIT
+
+//! Everything KeyPeek fetches from the network: community sheets and release
+//! checks. Both are opt-in, and only HTTPS addresses are fetched.
+
+use crate::launcher::APP_ID;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// Latest release of the repository, from the GitHub API.
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/l-const/keypeek-applet/releases/latest";
+
+/// A release newer than the running version.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Release {
+    #[serde(rename = "tag_name")]
+    pub version: String,
+    /// Release notes, in Markdown.
+    #[serde(default)]
+    pub body: String,
+    /// Release page on GitHub.
+    pub html_url: String,
+}
+
+impl Release {
+    /// Where to get the update: the software center for Flatpak installs,
+    /// otherwise the release page.
+    pub fn download_url(&self) -> String {
+        if crate::utils::is_flatpak() {
+            format!("appstream://{APP_ID}")
+        } else {
+            self.html_url.clone()
+        }
+    }
+}
+
+/// Fetches `url`, which must use HTTPS.
+pub async fn get(url: &str) -> Result<Vec<u8>> {
+    if !url.starts_with("https://") {
+        anyhow::bail!("{url} does not use HTTPS");
+    }
+
+    // GitHub rejects requests without a user agent.
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("keypeek-applet/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .context("failed to set up HTTP client")?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("failed to fetch {url}"))?
+        .error_for_status()
+        .with_context(|| format!("failed to fetch {url}"))?;
+    let body = response
+        .bytes()
+        .await
+        .with_context(|| format!("failed to read {url}"))?;
+
+    Ok(body.to_vec())
+}
+
+/// Checks GitHub for a release newer than this build.
+pub async fn check_for_update() -> Result<Option<Release>> {
+    let body = get(LATEST_RELEASE_URL).await?;
+    let release: Release = serde_json::from_slice(&body).context("unexpected release data")?;
+    let newer = is_newer(&release.version, env!("CARGO_PKG_VERSION"));
+    Ok(newer.then_some(release))
+}
+
+/// Compares `major.minor.patch` versions, ignoring a leading `v` and
+/// pre-release suffixes. Unparsable versions are never newer.
+fn is_newer(version: &str, current: &str) -> bool {
+    fn parse(version: &str) -> Option<Vec<u64>> {
+        let version = version.trim().trim_start_matches('v');
+        let version = version.split(['-', '+']).next()?;
+        version.split('.').map(|part| part.parse().ok()).collect()
+    }
+
+    match (parse(version), parse(current)) {
+        (Some(version), Some(current)) => version > current,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newer_versions_compare_numerically() {
+        assert!(is_newer("v0.10.0", "0.2.1"));
+        assert!(is_newer("0.2.2-beta", "0.2.1"));
+        assert!(!is_newer("v0.2.1", "0.2.1"));
+        assert!(!is_newer("0.1.9", "0.2.1"));
+        assert!(!is_newer("nightly", "0.2.1"));
+    }
+}
@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: MIT
+
+//! Desktop notifications confirming actions, through the
+//! `org.freedesktop.Notifications` service of the session.
+
+use anyhow::Result;
+#[cfg(feature = "dbus")]
+use std::collections::HashMap;
+
+/// How long a notification stays before the server closes it.
+#[cfg(feature = "dbus")]
+const EXPIRE_TIMEOUT_MS: i32 = 3000;
+
+/// Shows a short notification from KeyPeek.
+#[cfg(feature = "dbus")]
+pub async fn notify(summary: String, body: String) -> Result<()> {
+    let connection = zbus::Connection::session().await?;
+    let hints: HashMap<&str, zbus::zvariant::Value<'_>> = HashMap::new();
+    connection
+        .call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            &(
+                "KeyPeek",
+                0u32,
+                crate::launcher::APP_ID,
+                summary.as_str(),
+                body.as_str(),
+                Vec::<&str>::new(),
+                hints,
+                EXPIRE_TIMEOUT_MS,
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(not(feature = "dbus"))]
+pub async fn notify(_summary: String, _body: String) -> Result<()> {
+    Err(crate::utils::no_dbus())
+}
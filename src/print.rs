@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MIT
+
+//! Printing through the XDG desktop print portal.
+
+#[cfg(feature = "dbus")]
+use crate::export;
+use crate::export::ExportSection;
+#[cfg(feature = "dbus")]
+use anyhow::Context;
+use anyhow::Result;
+#[cfg(feature = "dbus")]
+use ashpd::desktop::print::{PageSetup, PrintProxy, Settings};
+#[cfg(feature = "dbus")]
+use std::io::{Seek, Write};
+#[cfg(feature = "dbus")]
+use std::os::fd::AsFd;
+#[cfg(feature = "dbus")]
+use std::os::unix::fs::OpenOptionsExt;
+
+/// Asks the user for print settings and sends the sections to the printer as a PDF.
+#[cfg(feature = "dbus")]
+pub async fn print(title: String, sections: Vec<ExportSection>) -> Result<()> {
+    let pdf = export::render_pdf(&title, &sections);
+    let file = write_private(&pdf).context("failed to write the document to print")?;
+
+    let proxy = PrintProxy::new()
+        .await
+        .context("print portal is not available")?;
+    let prepared = proxy
+        .prepare_print(
+            None,
+            &title,
+            Settings::default(),
+            PageSetup::default(),
+            None,
+            true,
+        )
+        .await?
+        .response()
+        .context("printing was cancelled")?;
+
+    proxy
+        .print(None, &title, &file.as_fd(), Some(prepared.token), true)
+        .await
+        .context("failed to send document to the printer")?;
+
+    Ok(())
+}
+
+/// Writes `pdf` to a new file only the user can read, in their runtime
+/// directory. The file is unlinked right away, so nothing is left behind
+/// however printing ends; the portal reads it through the descriptor.
+#[cfg(feature = "dbus")]
+fn write_private(pdf: &[u8]) -> Result<std::fs::File> {
+    let dir = dirs::runtime_dir().context("XDG_RUNTIME_DIR is not set")?;
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let path = dir.join(format!("keypeek-print-{}-{nanos}.pdf", std::process::id()));
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&path)
+        .with_context(|| format!("failed to create {}", path.display()))?;
+    let _ = std::fs::remove_file(&path);
+
+    file.write_all(pdf)?;
+    file.rewind()?;
+    Ok(file)
+}
+
+#[cfg(not(feature = "dbus"))]
+pub async fn print(_title: String, _sections: Vec<ExportSection>) -> Result<()> {
+    Err(crate::utils::no_dbus())
+}
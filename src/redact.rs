@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: MIT
+
+//! Masks secrets (tokens, passwords) in spawn commands before they are shown or copied.
+
+use regex::Regex;
+use std::borrow::Cow;
+
+/// Replacement text for redacted parts of a command.
+const MASK: &str = "••••••";
+
+/// Compiles the configured redaction patterns, skipping (and logging) invalid ones.
+pub fn compile_patterns(patterns: &[String]) -> Vec<Regex> {
+    patterns
+        .iter()
+        .filter_map(|pattern| match Regex::new(pattern) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                log::warn!("Ignoring invalid redaction pattern {:?}: {}", pattern, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Replaces every match of the given patterns in `command` with a mask.
+///
+/// If a pattern has capture groups, only the first group is masked, so a pattern like
+/// `--token[= ](\S+)` keeps the flag name visible.
+pub fn redact<'a>(command: &'a str, patterns: &[Regex]) -> Cow<'a, str> {
+    let mut result = Cow::Borrowed(command);
+
+    for pattern in patterns {
+        if !pattern.is_match(&result) {
+            continue;
+        }
+
+        let replaced = pattern
+            .replace_all(&result, |caps: &regex::Captures<'_>| match caps.get(1) {
+                Some(group) => {
+                    let whole = caps.get(0).unwrap();
+                    let start = group.start() - whole.start();
+                    let end = group.end() - whole.start();
+                    format!(
+                        "{}{}{}",
+                        &whole.as_str()[..start],
+                        MASK,
+                        &whole.as_str()[end..]
+                    )
+                }
+                None => MASK.to_string(),
+            })
+            .into_owned();
+        result = Cow::Owned(replaced);
+    }
+
+    result
+}
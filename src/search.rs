@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: MIT
+
+//! Matching of search queries against shortcut descriptions.
+//!
+//! A query matches when every whitespace-separated token matches the text, so
+//! "mv win next" finds "Move window to next workspace". A single token may also
+//! match the initials of the text's words, so "mwnw" finds the same entry.
+
+/// Score of a match where the whole query appears verbatim in the text.
+const SCORE_SUBSTRING: u32 = 3;
+/// Score of a match where every token matches the start (or an abbreviation) of a word.
+const SCORE_TOKENS: u32 = 2;
+/// Score of a match on the initials of the words.
+const SCORE_INITIALISM: u32 = 1;
+
+/// Scores how well `query` matches `text`; `None` means it doesn't match.
+///
+/// Higher scores are better matches. An empty query matches everything.
+pub fn score(query: &str, text: &str) -> Option<u32> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Some(SCORE_SUBSTRING);
+    }
+
+    let text = text.to_lowercase();
+    if text.contains(&query) {
+        return Some(SCORE_SUBSTRING);
+    }
+
+    let words = words(&text);
+    let tokens: Vec<&str> = query.split_whitespace().collect();
+
+    if tokens
+        .iter()
+        .all(|token| text.contains(token) || words.iter().any(|word| abbreviates(token, word)))
+    {
+        return Some(SCORE_TOKENS);
+    }
+
+    if let [token] = tokens.as_slice() {
+        let initials: String = words
+            .iter()
+            .filter_map(|word| word.chars().next())
+            .collect();
+        if abbreviates(token, &initials) {
+            return Some(SCORE_INITIALISM);
+        }
+    }
+
+    None
+}
+
+/// Splits lowercase text into alphanumeric words.
+fn words(text: &str) -> Vec<&str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// True if `token` starts like `word` and its letters appear in order within it,
+/// as in "mv" for "move" or "ws" for "workspace".
+fn abbreviates(token: &str, word: &str) -> bool {
+    token.chars().next() == word.chars().next() && is_subsequence(token, word)
+}
+
+/// True if all characters of `needle` appear in `haystack` in the same order.
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.by_ref().any(|h| h == c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn every_token_of_a_match_is_a_subsequence(query in "[a-zA-Z ]{0,12}", text in "[a-zA-Z ]{0,40}") {
+            if score(&query, &text).is_some() {
+                let text = text.to_lowercase();
+                for token in query.to_lowercase().split_whitespace() {
+                    prop_assert!(is_subsequence(token, &text), "{token:?} in {text:?}");
+                }
+            }
+        }
+
+        #[test]
+        fn scores_ignore_case_and_surrounding_space(query in "[a-zA-Z ]{0,12}", text in "[a-zA-Z ]{0,40}") {
+            let expected = score(query.trim(), &text.to_ascii_lowercase());
+            prop_assert_eq!(score(&query.to_ascii_lowercase(), &text), expected);
+            prop_assert_eq!(score(&query.to_ascii_uppercase(), &text), expected);
+            prop_assert_eq!(score(&format!(" {query}  "), &text.to_ascii_uppercase()), expected);
+        }
+
+        #[test]
+        fn verbatim_matches_score_highest(text in "[a-z ]{1,40}", start in 0usize..40, len in 1usize..12) {
+            let start = start.min(text.len() - 1);
+            let query = &text[start..(start + len).min(text.len())];
+            prop_assert_eq!(score(query, &text), Some(SCORE_SUBSTRING));
+        }
+    }
+}
@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: MIT
+
+//! Session resume notifications from logind, so shortcuts changed while the
+//! machine was suspended or from another TTY are picked up without polling.
+
+use futures_util::{Stream, StreamExt};
+use zbus::zvariant::OwnedObjectPath;
+
+const LOGIND: &str = "org.freedesktop.login1";
+
+/// Why the session is considered resumed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resume {
+    /// The machine woke up from suspend or hibernation.
+    Wake,
+    /// The session became active again, e.g. after switching back from
+    /// another TTY or user.
+    Activated,
+}
+
+/// Streams a [`Resume`] whenever the session resumes. Fails if logind can't be
+/// reached, e.g. inside a sandbox without system bus access, or doesn't know
+/// the session, e.g. when the applet runs outside of one.
+pub async fn resumes() -> zbus::Result<impl Stream<Item = Resume>> {
+    let connection = zbus::Connection::system().await?;
+    let manager = zbus::Proxy::new(
+        &connection,
+        LOGIND,
+        "/org/freedesktop/login1",
+        "org.freedesktop.login1.Manager",
+    )
+    .await?;
+    // "auto" is the caller's session or, outside of one, the user's display
+    // session. Signals are sent from the real path, so it is resolved first.
+    let session_path: OwnedObjectPath = manager.call("GetSession", &("auto",)).await?;
+    let session = zbus::Proxy::new(
+        &connection,
+        LOGIND,
+        session_path,
+        "org.freedesktop.login1.Session",
+    )
+    .await?;
+
+    // PrepareForSleep(false) is sent after waking up.
+    let wake = manager
+        .receive_signal("PrepareForSleep")
+        .await?
+        .filter_map(|message| async move {
+            let (sleeping,): (bool,) = message.body().deserialize().ok()?;
+            (!sleeping).then_some(Resume::Wake)
+        });
+    let activated = session
+        .receive_property_changed::<bool>("Active")
+        .await
+        .filter_map(|change| async move {
+            change
+                .get()
+                .await
+                .ok()
+                .filter(|active| *active)
+                .map(|_| Resume::Activated)
+        });
+
+    Ok(futures_util::stream::select(
+        Box::pin(wake),
+        Box::pin(activated),
+    ))
+}
+
+/// Whether [`resumes`] would fail again the same way: logind or the bus
+/// refused the request, or there is no system bus to connect to.
+pub fn is_permanent(error: &zbus::Error) -> bool {
+    match error {
+        zbus::Error::MethodError(..) | zbus::Error::FDO(_) | zbus::Error::Address(_) => true,
+        zbus::Error::InputOutput(e) => matches!(
+            e.kind(),
+            std::io::ErrorKind::NotFound | std::io::ErrorKind::PermissionDenied
+        ),
+        _ => false,
+    }
+}
@@ -0,0 +1,945 @@
+// SPDX-License-Identifier: MIT
+
+//! Custom cheat sheets: TOML files in `~/.config/keypeek/sheets/`, or the
+//! `sheets` subdirectory of the configured sync directory, listing shortcuts of
+//! other applications next to the COSMIC ones.
+//!
+//! ```toml
+//! name = "Firefox"
+//! category = "applications"
+//!
+//! [[shortcut]]
+//! keys = "Ctrl+T"
+//! description = "Open a new tab"
+//! ```
+//!
+//! Controller and other non-keyboard input is written as a `chord` instead of
+//! `keys`, e.g. `chord = "Guide + A"`, and shown as written.
+//!
+//! A sheet can build on another with `extends = "vim"`, naming a sheet in the
+//! same directory or a bundled one. Its shortcuts replace those of the base with
+//! the same combination, `remove = ["Ctrl+Q"]` leaves base shortcuts out, and
+//! the base is then only listed through it.
+
+use crate::shortcuts::{BindingId, KeyBinding, Modifiers, Pointer, ShortcutCategory};
+use anyhow::{Context, Result};
+#[cfg(feature = "dbus")]
+use ashpd::desktop::open_uri::OpenFileRequest;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+#[cfg(feature = "dbus")]
+use std::os::fd::AsFd;
+use std::path::{Path, PathBuf};
+use toml::Spanned;
+use xkbcommon::xkb;
+
+/// Commented example written by "New sheet…".
+const TEMPLATE: &str = include_str!("../resources/sheet-template.toml");
+
+/// A sheet file as written by the user.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SheetFile {
+    name: Option<String>,
+    category: Option<Spanned<String>>,
+    /// Hardware the sheet describes, e.g. a tablet; its rows are grouped under it.
+    device: Option<String>,
+    author: Option<String>,
+    version: Option<String>,
+    homepage: Option<Spanned<String>>,
+    /// Sheet this one builds on, by file name without `.toml`.
+    extends: Option<Spanned<String>>,
+    /// Combinations of the base sheet to leave out.
+    #[serde(default)]
+    remove: Vec<Spanned<String>>,
+    #[serde(default)]
+    shortcut: Vec<SheetShortcut>,
+}
+
+/// Name and optional metadata of a sheet, shown before it is imported or
+/// installed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SheetInfo {
+    pub name: String,
+    pub author: Option<String>,
+    pub version: Option<String>,
+    /// Where the sheet is maintained; always an HTTP or HTTPS URL.
+    pub homepage: Option<String>,
+    /// Base sheet the shortcuts were merged with.
+    pub extends: Option<String>,
+}
+
+/// Where `extends` finds base sheets: the sheets directory, then the sheets
+/// bundled with the `bundled-sheets` feature.
+#[derive(Debug, Clone, Copy, Default)]
+struct Bases<'a> {
+    dir: Option<&'a Path>,
+}
+
+impl Bases<'_> {
+    fn text(&self, name: &str) -> Option<String> {
+        self.dir
+            .and_then(|dir| std::fs::read_to_string(dir.join(format!("{name}.toml"))).ok())
+            .or_else(|| crate::community::bundled_text(name))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SheetShortcut {
+    keys: Option<Spanned<String>>,
+    /// Free-form combination of non-keyboard buttons, e.g. "Guide + A".
+    chord: Option<Spanned<String>>,
+    description: String,
+    category: Option<Spanned<String>>,
+}
+
+/// Line and column, both starting at 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    fn from_offset(text: &str, offset: usize) -> Self {
+        let before = &text[..offset.min(text.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+/// A sheet that could not be loaded, shown as a warning in the popup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetError {
+    pub path: PathBuf,
+    pub location: Option<Location>,
+    pub message: String,
+}
+
+impl SheetError {
+    /// File name of the sheet, for display.
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for SheetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Some(Location { line, column }) => {
+                write!(f, "line {line}, column {column}: {}", self.message)
+            }
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// A binding read from a sheet, with where its keys are written.
+struct SheetEntry {
+    binding: KeyBinding,
+    location: Location,
+}
+
+/// Writes the template to a new file in the sheets directory, without
+/// overwriting existing sheets.
+pub fn create_sheet(dir: Option<PathBuf>) -> Result<PathBuf> {
+    write_new_sheet(dir, "new-sheet", TEMPLATE)
+}
+
+/// Writes `text` to `<stem>.toml` in the sheets directory, adding a number to
+/// the name if a sheet of that name exists.
+pub fn write_new_sheet(dir: Option<PathBuf>, stem: &str, text: &str) -> Result<PathBuf> {
+    let dir = dir.context("no config directory")?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+
+    let path = (1..)
+        .map(|n| match n {
+            1 => dir.join(format!("{stem}.toml")),
+            n => dir.join(format!("{stem}-{n}.toml")),
+        })
+        .find(|path| !path.exists())
+        .expect("an unused file name exists");
+    std::fs::write(&path, text).with_context(|| format!("failed to write {}", path.display()))?;
+
+    Ok(path)
+}
+
+/// A sheet dropped onto KeyPeek, validated and waiting for confirmation.
+#[derive(Debug, Clone)]
+pub struct Import {
+    /// The dropped file.
+    pub path: PathBuf,
+    /// Name and metadata shown in the confirmation.
+    pub info: SheetInfo,
+    /// Number of shortcuts in the sheet.
+    pub count: usize,
+    /// The sheet as TOML; JSON sheets are converted.
+    text: String,
+}
+
+/// Reads and validates a dropped `.toml` or `.json` sheet. JSON sheets use the
+/// same fields as TOML ones; base sheets are looked up in `dir`.
+pub fn prepare_import(path: &Path, dir: Option<PathBuf>) -> Result<Import, SheetError> {
+    let error = |location, message| SheetError {
+        path: path.to_path_buf(),
+        location,
+        message,
+    };
+
+    let text = std::fs::read_to_string(path).map_err(|e| error(None, e.to_string()))?;
+    let is_json = path.extension().is_some_and(|ext| ext == "json");
+    let text = if is_json {
+        let value: serde_json::Value = serde_json::from_str(&text).map_err(|e| {
+            let location = Location {
+                line: e.line(),
+                column: e.column(),
+            };
+            error(Some(location), e.to_string())
+        })?;
+        toml::to_string(&value).map_err(|e| error(None, e.to_string()))?
+    } else if path.extension().is_some_and(|ext| ext == "toml") {
+        text
+    } else {
+        return Err(error(None, String::from("not a .toml or .json sheet")));
+    };
+
+    let name = stem(path);
+    let bases = Bases {
+        dir: dir.as_deref(),
+    };
+    let (info, entries) = parse_sheet(&text, name, bases).map_err(|(location, message)| {
+        // Locations in converted JSON don't match the dropped file.
+        error(location.filter(|_| !is_json), message)
+    })?;
+
+    Ok(Import {
+        path: path.to_path_buf(),
+        info,
+        count: entries.len(),
+        text,
+    })
+}
+
+/// Copies a confirmed import into the sheets directory.
+pub fn import_sheet(import: &Import, dir: Option<PathBuf>) -> Result<PathBuf> {
+    write_new_sheet(dir, &stem(&import.path), &import.text)
+}
+
+/// Parses a sheet that isn't in the sheets directory yet, returning its name,
+/// or `default_name` if it has none, with its metadata and its shortcuts. It
+/// can only extend bundled sheets.
+pub fn parse_text(text: &str, default_name: &str) -> Result<(SheetInfo, Vec<KeyBinding>), String> {
+    match parse_sheet(text, default_name.to_string(), Bases::default()) {
+        Ok((info, entries)) => Ok((
+            info,
+            entries.into_iter().map(|entry| entry.binding).collect(),
+        )),
+        Err((Some(Location { line, column }), message)) => {
+            Err(format!("line {line}, column {column}: {message}"))
+        }
+        Err((None, message)) => Err(message),
+    }
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Opens a sheet in the user's default editor through the OpenURI portal.
+#[cfg(feature = "dbus")]
+pub async fn open_sheet(path: PathBuf) -> Result<()> {
+    let file =
+        std::fs::File::open(&path).with_context(|| format!("failed to open {}", path.display()))?;
+
+    OpenFileRequest::default()
+        .ask(false)
+        .send_file(&file.as_fd())
+        .await
+        .context("open URI portal is not available")?
+        .response()
+        .with_context(|| format!("failed to open {} in an editor", path.display()))
+}
+
+#[cfg(not(feature = "dbus"))]
+pub async fn open_sheet(_path: PathBuf) -> Result<()> {
+    Err(crate::utils::no_dbus())
+}
+
+/// Loads every `*.toml` sheet, returning the shortcuts of the valid ones and an
+/// error for each sheet that failed to parse.
+pub fn load_sheets(dir: Option<PathBuf>) -> (Vec<KeyBinding>, Vec<SheetError>) {
+    let (sheets, errors) = read_sheets(dir);
+    let bindings = sheets
+        .into_iter()
+        .flat_map(|(_, entries)| entries)
+        .map(|entry| entry.binding)
+        .collect();
+
+    (bindings, errors)
+}
+
+/// Validates every sheet, reporting parse errors and key combinations bound
+/// more than once within a sheet. Different applications binding the same
+/// keys is normal and not reported.
+pub fn check_sheets(dir: Option<PathBuf>) -> Vec<SheetError> {
+    let (sheets, mut errors) = read_sheets(dir);
+
+    for (path, entries) in &sheets {
+        let mut first_seen: HashMap<BindingId, Location> = HashMap::new();
+        for entry in entries {
+            match first_seen.get(&entry.binding.id()) {
+                Some(other) => errors.push(SheetError {
+                    path: path.clone(),
+                    location: Some(entry.location),
+                    message: format!(
+                        "{} is already bound at line {}, column {}",
+                        entry.binding.display_label(),
+                        other.line,
+                        other.column
+                    ),
+                }),
+                None => {
+                    first_seen.insert(entry.binding.id(), entry.location);
+                }
+            }
+        }
+    }
+
+    errors
+}
+
+/// Runs `--check-sheets`, printing each problem and returning the exit code.
+pub fn check_sheets_cli(dir: Option<PathBuf>) -> i32 {
+    let errors = check_sheets(dir);
+    for error in &errors {
+        match error.location {
+            Some(Location { line, column }) => {
+                eprintln!(
+                    "{}:{line}:{column}: {}",
+                    error.path.display(),
+                    error.message
+                )
+            }
+            None => eprintln!("{}: {}", error.path.display(), error.message),
+        }
+    }
+
+    if errors.is_empty() {
+        println!("All sheets are valid");
+        0
+    } else {
+        1
+    }
+}
+
+fn read_sheets(dir: Option<PathBuf>) -> (Vec<(PathBuf, Vec<SheetEntry>)>, Vec<SheetError>) {
+    let mut sheets = Vec::new();
+    let mut errors = Vec::new();
+
+    let Some(dir) = dir else {
+        return (sheets, errors);
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return (sheets, errors);
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+
+    let bases = Bases {
+        dir: Some(dir.as_path()),
+    };
+    let mut loaded = Vec::new();
+    for path in paths {
+        match load_sheet(&path, bases) {
+            Ok((info, entries)) => loaded.push((path, info, entries)),
+            Err((location, message)) => {
+                log::warn!("Failed to load sheet {}: {}", path.display(), message);
+                errors.push(SheetError {
+                    path,
+                    location,
+                    message,
+                });
+            }
+        }
+    }
+
+    // A sheet extended by another one is listed through it
+    let extended: HashSet<String> = loaded
+        .iter()
+        .filter_map(|(_, info, _)| info.extends.clone())
+        .collect();
+    sheets.extend(
+        loaded
+            .into_iter()
+            .filter(|(path, _, _)| !extended.contains(&stem(path)))
+            .map(|(path, _, entries)| (path, entries)),
+    );
+
+    (sheets, errors)
+}
+
+type LoadError = (Option<Location>, String);
+
+fn load_sheet(path: &Path, bases: Bases<'_>) -> Result<(SheetInfo, Vec<SheetEntry>), LoadError> {
+    let text = std::fs::read_to_string(path).map_err(|e| (None, e.to_string()))?;
+    parse_sheet(&text, stem(path), bases)
+}
+
+/// Parses the text of a sheet, returning its name, or `default_name` if it has
+/// none, with its metadata and its entries merged with those of its base.
+fn parse_sheet(
+    text: &str,
+    default_name: String,
+    bases: Bases<'_>,
+) -> Result<(SheetInfo, Vec<SheetEntry>), LoadError> {
+    let mut chain = vec![default_name.clone()];
+    resolve_sheet(text, default_name, bases, &mut chain)
+}
+
+/// Parses a sheet whose bases are being resolved; `chain` lists the sheets
+/// extending it, to detect cycles.
+fn resolve_sheet(
+    text: &str,
+    default_name: String,
+    bases: Bases<'_>,
+    chain: &mut Vec<String>,
+) -> Result<(SheetInfo, Vec<SheetEntry>), LoadError> {
+    let at = |span: std::ops::Range<usize>| Some(Location::from_offset(text, span.start));
+
+    let sheet: SheetFile =
+        toml::from_str(text).map_err(|e| (e.span().and_then(at), e.message().to_string()))?;
+
+    let name = sheet.name.unwrap_or(default_name);
+    // The homepage is opened with a click, so only web links are accepted
+    if let Some(homepage) = &sheet.homepage {
+        let url = homepage.get_ref();
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err((
+                at(homepage.span()),
+                format!("homepage \"{url}\" is not a web link"),
+            ));
+        }
+    }
+    let parse_category = |id: &Spanned<String>| {
+        ShortcutCategory::from_id(id.get_ref()).ok_or_else(|| {
+            (
+                at(id.span()),
+                format!("unknown category \"{}\"", id.get_ref()),
+            )
+        })
+    };
+    let sheet_category = match &sheet.category {
+        Some(id) => parse_category(id)?,
+        None if sheet.device.is_some() => ShortcutCategory::SpecialKeys,
+        None => ShortcutCategory::Applications,
+    };
+
+    let entries = sheet
+        .shortcut
+        .into_iter()
+        .map(|shortcut| {
+            let (input, chord) = match (&shortcut.keys, &shortcut.chord) {
+                (Some(keys), None) => (keys, None),
+                (None, Some(chord)) => (chord, Some(chord)),
+                (Some(_), Some(chord)) => {
+                    return Err((at(chord.span()), String::from("use either keys or chord")));
+                }
+                (None, None) => {
+                    let message = format!("\"{}\": keys or chord missing", shortcut.description);
+                    return Err((None, message));
+                }
+            };
+            let location = Location::from_offset(text, input.span().start);
+            let (modifiers, key, pointer) = match chord {
+                Some(_) => (Modifiers::new(), None, None),
+                None => parse_keys(input.get_ref()).map_err(|e| (Some(location), e))?,
+            };
+            let chord = chord
+                .map(|chord| parse_chord(chord.get_ref()))
+                .transpose()
+                .map_err(|e| (Some(location), e))?;
+            let category = match &shortcut.category {
+                Some(id) => parse_category(id)?,
+                None => sheet_category,
+            };
+            Ok(SheetEntry {
+                binding: KeyBinding {
+                    modifiers,
+                    key,
+                    description: shortcut.description,
+                    command: String::new(),
+                    keybind_display: None,
+                    category,
+                    sources: vec![name.clone()],
+                    group: sheet.device.clone(),
+                    icon: None,
+                    command_missing: false,
+                    layer: None,
+                    tap: false,
+                    pointer,
+                    chord,
+                    alternates: Vec::new(),
+                },
+                location,
+            })
+        })
+        .collect::<Result<_, LoadError>>()?;
+
+    let entries = match &sheet.extends {
+        Some(base) => {
+            let location = Location::from_offset(text, base.span().start);
+            let inherited = load_base(base.get_ref(), bases, chain)
+                .map_err(|message| (Some(location), message))?;
+            let removed = sheet
+                .remove
+                .iter()
+                .map(|keys| {
+                    let location = Location::from_offset(text, keys.span().start);
+                    parse_removal(keys.get_ref())
+                        .map(|id| (id, keys.get_ref().as_str(), location))
+                        .map_err(|e| (Some(location), e))
+                })
+                .collect::<Result<Vec<_>, LoadError>>()?;
+            merge_base(inherited, entries, removed, &name, location)?
+        }
+        None => match sheet.remove.first() {
+            Some(keys) => return Err((at(keys.span()), String::from("remove needs extends"))),
+            None => entries,
+        },
+    };
+
+    let info = SheetInfo {
+        name,
+        author: sheet.author,
+        version: sheet.version,
+        homepage: sheet.homepage.map(Spanned::into_inner),
+        extends: sheet.extends.map(Spanned::into_inner),
+    };
+    Ok((info, entries))
+}
+
+/// Resolves the base sheet `name` and its own bases.
+fn load_base(
+    name: &str,
+    bases: Bases<'_>,
+    chain: &mut Vec<String>,
+) -> Result<Vec<SheetEntry>, String> {
+    if chain.iter().any(|stem| stem == name) {
+        let mut cycle = chain.clone();
+        cycle.push(name.to_string());
+        return Err(format!("extends cycle: {}", cycle.join(" → ")));
+    }
+    // Names become file names, so keep them to plain names
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    let text = if valid { bases.text(name) } else { None };
+    let Some(text) = text else {
+        return Err(format!("base sheet \"{name}\" not found"));
+    };
+
+    chain.push(name.to_string());
+    let resolved = resolve_sheet(&text, name.to_string(), bases, chain);
+    chain.pop();
+
+    resolved
+        .map(|(_, entries)| entries)
+        .map_err(|(location, message)| match location {
+            Some(Location { line, column }) => {
+                format!("base sheet \"{name}\", line {line}, column {column}: {message}")
+            }
+            None => format!("base sheet \"{name}\": {message}"),
+        })
+}
+
+/// Merges a sheet's own entries into those of its base: removed combinations
+/// are dropped, entries with the combination of a base entry replace it in
+/// place and the others follow in the order of the sheet.
+fn merge_base(
+    inherited: Vec<SheetEntry>,
+    own: Vec<SheetEntry>,
+    removed: Vec<(BindingId, &str, Location)>,
+    name: &str,
+    location: Location,
+) -> Result<Vec<SheetEntry>, LoadError> {
+    // Inherited rows belong to this sheet and point at its `extends`
+    let mut entries: Vec<SheetEntry> = inherited
+        .into_iter()
+        .map(|mut entry| {
+            entry.binding.sources = vec![name.to_string()];
+            entry.location = location;
+            entry
+        })
+        .collect();
+
+    for (id, keys, location) in removed {
+        let count = entries.len();
+        entries.retain(|entry| entry.binding.id() != id);
+        if entries.len() == count {
+            return Err((
+                Some(location),
+                format!("\"{keys}\" is not in the base sheet"),
+            ));
+        }
+    }
+
+    for entry in own {
+        let id = entry.binding.id();
+        match entries
+            .iter_mut()
+            .find(|inherited| inherited.binding.id() == id)
+        {
+            Some(inherited) => *inherited = entry,
+            None => entries.push(entry),
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Reads a combination to remove, as keys or, failing that, as a chord.
+fn parse_removal(keys: &str) -> Result<BindingId, String> {
+    match parse_keys(keys) {
+        Ok((modifiers, key, pointer)) => Ok(BindingId {
+            modifiers,
+            key,
+            pointer,
+            chord: None,
+        }),
+        Err(_) => Ok(BindingId {
+            modifiers: Modifiers::new(),
+            key: None,
+            pointer: None,
+            chord: Some(parse_chord(keys)?),
+        }),
+    }
+}
+
+/// Parses a combination like `Super+Shift+Q`. Modifier names are case-insensitive;
+/// the key is an XKB keysym name, or a pointer input like `Mouse8`, `Pen1` or `ScrollUp`.
+fn parse_keys(keys: &str) -> Result<(Modifiers, Option<xkb::Keysym>, Option<Pointer>), String> {
+    let mut modifiers = Modifiers::new();
+    let mut key = None;
+    let mut pointer = None;
+
+    for part in keys.split('+').map(str::trim) {
+        if key.is_some() || pointer.is_some() {
+            return Err(format!("\"{keys}\": the key must come after the modifiers"));
+        }
+        match part.to_lowercase().as_str() {
+            "super" | "logo" | "meta" => modifiers.logo = true,
+            "ctrl" | "control" => modifiers.ctrl = true,
+            "alt" => modifiers.alt = true,
+            "altgr" | "iso_level3_shift" => modifiers.altgr = true,
+            "shift" => modifiers.shift = true,
+            "" => return Err(format!("\"{keys}\": empty key")),
+            lower => match parse_pointer(lower) {
+                Some(parsed) => pointer = Some(parsed),
+                None => {
+                    let mut keysym = xkb::keysym_from_name(part, xkb::KEYSYM_NO_FLAGS);
+                    if keysym.raw() == 0 {
+                        keysym = xkb::keysym_from_name(part, xkb::KEYSYM_CASE_INSENSITIVE);
+                    }
+                    if keysym.raw() == 0 {
+                        return Err(format!("\"{keys}\": unknown key \"{part}\""));
+                    }
+                    key = Some(keysym);
+                }
+            },
+        }
+    }
+
+    Ok((modifiers, key, pointer))
+}
+
+/// Normalizes a chord like `Guide+A` to `Guide + A`; the buttons are not checked.
+fn parse_chord(chord: &str) -> Result<String, String> {
+    let buttons: Vec<&str> = chord.split('+').map(str::trim).collect();
+    if buttons.iter().any(|button| button.is_empty()) {
+        return Err(format!("\"{chord}\": empty button"));
+    }
+    Ok(buttons.join(" + "))
+}
+
+/// Reads a lowercased pointer input: `scroll`, `scrollup` and the other
+/// directions, `mouse<n>`/`button<n>`, `pen<n>` or `pad<n>`.
+fn parse_pointer(name: &str) -> Option<Pointer> {
+    match name {
+        "scroll" => return Some(Pointer::Scroll),
+        "scrollup" => return Some(Pointer::ScrollUp),
+        "scrolldown" => return Some(Pointer::ScrollDown),
+        "scrollleft" => return Some(Pointer::ScrollLeft),
+        "scrollright" => return Some(Pointer::ScrollRight),
+        _ => {}
+    }
+    let buttons: [(&str, fn(u8) -> Pointer); 4] = [
+        ("mouse", Pointer::Button),
+        ("button", Pointer::Button),
+        ("pen", Pointer::Pen),
+        ("pad", Pointer::Pad),
+    ];
+    buttons.into_iter().find_map(|(prefix, kind)| {
+        let button = name
+            .strip_prefix(prefix)?
+            .parse()
+            .ok()
+            .filter(|&button| button > 0)?;
+        Some(kind(button))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_keys_reads_modifiers_and_key() {
+        let (modifiers, key, _) = parse_keys("Ctrl + Shift+t").unwrap();
+        assert!(modifiers.ctrl && modifiers.shift && !modifiers.alt && !modifiers.logo);
+        assert_eq!(key, Some(xkb::Keysym::new(0x74)));
+
+        let (modifiers, key, _) = parse_keys("AltGr+KP_Enter").unwrap();
+        assert!(modifiers.altgr && !modifiers.alt);
+        assert_eq!(key, Some(xkb::Keysym::new(0xff8d)));
+
+        assert!(parse_keys("Ctrl+NotAKey").is_err());
+        assert!(parse_keys("t+Ctrl").is_err());
+    }
+
+    #[test]
+    fn parse_keys_reads_mouse_buttons_and_scrolling() {
+        let (modifiers, key, pointer) = parse_keys("Super+ScrollDown").unwrap();
+        assert!(modifiers.logo);
+        assert_eq!((key, pointer), (None, Some(Pointer::ScrollDown)));
+
+        let (_, key, pointer) = parse_keys("Ctrl+Mouse8").unwrap();
+        assert_eq!((key, pointer), (None, Some(Pointer::Button(8))));
+        assert_eq!(parse_keys("button2").unwrap().2, Some(Pointer::Button(2)));
+
+        assert!(parse_keys("Scroll+Super").is_err());
+        assert!(parse_keys("Mouse0").is_err());
+    }
+
+    #[test]
+    fn device_sheets_group_pen_and_pad_buttons() {
+        let text = r#"
+name = "Tablet"
+device = "Wacom Intuos M"
+
+[[shortcut]]
+keys = "Pen2"
+description = "Right click"
+
+[[shortcut]]
+keys = "Ctrl+Pad1"
+description = "Undo"
+"#;
+        let (_, entries) = parse_sheet(text, String::from("tablet"), Bases::default()).unwrap();
+        let bindings: Vec<&KeyBinding> = entries.iter().map(|entry| &entry.binding).collect();
+        assert_eq!(bindings[0].pointer, Some(Pointer::Pen(2)));
+        assert_eq!(bindings[1].display_label(), "Ctrl + Pad 1");
+        assert!(bindings.iter().all(|binding| {
+            binding.category == ShortcutCategory::SpecialKeys
+                && binding.group.as_deref() == Some("Wacom Intuos M")
+        }));
+    }
+
+    #[test]
+    fn chords_are_shown_as_written() {
+        let text = r#"
+name = "Steam Input"
+
+[[shortcut]]
+chord = "Guide+A"
+description = "Take a screenshot"
+"#;
+        let (_, entries) = parse_sheet(text, String::from("steam"), Bases::default()).unwrap();
+        let binding = &entries[0].binding;
+        assert_eq!(binding.display_label(), "Guide + A");
+        assert_eq!((binding.key, binding.pointer), (None, None));
+
+        let both = "[[shortcut]]\nkeys = \"Ctrl+A\"\nchord = \"A\"\ndescription = \"x\"\n";
+        assert!(parse_sheet(both, String::new(), Bases::default()).is_err());
+        let neither = "[[shortcut]]\ndescription = \"x\"\n";
+        assert!(parse_sheet(neither, String::new(), Bases::default()).is_err());
+        let empty = "[[shortcut]]\nchord = \"Guide+\"\ndescription = \"x\"\n";
+        assert!(parse_sheet(empty, String::new(), Bases::default()).is_err());
+    }
+
+    #[test]
+    fn metadata_is_read_and_homepages_must_be_web_links() {
+        let text = r#"
+name = "Firefox"
+author = "Jane Doe"
+version = "1.2"
+homepage = "https://example.org/sheets"
+"#;
+        let (info, entries) = parse_sheet(text, String::from("firefox"), Bases::default()).unwrap();
+        assert!(entries.is_empty());
+        assert_eq!(
+            info,
+            SheetInfo {
+                name: String::from("Firefox"),
+                author: Some(String::from("Jane Doe")),
+                version: Some(String::from("1.2")),
+                homepage: Some(String::from("https://example.org/sheets")),
+                extends: None,
+            }
+        );
+
+        let text = "homepage = \"file:///etc/passwd\"\n";
+        let (location, _) = parse_sheet(text, String::new(), Bases::default()).unwrap_err();
+        assert_eq!(
+            location,
+            Some(Location {
+                line: 1,
+                column: 12
+            })
+        );
+    }
+
+    #[test]
+    fn sheets_override_and_remove_entries_of_their_base() {
+        let dir = std::env::temp_dir().join(format!("keypeek-extends-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let base = r#"
+name = "Vim"
+
+[[shortcut]]
+keys = "Ctrl+S"
+description = "Save"
+
+[[shortcut]]
+keys = "Ctrl+Z"
+description = "Undo"
+
+[[shortcut]]
+keys = "Ctrl+Q"
+description = "Quit"
+"#;
+        let personal = r#"
+name = "My Vim"
+extends = "vim"
+remove = ["Ctrl+Q"]
+
+[[shortcut]]
+keys = "Ctrl+Y"
+description = "Redo"
+
+[[shortcut]]
+keys = "Ctrl+S"
+description = "Write the file"
+"#;
+        std::fs::write(dir.join("vim.toml"), base).unwrap();
+        std::fs::write(dir.join("my-vim.toml"), personal).unwrap();
+        let (bindings, errors) = load_sheets(Some(dir.clone()));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(errors.is_empty());
+        // The base is only listed through the sheet extending it
+        let rows: Vec<(&str, &str)> = bindings
+            .iter()
+            .map(|binding| (binding.description.as_str(), binding.sources[0].as_str()))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("Write the file", "My Vim"),
+                ("Undo", "My Vim"),
+                ("Redo", "My Vim")
+            ]
+        );
+    }
+
+    #[test]
+    fn check_only_reports_keys_bound_twice_in_one_sheet() {
+        let dir = std::env::temp_dir().join(format!("keypeek-check-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let sheet = |name: &str, extra: &str| {
+            format!(
+                "name = \"{name}\"\n\n[[shortcut]]\nkeys = \"Ctrl+S\"\ndescription = \"Save\"\n{extra}"
+            )
+        };
+        std::fs::write(dir.join("a.toml"), sheet("A", "")).unwrap();
+        let twice = "\n[[shortcut]]\nkeys = \"Ctrl+S\"\ndescription = \"Save all\"\n";
+        std::fs::write(dir.join("b.toml"), sheet("B", twice)).unwrap();
+        let errors = check_sheets(Some(dir.clone()));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, dir.join("b.toml"));
+        assert_eq!(errors[0].location.map(|location| location.line), Some(8));
+    }
+
+    #[test]
+    fn extends_cycles_and_unknown_bases_are_errors() {
+        let dir = std::env::temp_dir().join(format!("keypeek-cycle-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.toml"), "extends = \"b\"\n").unwrap();
+        std::fs::write(dir.join("b.toml"), "extends = \"a\"\n").unwrap();
+        let (bindings, errors) = load_sheets(Some(dir.clone()));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(bindings.is_empty());
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].message.ends_with("extends cycle: a → b → a"));
+
+        let unknown = "extends = \"../vim\"\n";
+        assert!(parse_sheet(unknown, String::new(), Bases::default()).is_err());
+        let orphan = "remove = [\"Ctrl+Q\"]\n";
+        assert!(parse_sheet(orphan, String::new(), Bases::default()).is_err());
+    }
+
+    #[test]
+    fn template_is_a_valid_sheet() {
+        let sheet: SheetFile = toml::from_str(TEMPLATE).unwrap();
+        assert_eq!(sheet.shortcut.len(), 2);
+        for shortcut in sheet.shortcut {
+            assert!(parse_keys(shortcut.keys.unwrap().get_ref()).is_ok());
+        }
+    }
+
+    #[test]
+    fn json_sheets_are_converted_to_toml() {
+        let path = std::env::temp_dir().join(format!("keypeek-import-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"{"name": "Firefox", "shortcut": [{"keys": "Ctrl+T", "description": "New tab"}]}"#,
+        )
+        .unwrap();
+        let import = prepare_import(&path, None);
+        std::fs::remove_file(&path).unwrap();
+
+        let import = import.unwrap();
+        assert_eq!((import.info.name.as_str(), import.count), ("Firefox", 1));
+        assert!(parse_sheet(&import.text, String::new(), Bases::default()).is_ok());
+    }
+
+    #[test]
+    fn location_counts_lines_and_columns_from_one() {
+        let text = "name = \"x\"\nkeys = \"é+\"";
+        let offset = text.find('+').unwrap();
+        assert_eq!(
+            Location::from_offset(text, offset),
+            Location {
+                line: 2,
+                column: 10
+            }
+        );
+    }
+}
@@ -1,4 +1,5 @@
-use anyhow::{Context, Result};
+use crate::error::ShortcutsError;
+use crate::layers::Layer;
 use cosmic_config::Config;
 use cosmic_settings_config::shortcuts as cs;
 use cosmic_settings_config::shortcuts::action::System as SystemAction;
@@ -7,14 +8,15 @@ use cosmic_settings_config::shortcuts::action::{
 };
 
 use cosmic_settings_config::shortcuts::Action;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::time::Instant;
 use xkbcommon::xkb;
 
 use std::env;
 
 /// Categories for organizing shortcuts
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
 pub enum ShortcutCategory {
     WindowManagement,
     WorkspaceNavigation,
@@ -23,6 +25,7 @@ pub enum ShortcutCategory {
     MediaControl,
     Display,
     Accessibility,
+    SpecialKeys,
     Applications,
     Custom,
     Other,
@@ -39,12 +42,38 @@ impl ShortcutCategory {
             ShortcutCategory::MediaControl => "Media Control",
             ShortcutCategory::Display => "Display & Brightness",
             ShortcutCategory::Accessibility => "Accessibility",
+            ShortcutCategory::SpecialKeys => "Hardware & Special Keys",
             ShortcutCategory::Applications => "Applications",
             ShortcutCategory::Custom => "Custom (User Defined)",
             ShortcutCategory::Other => "Other",
         }
     }
 
+    /// Returns a stable identifier for the category, used in config and exports
+    pub fn id(&self) -> &'static str {
+        match self {
+            ShortcutCategory::WindowManagement => "window-management",
+            ShortcutCategory::WorkspaceNavigation => "workspace-navigation",
+            ShortcutCategory::WindowMovement => "window-movement",
+            ShortcutCategory::SystemActions => "system",
+            ShortcutCategory::MediaControl => "media",
+            ShortcutCategory::Display => "display",
+            ShortcutCategory::Accessibility => "accessibility",
+            ShortcutCategory::SpecialKeys => "special-keys",
+            ShortcutCategory::Applications => "applications",
+            ShortcutCategory::Custom => "custom",
+            ShortcutCategory::Other => "other",
+        }
+    }
+
+    /// Looks up a category by its [`id`](Self::id)
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|category| category.id() == id)
+    }
+
     /// Returns all categories in a sensible display order
     pub fn all() -> &'static [ShortcutCategory] {
         &[
@@ -55,6 +84,7 @@ impl ShortcutCategory {
             ShortcutCategory::MediaControl,
             ShortcutCategory::Display,
             ShortcutCategory::Accessibility,
+            ShortcutCategory::SpecialKeys,
             ShortcutCategory::Applications,
             ShortcutCategory::Custom,
             ShortcutCategory::Other,
@@ -72,12 +102,13 @@ impl ShortcutCategory {
 //   repository's `KeyBinding` structure.
 //
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
 pub struct Modifiers {
     pub ctrl: bool,
     pub alt: bool,
     pub shift: bool,
-    pub logo: bool, // Super/Win key
+    pub logo: bool,  // Super/Win key
+    pub altgr: bool, // ISO_Level3_Shift
 }
 
 impl Modifiers {
@@ -87,8 +118,19 @@ impl Modifiers {
             alt: false,
             shift: false,
             logo: false,
+            altgr: false,
         }
     }
+
+    /// Orders sets of modifiers like they are displayed: fewer first, then
+    /// Super before Ctrl before Alt before AltGr before Shift.
+    pub fn sort_key(&self) -> (usize, [bool; 5]) {
+        let held = [self.logo, self.ctrl, self.alt, self.altgr, self.shift];
+        (
+            held.iter().filter(|held| **held).count(),
+            held.map(|held| !held),
+        )
+    }
 }
 
 impl fmt::Display for Modifiers {
@@ -103,6 +145,9 @@ impl fmt::Display for Modifiers {
         if self.alt {
             parts.push("Alt");
         }
+        if self.altgr {
+            parts.push("AltGr");
+        }
         if self.shift {
             parts.push("Shift");
         }
@@ -110,6 +155,51 @@ impl fmt::Display for Modifiers {
     }
 }
 
+/// Modifier chips restricting the list to bindings that use a modifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+pub enum ModifierFilter {
+    Super,
+    Ctrl,
+    Alt,
+    Shift,
+    /// Hardware keys such as volume or brightness (XF86 keysyms)
+    Fn,
+}
+
+impl ModifierFilter {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ModifierFilter::Super => "Super",
+            ModifierFilter::Ctrl => "Ctrl",
+            ModifierFilter::Alt => "Alt",
+            ModifierFilter::Shift => "Shift",
+            ModifierFilter::Fn => "Fn / XF86",
+        }
+    }
+
+    pub fn all() -> &'static [ModifierFilter] {
+        &[
+            ModifierFilter::Super,
+            ModifierFilter::Ctrl,
+            ModifierFilter::Alt,
+            ModifierFilter::Shift,
+            ModifierFilter::Fn,
+        ]
+    }
+
+    /// Whether the binding uses this modifier
+    pub fn matches(&self, binding: &KeyBinding) -> bool {
+        let m = &binding.modifiers;
+        match self {
+            ModifierFilter::Super => m.logo,
+            ModifierFilter::Ctrl => m.ctrl,
+            ModifierFilter::Alt => m.alt,
+            ModifierFilter::Shift => m.shift,
+            ModifierFilter::Fn => binding.is_xf86(),
+        }
+    }
+}
+
 /// Categorizes an Action into a ShortcutCategory
 pub fn categorize_action(action: &Action) -> ShortcutCategory {
     match action {
@@ -181,12 +271,12 @@ pub fn categorize_action(action: &Action) -> ShortcutCategory {
         Action::System(SystemAction::BrightnessDown)
         | Action::System(SystemAction::BrightnessUp)
         | Action::System(SystemAction::KeyboardBrightnessDown)
-        | Action::System(SystemAction::KeyboardBrightnessUp)
-        | Action::ZoomIn
-        | Action::ZoomOut => ShortcutCategory::Display,
+        | Action::System(SystemAction::KeyboardBrightnessUp) => ShortcutCategory::Display,
 
         // Accessibility
-        Action::System(SystemAction::ScreenReader) => ShortcutCategory::Accessibility,
+        Action::System(SystemAction::ScreenReader) | Action::ZoomIn | Action::ZoomOut => {
+            ShortcutCategory::Accessibility
+        }
 
         // Applications
         Action::System(SystemAction::AppLibrary)
@@ -206,18 +296,140 @@ pub fn categorize_action(action: &Action) -> ShortcutCategory {
     }
 }
 
+/// A mouse, pen or tablet button, or a scroll direction, bound in place of a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Pointer {
+    /// Numbered as by the X server: 1 is the left button, 8 and 9 the side buttons.
+    Button(u8),
+    /// Button on the barrel of a stylus, counted from the tip.
+    Pen(u8),
+    /// Button or macro key on a tablet pad or other extra-button device.
+    Pad(u8),
+    /// Scrolling either way, e.g. to switch workspaces.
+    Scroll,
+    ScrollUp,
+    ScrollDown,
+    ScrollLeft,
+    ScrollRight,
+}
+
+impl fmt::Display for Pointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pointer::Button(button) => write!(f, "Mouse {}", button),
+            Pointer::Pen(button) => write!(f, "Pen {}", button),
+            Pointer::Pad(button) => write!(f, "Pad {}", button),
+            Pointer::Scroll => write!(f, "Scroll ↑/↓"),
+            Pointer::ScrollUp => write!(f, "Scroll ↑"),
+            Pointer::ScrollDown => write!(f, "Scroll ↓"),
+            Pointer::ScrollLeft => write!(f, "Scroll ←"),
+            Pointer::ScrollRight => write!(f, "Scroll →"),
+        }
+    }
+}
+
 /// Representation used by the overlay renderer
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct KeyBinding {
     pub modifiers: Modifiers,
+    #[serde(with = "keysym_serde")]
     pub key: Option<xkb::Keysym>,
     pub description: String,
     /// Best-effort textual representation of the underlying action/command.
-    pub _command: String,
+    pub command: String,
     /// Display string for concatenated keybinds (when multiple bindings share same description)
     pub keybind_display: Option<String>,
     /// Category this shortcut belongs to
     pub category: ShortcutCategory,
+    /// Names of the sources that provide this binding (e.g. "COSMIC")
+    pub sources: Vec<String>,
+    /// Group within the category, e.g. "Browsers" for custom commands
+    pub group: Option<String>,
+    /// Icon name or image path shown next to the binding
+    pub icon: Option<String>,
+    /// Spawn command whose program isn't installed
+    pub command_missing: bool,
+    /// COSMIC layer the binding comes from; `None` for other sources.
+    pub layer: Option<Layer>,
+    /// Fires when the modifiers are pressed and released on their own, like
+    /// tapping Super to open the launcher.
+    pub tap: bool,
+    /// Mouse button or scroll direction pressed with the modifiers; `key` is
+    /// then `None`.
+    pub pointer: Option<Pointer>,
+    /// Non-keyboard input written as-is, e.g. the controller chord
+    /// "Guide + A"; takes the place of the modifiers and key.
+    pub chord: Option<String>,
+    /// Other key combinations of the same action, merged into this row.
+    pub alternates: Vec<BindingId>,
+}
+
+/// Stores keysyms by their raw value, e.g. in the shortcut cache.
+mod keysym_serde {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use xkbcommon::xkb;
+
+    pub fn serialize<S: Serializer>(key: &Option<xkb::Keysym>, s: S) -> Result<S::Ok, S::Error> {
+        key.map(|key| key.raw()).serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<xkb::Keysym>, D::Error> {
+        Ok(Option::<u32>::deserialize(d)?.map(xkb::Keysym::new))
+    }
+}
+
+/// Identifies a key combination independently of what it does or where it came from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+pub struct BindingId {
+    pub modifiers: Modifiers,
+    #[serde(with = "keysym_serde")]
+    pub key: Option<xkb::Keysym>,
+    pub pointer: Option<Pointer>,
+    pub chord: Option<String>,
+}
+
+/// Names custom shortcuts whose command matches `pattern`, a regular expression.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct SpawnGroup {
+    pub name: String,
+    pub pattern: String,
+}
+
+impl SpawnGroup {
+    pub fn new(name: &str, pattern: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+        }
+    }
+}
+
+/// How bindings that appear in more than one source are combined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum MergePolicy {
+    /// Show every binding, even if several sources list the same one.
+    KeepAll,
+    /// Merge bindings with the same keys and the same description.
+    #[default]
+    MergeIdentical,
+    /// Merge bindings with the same keys, keeping the first description seen.
+    MergeByKeys,
+}
+
+/// Which binding of an action comes first when its bindings are merged into
+/// one row.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize,
+)]
+pub enum PrimaryBinding {
+    /// Bindings the user added or changed before the system defaults.
+    #[default]
+    UserFirst,
+    /// The system defaults before the user's bindings.
+    DefaultFirst,
+    /// The binding with the fewest modifiers.
+    ShortestFirst,
 }
 
 impl fmt::Display for KeyBinding {
@@ -228,21 +440,194 @@ impl fmt::Display for KeyBinding {
         }
 
         // Otherwise, format the individual keybind
-        let mut parts = Vec::new();
-        let mod_str = self.modifiers.to_string();
-        if !mod_str.is_empty() {
-            parts.push(mod_str);
+        write!(f, "{}", self.keys_label())
+    }
+}
+
+impl KeyBinding {
+    /// Whether the key is a hardware key such as `XF86AudioMute`.
+    pub fn is_xf86(&self) -> bool {
+        self.key
+            .is_some_and(|key| key.name().is_some_and(|name| name.starts_with("XF86")))
+    }
+
+    /// The binding's own keys, e.g. "Super + q" or "Super (tap)", ignoring
+    /// `keybind_display`.
+    pub fn keys_label(&self) -> String {
+        if let Some(chord) = &self.chord {
+            chord.clone()
+        } else if self.tap {
+            format!("{} (tap)", self.modifiers)
+        } else if let Some(pointer) = self.pointer {
+            match self.modifiers.to_string() {
+                modifiers if modifiers.is_empty() => pointer.to_string(),
+                modifiers => format!("{} + {}", modifiers, pointer),
+            }
+        } else {
+            format_keybind(&self.modifiers, self.key)
         }
+    }
 
-        if let Some(keysym) = self.key {
-            let key_name = xkb::keysym_get_name(keysym);
-            // Clean up the key name if it follows KEY_ prefix convention
-            let key_name = key_name.strip_prefix("KEY_").unwrap_or(&key_name);
-            parts.push(key_name.to_string());
+    pub fn id(&self) -> BindingId {
+        BindingId {
+            modifiers: self.modifiers.clone(),
+            key: self.key,
+            pointer: self.pointer,
+            chord: self.chord.clone(),
         }
+    }
 
-        write!(f, "{}", parts.join(" + "))
+    /// Formats the binding for display without letting a failure take down the view.
+    ///
+    /// Keysym name resolution goes through libxkbcommon; if formatting panics for any
+    /// reason, the offending binding is logged and a placeholder is shown instead.
+    pub fn display_label(&self) -> String {
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| self.to_string())) {
+            Ok(label) => label,
+            Err(_) => {
+                log::error!("Failed to format binding: {:?}", self);
+                let mod_str = self.modifiers.to_string();
+                if mod_str.is_empty() {
+                    String::from("Unknown key")
+                } else {
+                    format!("{} + Unknown key", mod_str)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+impl KeyBinding {
+    /// A binding with only its keys and description set, for test fixtures to
+    /// fill in the rest with struct update syntax.
+    pub fn for_test(modifiers: Modifiers, key: Option<u32>, description: &str) -> Self {
+        Self {
+            modifiers,
+            key: key.map(xkb::Keysym::new),
+            description: description.to_string(),
+            command: String::new(),
+            keybind_display: None,
+            category: ShortcutCategory::Other,
+            sources: Vec::new(),
+            group: None,
+            icon: None,
+            command_missing: false,
+            layer: None,
+            tap: false,
+            pointer: None,
+            chord: None,
+            alternates: Vec::new(),
+        }
+    }
+}
+
+/// Formats modifiers and key as "Super + Shift + Q".
+pub fn format_keybind(modifiers: &Modifiers, key: Option<xkb::Keysym>) -> String {
+    let mut parts = Vec::new();
+    let mod_str = modifiers.to_string();
+    if !mod_str.is_empty() {
+        parts.push(mod_str);
     }
+
+    if let Some(keysym) = key {
+        parts.push(format_key(keysym));
+    }
+
+    parts.join(" + ")
+}
+
+/// First keysym of the range that maps directly to Unicode code points.
+const UNICODE_KEYSYM_OFFSET: u32 = 0x0100_0000;
+
+/// Returns a readable name for a single keysym.
+///
+/// Unicode keysyms (named like "U0431") are shown as the character they produce,
+/// `NoSymbol` as "No key", and keysyms without a name as their hex value.
+pub fn format_key(keysym: xkb::Keysym) -> String {
+    let raw = keysym.raw();
+    if raw == 0 {
+        return String::from("No key");
+    }
+
+    if (UNICODE_KEYSYM_OFFSET + 0x100..=UNICODE_KEYSYM_OFFSET + 0x10_FFFF).contains(&raw) {
+        if let Some(c) = char::from_u32(raw - UNICODE_KEYSYM_OFFSET).filter(|c| !c.is_control()) {
+            return c.to_string();
+        }
+    }
+
+    let key_name = xkb::keysym_get_name(keysym);
+    if key_name.is_empty() {
+        return format!("0x{:x}", raw);
+    }
+
+    if let Some(label) = special_key_label(&key_name) {
+        return label.to_string();
+    }
+    if let Some(hardware) = key_name.strip_prefix("XF86") {
+        return hardware.to_string();
+    }
+    if let Some(keypad) = key_name.strip_prefix("KP_") {
+        return format!("Keypad {}", keypad_label(keypad));
+    }
+
+    // Clean up the key name if it follows KEY_ prefix convention
+    key_name
+        .strip_prefix("KEY_")
+        .unwrap_or(&key_name)
+        .to_string()
+}
+
+/// Names keypad keys by the symbol printed on them, e.g. "Add" as "+".
+fn keypad_label(key: &str) -> String {
+    match key {
+        "Add" => String::from("+"),
+        "Subtract" => String::from("-"),
+        "Multiply" => String::from("*"),
+        "Divide" => String::from("/"),
+        "Decimal" | "Separator" => String::from("."),
+        "Equal" => String::from("="),
+        "Page_Up" | "Prior" => String::from("Page Up"),
+        "Page_Down" | "Next" => String::from("Page Down"),
+        other => other.replace('_', " "),
+    }
+}
+
+/// Friendly names for hardware and special keys, which are usually bound without
+/// modifiers and whose keysym names are hard to read.
+fn special_key_label(key_name: &str) -> Option<&'static str> {
+    let label = match key_name {
+        "Print" => "Print Screen",
+        "Scroll_Lock" => "Scroll Lock",
+        "Pause" => "Pause",
+        "Menu" => "Menu",
+        "ISO_Level3_Shift" => "AltGr",
+        "XF86AudioRaiseVolume" => "Volume Up",
+        "XF86AudioLowerVolume" => "Volume Down",
+        "XF86AudioMute" => "Mute",
+        "XF86AudioMicMute" => "Microphone Mute",
+        "XF86AudioPlay" => "Play",
+        "XF86AudioPause" => "Pause Media",
+        "XF86AudioStop" => "Stop Media",
+        "XF86AudioNext" => "Next Track",
+        "XF86AudioPrev" => "Previous Track",
+        "XF86MonBrightnessUp" => "Brightness Up",
+        "XF86MonBrightnessDown" => "Brightness Down",
+        "XF86KbdBrightnessUp" => "Keyboard Light Up",
+        "XF86KbdBrightnessDown" => "Keyboard Light Down",
+        "XF86TouchpadToggle" => "Touchpad Toggle",
+        "XF86Display" => "Display Switch",
+        "XF86PowerOff" => "Power",
+        "XF86Sleep" => "Sleep",
+        "XF86Calculator" => "Calculator",
+        "XF86Mail" => "Mail",
+        "XF86WWW" | "XF86HomePage" => "Browser",
+        "XF86Explorer" | "XF86MyComputer" => "Files",
+        "XF86Search" => "Search",
+        "XF86Tools" => "Settings",
+        _ => return None,
+    };
+    Some(label)
 }
 
 /// Reference:
@@ -367,12 +752,118 @@ pub fn localize_action(action: &Action) -> String {
     result.to_string()
 }
 
+/// Words in the debug names of accessibility actions.
+const ACCESSIBILITY_WORDS: &[&str] = &[
+    "zoom",
+    "magnif",
+    "contrast",
+    "screenreader",
+    "invert",
+    "colorfilter",
+    "accessib",
+];
+
+/// Whether the debug name of `action` suggests an accessibility feature.
+pub fn is_accessibility_action(action: &impl fmt::Debug) -> bool {
+    let name = format!("{:?}", action).to_lowercase();
+    ACCESSIBILITY_WORDS.iter().any(|word| name.contains(word))
+}
+
+/// First function key, F1; F2 to F12 follow it.
+const KEYSYM_F1: u32 = 0xffbe;
+
+/// Keys offered when looking for an unused combination: letters, digits and
+/// function keys.
+fn candidate_keys() -> impl Iterator<Item = xkb::Keysym> {
+    ('a'..='z')
+        .chain('0'..='9')
+        .map(|c| xkb::Keysym::new(c as u32))
+        .chain((0..12).map(|i| xkb::Keysym::new(KEYSYM_F1 + i)))
+}
+
+/// Keys that no binding uses together with exactly `modifiers`.
+pub fn free_keys(bindings: &[KeyBinding], modifiers: &Modifiers) -> Vec<xkb::Keysym> {
+    let bound: Vec<xkb::Keysym> = bindings
+        .iter()
+        .filter(|binding| binding.modifiers == *modifiers)
+        .filter_map(|binding| binding.key.map(xkb::keysym_to_lower))
+        .collect();
+
+    candidate_keys()
+        .filter(|key| !bound.contains(key))
+        .collect()
+}
+
+/// Source name of shortcuts read from the COSMIC settings config.
+pub const COSMIC_SOURCE: &str = "COSMIC";
+
+/// Groups custom (spawn) shortcuts by the first rule whose pattern matches their
+/// command. Invalid patterns are logged and skipped.
+pub fn assign_spawn_groups(bindings: &mut [KeyBinding], rules: &[SpawnGroup]) {
+    let rules: Vec<(regex::Regex, &str)> = rules
+        .iter()
+        .filter_map(|rule| match regex::Regex::new(&rule.pattern) {
+            Ok(re) => Some((re, rule.name.as_str())),
+            Err(e) => {
+                log::warn!("Ignoring spawn group {:?}: {}", rule.name, e);
+                None
+            }
+        })
+        .collect();
+
+    for binding in bindings
+        .iter_mut()
+        .filter(|binding| binding.category == ShortcutCategory::Custom)
+    {
+        binding.group = rules
+            .iter()
+            .find(|(re, _)| re.is_match(&binding.command))
+            .map(|(_, name)| name.to_string());
+    }
+}
+
+/// Merges rows that describe the same binding coming from different sources.
+///
+/// The first occurrence is kept in place and collects the source names of the
+/// duplicates, so the row can show everywhere the binding is defined.
+pub fn dedup_bindings(bindings: Vec<KeyBinding>, policy: MergePolicy) -> Vec<KeyBinding> {
+    if policy == MergePolicy::KeepAll {
+        return bindings;
+    }
+
+    let mut out: Vec<KeyBinding> = Vec::with_capacity(bindings.len());
+    let mut seen: HashMap<(BindingId, Option<String>), usize> = HashMap::new();
+
+    for binding in bindings {
+        let description = match policy {
+            MergePolicy::MergeIdentical => Some(binding.description.to_lowercase()),
+            _ => None,
+        };
+
+        match seen.get(&(binding.id(), description.clone())) {
+            Some(&index) => {
+                let kept = &mut out[index];
+                for source in binding.sources {
+                    if !kept.sources.contains(&source) {
+                        kept.sources.push(source);
+                    }
+                }
+            }
+            None => {
+                seen.insert((binding.id(), description), out.len());
+                out.push(binding);
+            }
+        }
+    }
+
+    out
+}
+
 /// Primary loader: reads cosmic shortcuts and converts them into KeyBinding list.
 ///
-/// Errors if the cosmic settings context cannot be opened or the shortcuts
-/// helper cannot be executed. The returned Vec may be empty if no shortcuts
-/// are configured.
-pub fn load_cosmic_shortcuts() -> Result<Vec<KeyBinding>> {
+/// Errors if the cosmic settings context or a shortcuts file can't be read.
+/// Files that can't be parsed are skipped, so the Vec may be empty.
+pub fn load_cosmic_shortcuts(primary: PrimaryBinding) -> Result<Vec<KeyBinding>, ShortcutsError> {
     let is_flatpak = crate::utils::is_flatpak();
     log::info!("is_flatpak: {}", is_flatpak);
 
@@ -394,10 +885,27 @@ pub fn load_cosmic_shortcuts() -> Result<Vec<KeyBinding>> {
     }
 
     // We call those here and convert their Shortcuts map into our KeyBinding list.
-    let ctx: Config = cs::context().context("failed to open cosmic settings config context")?;
+    let started = Instant::now();
+    if is_flatpak && !crate::layers::available() {
+        return Err(ShortcutsError::SandboxBlocked);
+    }
+    let ctx: Config =
+        cs::context().map_err(|e| ShortcutsError::ConfigUnavailable(e.to_string()))?;
+    // `cs::shortcuts` skips files it can't read, so check them first. Layers
+    // that can't be parsed are skipped too and reported by
+    // `layer_parse_failure`, so the other layer is still listed.
+    for key in ["defaults", "custom"] {
+        check_shortcuts_key(&ctx, key)?;
+    }
 
     // This returns the merged system + user shortcuts
     let cs_shortcuts = cs::shortcuts(&ctx);
+    // The user's layer is read on its own to tell their bindings from the
+    // system defaults
+    let user_bindings: HashSet<cs::Binding> = ctx
+        .get::<cs::Shortcuts>("custom")
+        .map(|custom| custom.0.into_keys().collect())
+        .unwrap_or_default();
 
     // This returns the user shortcuts only
 
@@ -406,7 +914,10 @@ pub fn load_cosmic_shortcuts() -> Result<Vec<KeyBinding>> {
         cs_shortcuts.0.iter().len()
     );
 
-    let mut out: Vec<KeyBinding> = Vec::new();
+    crate::timings::record("shortcut load", started);
+    let started = Instant::now();
+
+    let mut out: Vec<ReadBinding> = Vec::new();
 
     // Iterate by value over the merged shortcuts map (Binding, Action)
     for (binding, action) in cs_shortcuts.0.into_iter() {
@@ -428,12 +939,17 @@ pub fn load_cosmic_shortcuts() -> Result<Vec<KeyBinding>> {
         }
 
         // Description: prefer the binding description if present; otherwise synthesize
-        // a human-friendly label from the Action variant where possible.
-        let description = if let Some(desc) = &binding.description {
-            desc.clone()
-        } else {
-            localize_action(&action).to_string()
+        // a human-friendly label from the Action variant where possible. Spawn
+        // commands use the name of the application they start, if it's installed.
+        let app_name = match &action {
+            cs::Action::Spawn(cmd) => crate::desktop::lookup(cmd).and_then(|e| e.name),
+            _ => None,
         };
+        let description = binding
+            .description
+            .clone()
+            .or(app_name)
+            .unwrap_or_else(|| localize_action(&action));
 
         log::trace!(
             "binding: {:?}, action: {:?}, description: {}",
@@ -451,81 +967,668 @@ pub fn load_cosmic_shortcuts() -> Result<Vec<KeyBinding>> {
             _ => format!("{:?}", action),
         };
 
-        // Categorize the action
-        let category = categorize_action(&action);
+        // Flag custom shortcuts that would fail because their program is gone.
+        let command_missing = match &action {
+            cs::Action::Spawn(cmd) => crate::command::program(cmd)
+                .is_some_and(|program| !crate::utils::program_exists(program)),
+            _ => false,
+        };
 
-        out.push(KeyBinding {
-            modifiers: m,
-            key: keysym,
-            description,
-            _command: command,
-            keybind_display: None,
-            category,
+        // Categorize the action; keys pressed on their own, such as Print or the
+        // media keys, get a section of their own.
+        let category = if m == Modifiers::new() && keysym.is_some() {
+            ShortcutCategory::SpecialKeys
+        } else {
+            categorize_action(&action)
+        };
+
+        // Modifiers bound without a key or keycode fire when tapped alone
+        let tap = keysym.is_none() && binding.keycode.is_none() && m != Modifiers::new();
+
+        let layer = if user_bindings.contains(&binding) {
+            Layer::User
+        } else {
+            Layer::System
+        };
+        out.push(ReadBinding {
+            binding: KeyBinding {
+                modifiers: m,
+                key: keysym,
+                description,
+                command,
+                keybind_display: None,
+                category,
+                sources: vec![String::from(COSMIC_SOURCE)],
+                group: None,
+                icon: crate::icons::action_icon(&action),
+                command_missing,
+                layer: Some(layer),
+                tap,
+                // COSMIC's shortcuts config only binds keys
+                pointer: None,
+                chord: None,
+                alternates: Vec::new(),
+            },
+            action,
         });
     }
 
-    // Group keybindings by description and concatenate keybinds with slash separator
-    let mut grouped: HashMap<String, Vec<KeyBinding>> = HashMap::new();
-    for binding in out {
-        grouped
-            .entry(binding.description.clone())
-            .or_insert_with(Vec::new)
-            .push(binding);
+    let out = merge_by_action(out, primary);
+    crate::timings::record("conversion", started);
+
+    Ok(out)
+}
+
+/// A binding as read from COSMIC, before the bindings of each action are merged.
+#[derive(Debug, Clone)]
+struct ReadBinding {
+    action: Action,
+    binding: KeyBinding,
+}
+
+/// Shows bindings of the same action as one entry listing up to two key
+/// combinations, e.g. "Super + q / Alt + F4", sorted by description. Which
+/// comes first follows `primary`.
+///
+/// Bindings are grouped by their [`Action`], so translated or custom
+/// descriptions neither merge different actions nor split one. Different
+/// actions left with the same description get the action appended to tell
+/// them apart, e.g. "Terminal (kitty)".
+fn merge_by_action(out: Vec<ReadBinding>, primary: PrimaryBinding) -> Vec<KeyBinding> {
+    // Group keybindings by action, in the order they were read. Actions aren't
+    // hashable, but there are only a few hundred bindings.
+    let mut grouped: Vec<(Action, Vec<ReadBinding>)> = Vec::new();
+    for read in out {
+        match grouped
+            .iter_mut()
+            .find(|(action, _)| *action == read.action)
+        {
+            Some((_, bindings)) => bindings.push(read),
+            None => grouped.push((read.action.clone(), vec![read])),
+        }
     }
 
     // Create new keybindings with concatenated keybinds
     let mut out = Vec::new();
-    for (_description, bindings) in grouped {
+    for (_action, bindings) in grouped {
         if bindings.is_empty() {
             continue;
         }
 
-        // Use the first binding as a template
+        // COSMIC's map has no order, so sort by the policy and then list the
+        // simplest binding first
+        let mut bindings: Vec<KeyBinding> = bindings.into_iter().map(|read| read.binding).collect();
+        bindings.sort_by_key(|b| {
+            let user = b.layer == Some(Layer::User);
+            let origin = match primary {
+                PrimaryBinding::UserFirst => !user,
+                PrimaryBinding::DefaultFirst => user,
+                PrimaryBinding::ShortestFirst => false,
+            };
+            (origin, b.modifiers.sort_key(), b.key.map(|key| key.raw()))
+        });
+
+        // Use the first binding as a template; the row counts as the user's
+        // if any of its bindings is
         let mut merged_binding = bindings[0].clone();
+        if bindings.iter().any(|b| b.layer == Some(Layer::User)) {
+            merged_binding.layer = Some(Layer::User);
+        }
 
-        // If there are multiple bindings for this description, concatenate them
+        // If there are multiple bindings for this action, concatenate them
         // Limit to maximum 2 keybinds to prevent overlapping text
         if bindings.len() > 1 {
             let concatenated_keybind = bindings
                 .iter()
                 .take(2) // Only take first 2 keybinds
-                .map(|b| {
-                    // Temporarily format without keybind_display to get original format
-                    let mut parts = Vec::new();
-                    let mod_str = b.modifiers.to_string();
-                    if !mod_str.is_empty() {
-                        parts.push(mod_str);
-                    }
-                    if let Some(keysym) = b.key {
-                        let key_name = xkb::keysym_get_name(keysym);
-                        let key_name = key_name.strip_prefix("KEY_").unwrap_or(&key_name);
-                        parts.push(key_name.to_string());
-                    }
-                    parts.join(" + ")
-                })
+                // Format without keybind_display to get the original format
+                .map(KeyBinding::keys_label)
                 .collect::<Vec<_>>()
                 .join(" / ");
 
             merged_binding.keybind_display = Some(concatenated_keybind);
+            merged_binding.alternates = bindings[1..].iter().map(KeyBinding::id).collect();
         }
 
         out.push(merged_binding);
     }
 
-    // remove the ones whose binding starts with XF86
-    out.retain(|binding| {
-        !binding
-            .key
-            .is_some_and(|x| x.name().is_some_and(|x| x.starts_with("XF86")))
+    // Tell apart different actions left with the same description
+    let mut actions_per_description: HashMap<String, usize> = HashMap::new();
+    for binding in &out {
+        *actions_per_description
+            .entry(binding.description.clone())
+            .or_default() += 1;
+    }
+    for binding in &mut out {
+        if actions_per_description[&binding.description] > 1 && !binding.command.is_empty() {
+            binding.description = format!("{} ({})", binding.description, binding.command);
+        }
+    }
+
+    // sort by the description, then the action for a stable order
+    out.sort_by(|a, b| {
+        a.description
+            .cmp(&b.description)
+            .then_with(|| a.command.cmp(&b.command))
     });
+    out
+}
 
-    log::debug!(
-        "number of shortcuts after XF86 removal: {}",
-        out.iter().len()
-    );
+/// Reads the shortcuts stored under `key`, failing for files that exist but
+/// can't be read.
+fn check_shortcuts_key(ctx: &Config, key: &str) -> Result<(), ShortcutsError> {
+    match ctx.get::<cs::Shortcuts>(key) {
+        Err(cosmic_config::Error::GetKey(_, e) | cosmic_config::Error::Io(e))
+            if e.kind() == std::io::ErrorKind::PermissionDenied =>
+        {
+            Err(ShortcutsError::PermissionDenied(crate::layers::key_path(
+                key,
+            )))
+        }
+        // Missing files just mean nothing was changed
+        _ => Ok(()),
+    }
+}
 
-    // sort by the description
-    out.sort_by(|a, b| a.description.cmp(&b.description));
+/// The first COSMIC shortcut layer that exists but can't be parsed, e.g.
+/// because a newer COSMIC wrote an action this build doesn't know. The
+/// loader skips it and lists the other layer, so this is only a warning.
+pub fn layer_parse_failure() -> Option<ShortcutsError> {
+    let ctx = cs::context().ok()?;
+    ["defaults", "custom"].into_iter().find_map(|key| {
+        let detail = match ctx.get::<cs::Shortcuts>(key) {
+            Err(cosmic_config::Error::Ron(e)) => e.to_string(),
+            Err(cosmic_config::Error::RonSpanned(e)) => e.to_string(),
+            _ => return None,
+        };
+        Some(ShortcutsError::ParseFailure {
+            source: crate::layers::key_path(key),
+            detail,
+        })
+    })
+}
 
-    Ok(out)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zoom_and_screen_reader_are_accessibility() {
+        assert_eq!(
+            categorize_action(&Action::ZoomIn),
+            ShortcutCategory::Accessibility
+        );
+        assert_eq!(
+            categorize_action(&Action::System(SystemAction::ScreenReader)),
+            ShortcutCategory::Accessibility
+        );
+        assert!(is_accessibility_action(&"ToggleHighContrast"));
+        assert!(!is_accessibility_action(&Action::Close));
+    }
+
+    #[test]
+    fn format_key_uses_keysym_names() {
+        assert_eq!(format_key(xkb::Keysym::new(0x0071)), "q");
+        assert_eq!(format_key(xkb::Keysym::new(0xff0d)), "Return");
+    }
+
+    #[test]
+    fn format_key_maps_unicode_keysyms_to_characters() {
+        assert_eq!(format_key(xkb::Keysym::new(0x0100_0431)), "б");
+        assert_eq!(format_key(xkb::Keysym::new(0x0100_20ac)), "€");
+    }
+
+    #[test]
+    fn format_key_uses_friendly_names_for_special_keys() {
+        assert_eq!(format_key(xkb::Keysym::new(0xff61)), "Print Screen");
+        assert_eq!(format_key(xkb::Keysym::new(0x1008_ff13)), "Volume Up");
+        // XF86 keys without a friendly name just lose the prefix
+        assert_eq!(format_key(xkb::Keysym::new(0x1008_ff2d)), "ScreenSaver");
+    }
+
+    #[test]
+    fn format_key_names_keypad_and_level3_keys() {
+        assert_eq!(format_key(xkb::Keysym::new(0xffb1)), "Keypad 1");
+        assert_eq!(format_key(xkb::Keysym::new(0xff8d)), "Keypad Enter");
+        assert_eq!(format_key(xkb::Keysym::new(0xffab)), "Keypad +");
+        assert_eq!(format_key(xkb::Keysym::new(0xfe03)), "AltGr");
+    }
+
+    #[test]
+    fn format_key_handles_no_symbol() {
+        assert_eq!(format_key(xkb::Keysym::new(0)), "No key");
+    }
+
+    #[test]
+    fn format_keybind_joins_modifiers_and_key() {
+        let mut modifiers = Modifiers::new();
+        modifiers.logo = true;
+        modifiers.shift = true;
+        assert_eq!(
+            format_keybind(&modifiers, Some(xkb::Keysym::new(0x0100_0431))),
+            "Super + Shift + б"
+        );
+        assert_eq!(format_keybind(&modifiers, None), "Super + Shift");
+    }
+
+    #[test]
+    fn free_keys_skips_keys_bound_with_the_same_modifiers() {
+        let mut modifiers = Modifiers::new();
+        modifiers.logo = true;
+        let binding =
+            |modifiers: Modifiers, key: u32| KeyBinding::for_test(modifiers, Some(key), "");
+        // Super+T, Super+Shift+Q and Super+F1
+        let mut super_shift = modifiers.clone();
+        super_shift.shift = true;
+        let bindings = [
+            binding(modifiers.clone(), 0x54),
+            binding(super_shift, 0x71),
+            binding(modifiers.clone(), KEYSYM_F1),
+        ];
+
+        let free = free_keys(&bindings, &modifiers);
+        assert!(!free.contains(&xkb::Keysym::new(0x74)));
+        assert!(free.contains(&xkb::Keysym::new(0x71)));
+        assert!(!free.contains(&xkb::Keysym::new(KEYSYM_F1)));
+        assert_eq!(free.len(), 26 + 10 + 12 - 2);
+    }
+
+    #[test]
+    fn assign_spawn_groups_uses_first_matching_rule() {
+        let custom = |command: &str| KeyBinding {
+            command: command.to_string(),
+            category: ShortcutCategory::Custom,
+            ..KeyBinding::for_test(Modifiers::new(), None, "")
+        };
+        let mut bindings = vec![custom("firefox --new-window"), custom("htop")];
+        let rules = [
+            SpawnGroup::new("Browsers", "firefox|brave"),
+            SpawnGroup::new("Everything", "."),
+            SpawnGroup::new("Broken", "("),
+        ];
+
+        assign_spawn_groups(&mut bindings, &rules);
+        assert_eq!(bindings[0].group.as_deref(), Some("Browsers"));
+        assert_eq!(bindings[1].group.as_deref(), Some("Everything"));
+    }
+
+    fn binding(modifiers: &[&str], key: Option<u32>, description: &str) -> KeyBinding {
+        let modifiers = Modifiers {
+            ctrl: modifiers.contains(&"ctrl"),
+            alt: modifiers.contains(&"alt"),
+            shift: modifiers.contains(&"shift"),
+            logo: modifiers.contains(&"super"),
+            altgr: modifiers.contains(&"altgr"),
+        };
+        KeyBinding::for_test(modifiers, key, description)
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn merging_keeps_every_description_once(
+            bindings in proptest::collection::vec((0usize..4, 0u32..26, proptest::bool::ANY), 0..16)
+        ) {
+            let descriptions = ["Close window", "Maximize window", "Lock the screen", "Zoom in"];
+            let actions = [
+                Action::Close,
+                Action::Maximize,
+                Action::System(SystemAction::LockScreen),
+                Action::ZoomIn,
+            ];
+            let bindings: Vec<ReadBinding> = bindings
+                .into_iter()
+                .map(|(index, key, shift)| {
+                    let modifiers: &[&str] = if shift { &["super", "shift"] } else { &["super"] };
+                    let binding = binding(modifiers, Some(0x61 + key), descriptions[index]);
+                    read(actions[index].clone(), binding)
+                })
+                .collect();
+            let merged = merge_by_action(bindings.clone(), PrimaryBinding::ShortestFirst);
+            let bindings: Vec<KeyBinding> =
+                bindings.into_iter().map(|read| read.binding).collect();
+
+            for description in descriptions {
+                let inputs: Vec<&KeyBinding> =
+                    bindings.iter().filter(|b| b.description == description).collect();
+                let outputs: Vec<&KeyBinding> =
+                    merged.iter().filter(|b| b.description == description).collect();
+                if inputs.is_empty() {
+                    proptest::prop_assert!(outputs.is_empty());
+                    continue;
+                }
+                proptest::prop_assert_eq!(outputs.len(), 1);
+                // Every binding is listed, up to the first two.
+                let label = outputs[0].to_string();
+                proptest::prop_assert_eq!(label.split(" / ").count(), inputs.len().min(2));
+                let simplest = inputs
+                    .iter()
+                    .min_by_key(|b| (b.modifiers.sort_key(), b.key.map(|key| key.raw())))
+                    .unwrap();
+                proptest::prop_assert!(label.starts_with(&simplest.to_string()));
+            }
+            proptest::prop_assert!(merged.is_sorted_by(|a, b| a.description <= b.description));
+        }
+    }
+
+    #[test]
+    fn snapshot_modifier_order() {
+        let names = ["super", "ctrl", "alt", "altgr", "shift"];
+        let lines: Vec<String> = (1..1u32 << names.len())
+            .map(|mask| {
+                let held: Vec<&str> = (0..names.len())
+                    .filter(|bit| mask & (1 << bit) != 0)
+                    .map(|bit| names[bit])
+                    .collect();
+                binding(&held, None, "").modifiers.to_string()
+            })
+            .collect();
+        insta::assert_snapshot!("modifier_order", lines.join("\n"));
+    }
+
+    #[test]
+    fn snapshot_binding_display() {
+        let mut preformatted = binding(&["super"], Some(0x71), "");
+        preformatted.keybind_display = Some(String::from("Super + q / Alt + F4"));
+        let mut tap = binding(&["super"], None, "");
+        tap.tap = true;
+        let mut scroll = binding(&["super"], None, "");
+        scroll.pointer = Some(Pointer::Scroll);
+        let mut button = binding(&[], None, "");
+        button.pointer = Some(Pointer::Button(8));
+        let bindings = [
+            binding(&["super"], Some(0x71), ""),
+            binding(&["ctrl", "alt"], Some(0xffff), ""),
+            binding(&["super", "shift"], Some(0x0100_0431), ""),
+            binding(&["altgr"], Some(0x65), ""),
+            binding(&["ctrl"], Some(0xffab), ""),
+            binding(&[], Some(0x1008_ff13), ""),
+            binding(&[], Some(0xff61), ""),
+            binding(&[], Some(0x1008_ff2d), ""),
+            binding(&["super"], None, ""),
+            tap,
+            scroll,
+            button,
+            preformatted,
+        ];
+        let lines: Vec<String> = bindings.iter().map(KeyBinding::display_label).collect();
+        insta::assert_snapshot!("binding_display", lines.join("\n"));
+    }
+
+    #[test]
+    fn snapshot_merged_bindings() {
+        let lock = Action::System(SystemAction::LockScreen);
+        let merged = merge_by_action(
+            vec![
+                read(
+                    Action::Close,
+                    binding(&["super"], Some(0x71), "Close window"),
+                ),
+                read(lock, binding(&["super"], Some(0xff1b), "Lock the screen")),
+                read(
+                    Action::Close,
+                    binding(&["alt"], Some(0xffc1), "Close window"),
+                ),
+                read(
+                    Action::Close,
+                    binding(&["ctrl"], Some(0x77), "Close window"),
+                ),
+            ],
+            PrimaryBinding::ShortestFirst,
+        );
+        let lines: Vec<String> = merged
+            .iter()
+            .map(|binding| format!("{}: {}", binding.description, binding))
+            .collect();
+        insta::assert_snapshot!("merged_bindings", lines.join("\n"));
+
+        // Every other binding is kept, even past the two shown
+        let alternates: Vec<String> = merged[0]
+            .alternates
+            .iter()
+            .map(|id| format_keybind(&id.modifiers, id.key))
+            .collect();
+        assert_eq!(alternates, ["Ctrl + w", "Alt + F4"]);
+    }
+
+    fn read(action: Action, binding: KeyBinding) -> ReadBinding {
+        ReadBinding { action, binding }
+    }
+
+    fn spawn(command: &str, modifiers: &[&str], description: &str) -> ReadBinding {
+        let mut binding = binding(modifiers, Some(0x74), description);
+        binding.command = command.to_string();
+        read(Action::Spawn(command.to_string()), binding)
+    }
+
+    #[test]
+    fn distinct_actions_sharing_a_description_stay_apart() {
+        let merged = merge_by_action(
+            vec![
+                spawn("kitty", &["super"], "Terminal"),
+                spawn("foot", &["super", "shift"], "Terminal"),
+                spawn("foot", &["ctrl", "alt"], "Terminal"),
+            ],
+            PrimaryBinding::ShortestFirst,
+        );
+        let descriptions: Vec<&str> = merged.iter().map(|b| b.description.as_str()).collect();
+        assert_eq!(descriptions, ["Terminal (foot)", "Terminal (kitty)"]);
+        assert_eq!(merged[0].to_string(), "Super + Shift + t / Ctrl + Alt + t");
+    }
+
+    #[test]
+    fn one_action_described_differently_stays_one_row() {
+        // E.g. a default with a translated description and a user binding
+        // with its own
+        let merged = merge_by_action(
+            vec![
+                spawn("kitty", &["super"], "Terminal"),
+                spawn("kitty", &["super", "shift"], "Terminal öffnen"),
+            ],
+            PrimaryBinding::ShortestFirst,
+        );
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].description, "Terminal");
+        assert_eq!(merged[0].to_string(), "Super + t / Super + Shift + t");
+    }
+
+    #[test]
+    fn merged_displays_do_not_depend_on_read_order() {
+        let bindings = vec![
+            read(
+                Action::Close,
+                binding(&["alt"], Some(0xffc1), "Close window"),
+            ),
+            read(
+                Action::Close,
+                binding(&["super", "shift"], Some(0x71), "Close window"),
+            ),
+            read(
+                Action::Close,
+                binding(&["super"], Some(0x71), "Close window"),
+            ),
+        ];
+        let mut reversed = bindings.clone();
+        reversed.reverse();
+
+        let merged = merge_by_action(bindings, PrimaryBinding::ShortestFirst);
+        assert_eq!(merged[0].to_string(), "Super + q / Alt + F4");
+        let merged_reversed = merge_by_action(reversed, PrimaryBinding::ShortestFirst);
+        assert_eq!(merged_reversed[0].to_string(), merged[0].to_string());
+    }
+
+    #[test]
+    fn primary_binding_policy_orders_merged_rows() {
+        let mut user = read(
+            Action::Close,
+            binding(&["ctrl", "alt"], Some(0x71), "Close window"),
+        );
+        user.binding.layer = Some(Layer::User);
+        let default = read(
+            Action::Close,
+            binding(&["super"], Some(0x71), "Close window"),
+        );
+        let label =
+            |primary| merge_by_action(vec![default.clone(), user.clone()], primary)[0].to_string();
+
+        assert_eq!(
+            label(PrimaryBinding::UserFirst),
+            "Ctrl + Alt + q / Super + q"
+        );
+        assert_eq!(
+            label(PrimaryBinding::DefaultFirst),
+            "Super + q / Ctrl + Alt + q"
+        );
+        assert_eq!(
+            label(PrimaryBinding::ShortestFirst),
+            "Super + q / Ctrl + Alt + q"
+        );
+    }
+
+    #[test]
+    fn rows_with_a_user_binding_count_as_the_users() {
+        let mut user = read(
+            Action::Close,
+            binding(&["ctrl", "alt"], Some(0x71), "Close window"),
+        );
+        user.binding.layer = Some(Layer::User);
+        let mut default = read(
+            Action::Close,
+            binding(&["super"], Some(0x71), "Close window"),
+        );
+        default.binding.layer = Some(Layer::System);
+        let mut other = read(
+            Action::Maximize,
+            binding(&["super"], Some(0x6d), "Maximize window"),
+        );
+        other.binding.layer = Some(Layer::System);
+
+        let merged = merge_by_action(vec![default, user, other], PrimaryBinding::DefaultFirst);
+        assert_eq!(merged[0].layer, Some(Layer::User));
+        assert_eq!(merged[1].layer, Some(Layer::System));
+    }
+
+    #[test]
+    fn actions_with_unique_descriptions_keep_them() {
+        let merged = merge_by_action(
+            vec![
+                read(
+                    Action::Close,
+                    binding(&["super"], Some(0x71), "Close window"),
+                ),
+                read(
+                    Action::Maximize,
+                    binding(&["super"], Some(0x6d), "Maximize window"),
+                ),
+            ],
+            PrimaryBinding::ShortestFirst,
+        );
+        let descriptions: Vec<&str> = merged.iter().map(|b| b.description.as_str()).collect();
+        assert_eq!(descriptions, ["Close window", "Maximize window"]);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn snapshot_localize_action() {
+        let directions = [
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+            Direction::Up,
+        ];
+        let mut actions = vec![
+            Action::Close,
+            Action::Disable,
+            Action::Focus(FocusDirection::Down),
+            Action::Focus(FocusDirection::In),
+            Action::Focus(FocusDirection::Left),
+            Action::Focus(FocusDirection::Out),
+            Action::Focus(FocusDirection::Right),
+            Action::Focus(FocusDirection::Up),
+            Action::Workspace(3),
+            Action::LastWorkspace,
+            Action::Maximize,
+            Action::Fullscreen,
+            Action::Minimize,
+            Action::MoveToLastWorkspace,
+            Action::SendToLastWorkspace,
+            Action::MoveToNextOutput,
+            Action::SendToNextOutput,
+            Action::MoveToNextWorkspace,
+            Action::SendToNextWorkspace,
+            Action::MoveToPreviousWorkspace,
+            Action::SendToPreviousWorkspace,
+            Action::MoveToPreviousOutput,
+            Action::SendToPreviousOutput,
+            Action::MoveToWorkspace(2),
+            Action::SendToWorkspace(2),
+            Action::NextOutput,
+            Action::NextWorkspace,
+            Action::Orientation(Orientation::Horizontal),
+            Action::Orientation(Orientation::Vertical),
+            Action::PreviousOutput,
+            Action::PreviousWorkspace,
+            Action::Resizing(ResizeDirection::Inwards),
+            Action::Resizing(ResizeDirection::Outwards),
+            Action::SwapWindow,
+            Action::ToggleOrientation,
+            Action::ToggleStacking,
+            Action::ToggleSticky,
+            Action::ToggleTiling,
+            Action::ToggleWindowFloating,
+            Action::Debug,
+            Action::MigrateWorkspaceToNextOutput,
+            Action::MigrateWorkspaceToPreviousOutput,
+            Action::Terminate,
+            Action::ZoomIn,
+            Action::ZoomOut,
+            Action::Spawn(String::from("firefox")),
+        ];
+        for direction in directions {
+            actions.push(Action::Move(direction));
+            actions.push(Action::MoveToOutput(direction));
+            actions.push(Action::SendToOutput(direction));
+            actions.push(Action::SwitchOutput(direction));
+            actions.push(Action::MigrateWorkspaceToOutput(direction));
+        }
+        actions.extend(
+            [
+                SystemAction::AppLibrary,
+                SystemAction::BrightnessDown,
+                SystemAction::BrightnessUp,
+                SystemAction::InputSourceSwitch,
+                SystemAction::HomeFolder,
+                SystemAction::KeyboardBrightnessDown,
+                SystemAction::KeyboardBrightnessUp,
+                SystemAction::Launcher,
+                SystemAction::LogOut,
+                SystemAction::LockScreen,
+                SystemAction::Mute,
+                SystemAction::MuteMic,
+                SystemAction::PlayPause,
+                SystemAction::PlayNext,
+                SystemAction::PlayPrev,
+                SystemAction::PowerOff,
+                SystemAction::Screenshot,
+                SystemAction::Suspend,
+                SystemAction::ScreenReader,
+                SystemAction::Terminal,
+                SystemAction::TouchpadToggle,
+                SystemAction::VolumeLower,
+                SystemAction::VolumeRaise,
+                SystemAction::WebBrowser,
+                SystemAction::WindowSwitcher,
+                SystemAction::WindowSwitcherPrevious,
+                SystemAction::WorkspaceOverview,
+                SystemAction::DisplayToggle,
+            ]
+            .map(Action::System),
+        );
+
+        let lines: Vec<String> = actions
+            .iter()
+            .map(|action| format!("{:?}: {}", action, localize_action(action)))
+            .collect();
+        insta::assert_snapshot!("localize_action", lines.join("\n"));
+    }
 }
@@ -0,0 +1,31 @@
+// SPDX-License-Identifier: MIT
+
+//! Runtime state restored when the panel restarts the applet, kept apart from
+//! the settings in [`crate::config`].
+
+use crate::app::PopupPage;
+use crate::shortcuts::ModifierFilter;
+use cosmic::cosmic_config::{self, CosmicConfigEntry, cosmic_config_derive::CosmicConfigEntry};
+
+#[derive(Debug, Clone, Default, CosmicConfigEntry, Eq, PartialEq)]
+#[version = 1]
+pub struct State {
+    /// Page the popup showed when it was last closed.
+    pub page: PopupPage,
+    pub search_query: String,
+    /// Ids of the categories shown; empty shows every category.
+    pub categories: Vec<String>,
+    pub modifier_filters: Vec<ModifierFilter>,
+}
+
+impl State {
+    /// Reads the state saved by the previous run, if any.
+    pub fn load(app_id: &str) -> (Option<cosmic_config::Config>, Self) {
+        let handler = cosmic_config::Config::new_state(app_id, Self::VERSION).ok();
+        let state = handler
+            .as_ref()
+            .map(|context| Self::get_entry(context).unwrap_or_else(|(_, state)| state))
+            .unwrap_or_default();
+        (handler, state)
+    }
+}
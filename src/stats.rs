@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: MIT
+
+//! Statistics about the loaded shortcuts, shown on the statistics page.
+
+use crate::shortcuts::{COSMIC_SOURCE, KeyBinding, Modifiers, ShortcutCategory};
+use std::collections::HashMap;
+use xkbcommon::xkb;
+
+/// Counts computed from a list of shortcuts. Lists are sorted with the largest
+/// count first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub total: usize,
+    pub per_category: Vec<(ShortcutCategory, usize)>,
+    pub per_modifiers: Vec<(Modifiers, usize)>,
+    pub per_key: Vec<(xkb::Keysym, usize)>,
+    /// Custom (spawn) shortcuts and shortcuts from custom sheets.
+    pub custom: usize,
+    /// Everything else, i.e. the shortcuts COSMIC ships with.
+    pub default: usize,
+}
+
+impl Stats {
+    pub fn compute(shortcuts: &[KeyBinding]) -> Self {
+        let per_category = ShortcutCategory::all()
+            .iter()
+            .map(|category| {
+                let count = shortcuts.iter().filter(|s| s.category == *category).count();
+                (*category, count)
+            })
+            .filter(|(_, count)| *count > 0);
+
+        let mut per_modifiers: HashMap<Modifiers, usize> = HashMap::new();
+        let mut per_key: HashMap<xkb::Keysym, usize> = HashMap::new();
+        for shortcut in shortcuts {
+            *per_modifiers.entry(shortcut.modifiers.clone()).or_default() += 1;
+            if let Some(key) = shortcut.key {
+                *per_key.entry(xkb::keysym_to_lower(key)).or_default() += 1;
+            }
+        }
+
+        let custom = shortcuts
+            .iter()
+            .filter(|s| {
+                s.category == ShortcutCategory::Custom
+                    || !s.sources.iter().any(|source| source == COSMIC_SOURCE)
+            })
+            .count();
+
+        Self {
+            total: shortcuts.len(),
+            per_category: sorted(per_category, |_| 0),
+            per_modifiers: sorted(per_modifiers, |m| m.to_string()),
+            per_key: sorted(per_key, |key| key.raw()),
+            custom,
+            default: shortcuts.len() - custom,
+        }
+    }
+}
+
+/// Sorts by count, largest first, then by `tie_break`; the sort is stable, so
+/// entries that are still equal keep their input order.
+fn sorted<T, K: Ord>(
+    counts: impl IntoIterator<Item = (T, usize)>,
+    tie_break: impl Fn(&T) -> K,
+) -> Vec<(T, usize)> {
+    let mut counts: Vec<(T, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| {
+        b.1.cmp(&a.1)
+            .then_with(|| tie_break(&a.0).cmp(&tie_break(&b.0)))
+    });
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(logo: bool, key: u32, category: ShortcutCategory, source: &str) -> KeyBinding {
+        let modifiers = Modifiers {
+            logo,
+            ..Modifiers::new()
+        };
+        KeyBinding {
+            category,
+            sources: vec![source.to_string()],
+            ..KeyBinding::for_test(modifiers, Some(key), "")
+        }
+    }
+
+    #[test]
+    fn compute_counts_categories_modifiers_keys_and_origins() {
+        let shortcuts = [
+            binding(
+                true,
+                0x71,
+                ShortcutCategory::WindowManagement,
+                COSMIC_SOURCE,
+            ),
+            binding(
+                true,
+                0x51,
+                ShortcutCategory::WindowManagement,
+                COSMIC_SOURCE,
+            ),
+            binding(true, 0x74, ShortcutCategory::Custom, COSMIC_SOURCE),
+            binding(false, 0x74, ShortcutCategory::Applications, "Firefox"),
+        ];
+
+        let stats = Stats::compute(&shortcuts);
+        assert_eq!(stats.total, 4);
+        assert_eq!(
+            stats.per_category[0],
+            (ShortcutCategory::WindowManagement, 2)
+        );
+        assert_eq!(stats.per_modifiers[0].1, 3);
+        // "q" and "Q" count as the same key
+        assert!(stats.per_key.contains(&(xkb::Keysym::new(0x71), 2)));
+        assert_eq!((stats.custom, stats.default), (2, 2));
+    }
+}
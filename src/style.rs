@@ -0,0 +1,200 @@
+// SPDX-License-Identifier: MIT
+
+//! Container styles derived from the active COSMIC theme.
+//!
+//! Styles are closures over the theme, so they follow theme and accent color
+//! changes without any extra bookkeeping.
+
+use cosmic::iced::widget::container;
+use cosmic::iced::{Background, Border, Color};
+use cosmic::theme;
+
+/// How much larger the interface is than at COSMIC's standard density, from
+/// the theme's spacing, which follows the interface density setting. Pixel
+/// sizes that sit next to text are multiplied by it.
+pub fn interface_scale() -> f32 {
+    let spacing = cosmic::theme::active().cosmic().spacing;
+    let standard = cosmic::cosmic_theme::Spacing::default();
+    f32::from(spacing.space_s) / f32::from(standard.space_s.max(1))
+}
+
+/// A key drawn as a small rounded cap.
+pub fn keycap() -> theme::Container<'static> {
+    theme::Container::custom(|theme| {
+        let cosmic = theme.cosmic();
+        let component = &cosmic.background.component;
+
+        container::Style {
+            text_color: Some(component.on.into()),
+            background: Some(Background::Color(component.base.into())),
+            border: Border {
+                color: component.divider.into(),
+                width: 1.0,
+                radius: cosmic.corner_radii.radius_s.into(),
+            },
+            ..Default::default()
+        }
+    })
+}
+
+/// Color of a category: one of the theme's palette accents, which have light
+/// and dark variants, unless the user picked a color.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CategoryColor {
+    Palette(usize),
+    Custom(Color),
+}
+
+impl CategoryColor {
+    fn resolve(self, cosmic: &cosmic::cosmic_theme::Theme) -> Color {
+        match self {
+            Self::Custom(color) => color,
+            Self::Palette(index) => {
+                let palette: &cosmic::cosmic_theme::CosmicPaletteInner = cosmic.palette.as_ref();
+                let accents = [
+                    palette.accent_blue,
+                    palette.accent_green,
+                    palette.accent_orange,
+                    palette.accent_purple,
+                    palette.accent_red,
+                    palette.accent_indigo,
+                    palette.accent_yellow,
+                    palette.accent_pink,
+                    palette.accent_warm_grey,
+                ];
+                accents[index % accents.len()].into()
+            }
+        }
+    }
+}
+
+/// Parses a `#rrggbb` color.
+pub fn parse_hex(text: &str) -> Option<Color> {
+    let hex = text.trim().strip_prefix('#')?;
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    Some(Color::from_rgb8(
+        (value >> 16) as u8,
+        (value >> 8) as u8,
+        value as u8,
+    ))
+}
+
+/// A small bar in the color of a category, shown next to its heading.
+pub fn category_marker(color: CategoryColor) -> theme::Container<'static> {
+    theme::Container::custom(move |theme| {
+        let cosmic = theme.cosmic();
+        container::Style {
+            background: Some(Background::Color(color.resolve(cosmic))),
+            border: Border {
+                radius: cosmic.corner_radii.radius_xs.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    })
+}
+
+/// A keycap outlined in the color of its category.
+pub fn category_keycap(color: CategoryColor) -> theme::Container<'static> {
+    theme::Container::custom(move |theme| {
+        let cosmic = theme.cosmic();
+        let component = &cosmic.background.component;
+        let color = color.resolve(cosmic);
+
+        container::Style {
+            text_color: Some(component.on.into()),
+            background: Some(Background::Color(Color { a: 0.12, ..color })),
+            border: Border {
+                color,
+                width: 1.0,
+                radius: cosmic.corner_radii.radius_s.into(),
+            },
+            ..Default::default()
+        }
+    })
+}
+
+/// A keycap tinted with the accent color by `level`, from 0.0 (unused) to 1.0
+/// (the most used key), for heatmaps.
+pub fn heat(level: f32) -> theme::Container<'static> {
+    theme::Container::custom(move |theme| {
+        let cosmic = theme.cosmic();
+        let component = &cosmic.background.component;
+        let accent: Color = cosmic.accent_color().into();
+
+        container::Style {
+            text_color: Some(component.on.into()),
+            background: Some(Background::Color(Color {
+                a: 0.1 + 0.8 * level.clamp(0.0, 1.0),
+                ..accent
+            })),
+            border: Border {
+                color: component.divider.into(),
+                width: 1.0,
+                radius: cosmic.corner_radii.radius_s.into(),
+            },
+            ..Default::default()
+        }
+    })
+}
+
+/// A row in the shortcut list: odd rows are striped, and hovered and selected rows
+/// are highlighted, the latter with the accent color.
+pub fn row(striped: bool, hovered: bool, selected: bool) -> theme::Container<'static> {
+    theme::Container::custom(move |theme| {
+        let cosmic = theme.cosmic();
+        let component = &cosmic.background.component;
+        let radius = cosmic.corner_radii.radius_s.into();
+
+        if selected {
+            let accent: Color = cosmic.accent_color().into();
+            return container::Style {
+                background: Some(Background::Color(Color { a: 0.15, ..accent })),
+                border: Border {
+                    color: accent,
+                    width: 1.0,
+                    radius,
+                },
+                ..Default::default()
+            };
+        }
+
+        let background = if hovered {
+            Some(Background::Color(component.hover.into()))
+        } else if striped {
+            let base: Color = component.base.into();
+            Some(Background::Color(Color {
+                a: base.a * 0.5,
+                ..base
+            }))
+        } else {
+            None
+        };
+
+        container::Style {
+            background,
+            border: Border {
+                radius,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hex_reads_rgb_colors() {
+        assert_eq!(parse_hex("#ff8000"), Some(Color::from_rgb8(255, 128, 0)));
+        assert_eq!(parse_hex(" #FF8000 "), Some(Color::from_rgb8(255, 128, 0)));
+        assert_eq!(parse_hex("ff8000"), None);
+        assert_eq!(parse_hex("#f80"), None);
+        assert_eq!(parse_hex("#gg8000"), None);
+    }
+}
@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: MIT
+
+//! How long the stages of startup take, to guide performance work.
+//!
+//! Durations are only kept when built with the `timings` feature; otherwise
+//! [`record`] does nothing. They are shown on the about page and printed by
+//! `--timings`.
+
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// When the process started, or rather when [`start`] was first called.
+static START: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+/// Last duration of each stage, in the order the stages first ran.
+static STAGES: Mutex<Vec<(&'static str, Duration)>> = Mutex::new(Vec::new());
+
+/// Marks the start of the process; call first thing in `main`.
+pub fn start() {
+    LazyLock::force(&START);
+}
+
+/// Records that `stage` ran from `since` until now.
+pub fn record(stage: &'static str, since: Instant) {
+    if cfg!(feature = "timings") {
+        set(stage, since.elapsed());
+    }
+}
+
+/// Records the time from process start until now as `stage`, the first time
+/// it is called for that stage.
+pub fn record_once(stage: &'static str) {
+    if cfg!(feature = "timings") && !snapshot().iter().any(|(name, _)| *name == stage) {
+        set(stage, START.elapsed());
+    }
+}
+
+fn set(stage: &'static str, duration: Duration) {
+    let mut stages = STAGES.lock().unwrap_or_else(|e| e.into_inner());
+    match stages.iter_mut().find(|(name, _)| *name == stage) {
+        Some(entry) => entry.1 = duration,
+        None => stages.push((stage, duration)),
+    }
+}
+
+pub fn snapshot() -> Vec<(&'static str, Duration)> {
+    STAGES.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// One line per stage, e.g. "shortcut load   12.3 ms".
+pub fn report() -> String {
+    let stages = snapshot();
+    let width = stages.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    stages
+        .iter()
+        .map(|(name, duration)| {
+            format!(
+                "{name:<width$}  {:>8.1} ms\n",
+                duration.as_secs_f64() * 1000.0
+            )
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "timings"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_keeps_the_latest_duration_per_stage() {
+        record("test stage", Instant::now());
+        record("test stage", Instant::now());
+        let count = snapshot()
+            .iter()
+            .filter(|(name, _)| *name == "test stage")
+            .count();
+        assert_eq!(count, 1);
+        assert!(report().contains("test stage"));
+    }
+}
@@ -0,0 +1,60 @@
+// SPDX-License-Identifier: MIT
+
+//! Local usage statistics: how often the list is searched, categories and
+//! shortcuts are looked at and features are used. They are only recorded
+//! with `usage_stats` on, are kept in the state directory next to
+//! [`crate::state`], and never leave the machine.
+
+use cosmic::cosmic_config::{self, CosmicConfigEntry, cosmic_config_derive::CosmicConfigEntry};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, CosmicConfigEntry, Eq, PartialEq)]
+#[version = 1]
+pub struct Usage {
+    /// Searches started from an empty search field.
+    pub searches: u64,
+    /// Times each category was picked in the filter, by category id.
+    pub category_views: HashMap<String, u64>,
+    /// Times each shortcut's detail page was opened, by description.
+    pub shortcut_views: HashMap<String, u64>,
+    /// Times each page or action was used, by name.
+    pub feature_uses: HashMap<String, u64>,
+}
+
+impl Usage {
+    /// Reads the statistics recorded so far.
+    pub fn load(app_id: &str) -> (Option<cosmic_config::Config>, Self) {
+        let handler = cosmic_config::Config::new_state(app_id, Self::VERSION).ok();
+        let usage = handler
+            .as_ref()
+            .map(|context| Self::get_entry(context).unwrap_or_else(|(_, usage)| usage))
+            .unwrap_or_default();
+        (handler, usage)
+    }
+
+    /// The `n` most counted entries of `counts`, most counted first.
+    pub fn top(counts: &HashMap<String, u64>, n: usize) -> Vec<(&str, u64)> {
+        let mut top: Vec<(&str, u64)> = counts
+            .iter()
+            .map(|(name, count)| (name.as_str(), *count))
+            .collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        top.truncate(n);
+        top
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn top_sorts_by_count_then_name() {
+        let counts = HashMap::from([
+            (String::from("print"), 2),
+            (String::from("export"), 5),
+            (String::from("about"), 2),
+        ]);
+        assert_eq!(Usage::top(&counts, 2), vec![("export", 5), ("about", 2)]);
+    }
+}
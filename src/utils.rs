@@ -1,5 +1,57 @@
+use std::collections::HashMap;
 use std::env;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Error returned by portal and logind features in builds without the `dbus`
+/// feature.
+#[cfg(not(feature = "dbus"))]
+pub(crate) fn no_dbus() -> anyhow::Error {
+    anyhow::anyhow!("this build of KeyPeek has no D-Bus support")
+}
 
 pub(crate) fn is_flatpak() -> bool {
     env::var("FLATPAK_ID").is_ok()
 }
+
+/// How long a [`program_exists`] result is reused, so that reloads don't search
+/// `PATH`, or start a process on the host, for every binding.
+const PROGRAM_CACHE_TTL: Duration = Duration::from_secs(60);
+
+static PROGRAMS: LazyLock<Mutex<HashMap<String, (bool, Instant)>>> =
+    LazyLock::new(Default::default);
+
+/// Whether `program` can be run: an existing file for paths, otherwise a match in
+/// `PATH`. Under Flatpak both are looked up on the host instead of in the sandbox.
+pub(crate) fn program_exists(program: &str) -> bool {
+    let mut cache = PROGRAMS.lock().unwrap_or_else(|e| e.into_inner());
+    let fresh = cache
+        .get(program)
+        .filter(|(_, checked)| checked.elapsed() < PROGRAM_CACHE_TTL);
+    if let Some(&(exists, _)) = fresh {
+        return exists;
+    }
+
+    let exists = find_program(program);
+    cache.insert(program.to_string(), (exists, Instant::now()));
+    exists
+}
+
+fn find_program(program: &str) -> bool {
+    if is_flatpak() {
+        // `command -v` also checks paths, which the sandbox may not see
+        return std::process::Command::new("flatpak-spawn")
+            .args(["--host", "sh", "-c", "command -v \"$1\"", "sh", program])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+    }
+
+    if program.contains('/') {
+        return std::path::Path::new(program).is_file();
+    }
+
+    env::var_os("PATH")
+        .is_some_and(|path| env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: MIT
+
+//! Runs the loader against the COSMIC shortcut trees in `tests/fixtures` and
+//! checks what `--export-json` writes. Each fixture has a `data` tree for the
+//! system defaults and, optionally, a `config` tree for the user's changes.
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Loads the shortcuts of `fixture` and returns the exported bindings.
+fn load(fixture: &str) -> Vec<Value> {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(fixture);
+    let home = std::env::temp_dir().join(format!(
+        "keypeek-loader-{}-{}",
+        fixture.file_name().unwrap().to_string_lossy(),
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&home);
+    // The applet writes its own config too, so the user tree is copied.
+    let config = home.join("config");
+    std::fs::create_dir_all(&config).unwrap();
+    if fixture.join("config").exists() {
+        copy_dir(&fixture.join("config"), &config);
+    }
+
+    let out = home.join("shortcuts.json");
+    let status = Command::new(env!("CARGO_BIN_EXE_keypeek-applet"))
+        .arg("--export-json")
+        .arg(&out)
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", &config)
+        .env("XDG_CACHE_HOME", home.join("cache"))
+        .env("XDG_STATE_HOME", home.join("state"))
+        .env("XDG_DATA_HOME", home.join("data"))
+        .env("XDG_DATA_DIRS", fixture.join("data"))
+        .env("LC_ALL", "en_US.UTF-8")
+        .env_remove("FLATPAK_ID")
+        .status()
+        .unwrap();
+    assert!(
+        status.success(),
+        "--export-json failed for {}",
+        fixture.display()
+    );
+
+    let document: Value = serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    let _ = std::fs::remove_dir_all(&home);
+    document["bindings"].as_array().unwrap().clone()
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    for entry in std::fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target: PathBuf = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            std::fs::create_dir_all(&target).unwrap();
+            copy_dir(&entry.path(), &target);
+        } else {
+            std::fs::copy(entry.path(), &target).unwrap();
+        }
+    }
+}
+
+/// The binding for `modifiers` and `key`, if it was loaded.
+fn find<'a>(bindings: &'a [Value], modifiers: &[&str], key: Option<&str>) -> Option<&'a Value> {
+    bindings.iter().find(|binding| {
+        let held: Vec<&str> = binding["modifiers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m.as_str().unwrap())
+            .collect();
+        held == modifiers && binding["key"].as_str() == key
+    })
+}
+
+#[test]
+fn system_defaults_are_listed() {
+    let bindings = load("system-only");
+    assert_eq!(bindings.len(), 3);
+
+    let close = find(&bindings, &["super"], Some("q")).unwrap();
+    assert_eq!(close["description"], "Close window");
+    assert_eq!(close["category"], "window-management");
+
+    let log_out = find(&bindings, &["super", "shift"], Some("Escape")).unwrap();
+    assert_eq!(log_out["description"], "Log Out");
+    assert_eq!(log_out["category"], "system");
+}
+
+#[test]
+fn user_changes_override_and_disable_defaults() {
+    let bindings = load("user-overrides");
+
+    let rebound = find(&bindings, &["super"], Some("q")).unwrap();
+    assert_eq!(rebound["description"], "Maximize window");
+    assert!(find(&bindings, &["super"], Some("Escape")).is_none());
+
+    let terminal = find(&bindings, &["super"], Some("t")).unwrap();
+    assert_eq!(terminal["description"], "My terminal");
+    assert_eq!(terminal["category"], "custom");
+    assert_eq!(terminal["command"], "true");
+}
+
+#[test]
+fn exported_commands_are_redacted() {
+    let bindings = load("user-overrides");
+
+    let upload = find(&bindings, &["super"], Some("u")).unwrap();
+    assert_eq!(upload["command"], "upload --token=••••••");
+    let exported = serde_json::to_string(&bindings).unwrap();
+    assert!(!exported.contains("hunter2"));
+}
+
+#[test]
+fn keycodes_and_special_keys_are_kept() {
+    let bindings = load("special-keys");
+    assert_eq!(bindings.len(), 3);
+
+    let close = find(&bindings, &["super"], None).unwrap();
+    assert_eq!(close["description"], "Close window");
+
+    let volume = find(&bindings, &[], Some("XF86AudioRaiseVolume")).unwrap();
+    assert_eq!(volume["description"], "Increase audio output volume");
+    assert_eq!(volume["category"], "special-keys");
+    assert_eq!(
+        find(&bindings, &[], Some("Print")).unwrap()["category"],
+        "special-keys"
+    );
+}